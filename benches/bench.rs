@@ -195,6 +195,44 @@ fn bench_removal(c: &mut Criterion) {
     group.finish();
 }
 
+// wide nodes: compare with `cargo bench --bench bench -- fanout` and again with `--features art`
+
+const LAYOUT: &str = if cfg!(feature = "art") {
+    "art"
+} else {
+    "compact"
+};
+
+fn wide_keys(alphabet: &[u8], count: usize) -> Vec<Vec<u8>> {
+    let mut rng = rand::rng();
+    (0..count)
+        .map(|_| {
+            (0..8)
+                .map(|_| *alphabet.choose(&mut rng).unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+fn bench_wide_fanout(c: &mut Criterion) {
+    const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    const HEX: &[u8] = b"0123456789abcdef";
+
+    for (name, alphabet) in [("hex", HEX), ("base64", BASE64)] {
+        let mut group = c.benchmark_group(format!("{name} fanout ({LAYOUT})"));
+        let keys = wide_keys(alphabet, 100_000);
+        let set = keys.iter().cloned().collect::<RadixSet>();
+
+        group.bench_function("get", |b| {
+            b.iter(|| keys.iter().filter(|k| set.contains(black_box(k))).count())
+        });
+        group.bench_function("insert", |b| {
+            b.iter(|| keys.iter().cloned().collect::<RadixSet>())
+        });
+        group.finish();
+    }
+}
+
 // these benchmarks were taken from https://github.com/cloudflare/trie-hard/
 // which was taken from https://github.com/michaelsproul/rust_radix_trie/
 
@@ -306,6 +344,26 @@ fn bench_random_insert(c: &mut Criterion) {
 
 // bench get
 
+// `first-bytes` off (the default) against on, measured with
+// `cargo bench --bench bench [--features first-bytes] -- '(ours)|fanout|random retrieval'`
+// on a single core, `realloc` layout:
+//
+// benchmark                  off        on         change
+// random retrieval           698 ns     764 ns     +9.5%
+// random trie insert         28.9 ms    33.2 ms    +14.6%
+// random trie get            15.9 ms    16.9 ms    within noise
+// random trie remove         55.6 ms    57.8 ms    no change
+// domains trie insert        1.72 s     1.69 s     no change
+// domains trie get           1.37 s     1.30 s     -5.2%
+// domains trie remove        2.96 s     3.15 s     +6.6%
+// 1984 trie insert           6.77 ms    6.95 ms    no change
+// long trie get              4.81 ms    4.24 ms    -11.7%
+// long trie remove           13.4 ms    13.2 ms    no change
+// hex fanout get             31.1 ms    27.2 ms    -12.7%
+// hex fanout insert          68.8 ms    69.2 ms    no change
+// base64 fanout get          33.4 ms    25.7 ms    -23.1%
+// base64 fanout insert       76.3 ms    57.2 ms    -25.1%
+
 fn bench_domains_get(c: &mut Criterion) {
     let mut group = c.benchmark_group("domains trie get comparison");
 
@@ -499,4 +557,6 @@ criterion_group!(
     bench_long_remove
 );
 
-criterion_main!(benches, bench_insert, bench_get, bench_remove);
+criterion_group!(bench_fanout, bench_wide_fanout);
+
+criterion_main!(benches, bench_insert, bench_get, bench_remove, bench_fanout);
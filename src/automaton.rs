@@ -0,0 +1,589 @@
+//! Searching maps & sets with automata fed the keys byte by byte.
+//!
+//! An [`Automaton`] is driven over the node labels of a trie by
+//! [`GenericRadixMap::search`](crate::GenericRadixMap::search) and
+//! [`GenericRadixSet::search`](crate::GenericRadixSet::search). The state after each byte of a
+//! label is shared by every key below it, and a subtree is abandoned as soon as
+//! [`can_match`](Automaton::can_match) is `false`. The trait mirrors the one of the `fst` crate,
+//! so automata written for it port over unchanged.
+//!
+//! With the `regex-automata` feature, dense and sparse DFAs of the `regex-automata` crate are
+//! automata too, see [`regex`].
+//!
+//! # Examples
+//!
+//! ```
+//! use fast_radix_trie::StringRadixSet;
+//! use fast_radix_trie::automaton::{Automaton, Str, Subsequence};
+//!
+//! let set: StringRadixSet = ["bar", "baz", "foo", "foobar"].into_iter().collect();
+//!
+//! let mut found = Vec::new();
+//! let mut search = set.search(Subsequence::new("fb").union(Str::new("baz")));
+//! while let Some(key) = search.next() {
+//!     found.push(key.to_owned());
+//! }
+//! assert_eq!(found, ["baz", "foobar"]);
+//! ```
+use alloc::vec::Vec;
+
+use crate::walk::RowAutomaton;
+
+/// A state machine fed the bytes of a key one at a time, which decides whether it matches.
+pub trait Automaton {
+    /// The state between two bytes.
+    type State;
+
+    /// Returns the state before the first byte.
+    fn start(&self) -> Self::State;
+
+    /// Returns `true` if a key ending in `state` matches.
+    fn is_match(&self, state: &Self::State) -> bool;
+
+    /// Returns `false` if no key going through `state` can match, so the search can skip every
+    /// key below it.
+    ///
+    /// Defaults to `true`, which is always correct but never prunes.
+    fn can_match(&self, _state: &Self::State) -> bool {
+        true
+    }
+
+    /// Returns the state reached from `state` by `byte`.
+    fn accept(&self, state: &Self::State, byte: u8) -> Self::State;
+
+    /// Returns an automaton matching the keys that start with a key this one matches.
+    fn starts_with(self) -> StartsWith<Self>
+    where
+        Self: Sized,
+    {
+        StartsWith(self)
+    }
+
+    /// Returns an automaton matching the keys that this one or `other` matches.
+    fn union<B: Automaton>(self, other: B) -> Union<Self, B>
+    where
+        Self: Sized,
+    {
+        Union(self, other)
+    }
+
+    /// Returns an automaton matching the keys that both this one and `other` match.
+    fn intersection<B: Automaton>(self, other: B) -> Intersection<Self, B>
+    where
+        Self: Sized,
+    {
+        Intersection(self, other)
+    }
+
+    /// Returns an automaton matching the keys that this one doesn't.
+    fn complement(self) -> Complement<Self>
+    where
+        Self: Sized,
+    {
+        Complement(self)
+    }
+}
+
+impl<A: Automaton + ?Sized> Automaton for &A {
+    type State = A::State;
+    fn start(&self) -> A::State {
+        (**self).start()
+    }
+    fn is_match(&self, state: &A::State) -> bool {
+        (**self).is_match(state)
+    }
+    fn can_match(&self, state: &A::State) -> bool {
+        (**self).can_match(state)
+    }
+    fn accept(&self, state: &A::State, byte: u8) -> A::State {
+        (**self).accept(state, byte)
+    }
+}
+
+/// Matches every key.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlwaysMatch;
+
+impl Automaton for AlwaysMatch {
+    type State = ();
+    fn start(&self) {}
+    fn is_match(&self, _state: &()) -> bool {
+        true
+    }
+    fn accept(&self, _state: &(), _byte: u8) {}
+}
+
+/// Matches a single key.
+///
+/// Combined with [`starts_with`](Automaton::starts_with), it matches the keys with a given
+/// prefix.
+#[derive(Debug, Clone)]
+pub struct Str<'a>(&'a [u8]);
+
+impl<'a> Str<'a> {
+    /// Makes an automaton matching `key`.
+    pub fn new<K: AsRef<[u8]> + ?Sized>(key: &'a K) -> Self {
+        Str(key.as_ref())
+    }
+}
+
+impl Automaton for Str<'_> {
+    /// the number of bytes matched so far, or `None` on a mismatch
+    type State = Option<usize>;
+    fn start(&self) -> Option<usize> {
+        Some(0)
+    }
+    fn is_match(&self, state: &Option<usize>) -> bool {
+        *state == Some(self.0.len())
+    }
+    fn can_match(&self, state: &Option<usize>) -> bool {
+        state.is_some()
+    }
+    fn accept(&self, state: &Option<usize>, byte: u8) -> Option<usize> {
+        state
+            .filter(|&i| self.0.get(i) == Some(&byte))
+            .map(|i| i + 1)
+    }
+}
+
+/// Matches the keys containing the bytes of a needle in order, not necessarily contiguously.
+#[derive(Debug, Clone)]
+pub struct Subsequence<'a>(&'a [u8]);
+
+impl<'a> Subsequence<'a> {
+    /// Makes an automaton matching the keys that `needle` is a subsequence of.
+    pub fn new<K: AsRef<[u8]> + ?Sized>(needle: &'a K) -> Self {
+        Subsequence(needle.as_ref())
+    }
+}
+
+impl Automaton for Subsequence<'_> {
+    /// the number of bytes of the needle found so far
+    type State = usize;
+    fn start(&self) -> usize {
+        0
+    }
+    fn is_match(&self, state: &usize) -> bool {
+        *state == self.0.len()
+    }
+    fn accept(&self, &state: &usize, byte: u8) -> usize {
+        state + usize::from(self.0.get(state) == Some(&byte))
+    }
+}
+
+/// Matches the keys that start with a key the inner automaton matches.
+///
+/// This `struct` is created by [`Automaton::starts_with`].
+#[derive(Debug, Clone)]
+pub struct StartsWith<A>(A);
+
+/// The state of a [`StartsWith`] automaton.
+#[derive(Debug, Clone)]
+pub enum StartsWithState<A: Automaton> {
+    /// A prefix matched.
+    Done,
+    /// No prefix matched yet.
+    Running(A::State),
+}
+
+impl<A: Automaton> Automaton for StartsWith<A> {
+    type State = StartsWithState<A>;
+    fn start(&self) -> Self::State {
+        let state = self.0.start();
+        if self.0.is_match(&state) {
+            StartsWithState::Done
+        } else {
+            StartsWithState::Running(state)
+        }
+    }
+    fn is_match(&self, state: &Self::State) -> bool {
+        matches!(state, StartsWithState::Done)
+    }
+    fn can_match(&self, state: &Self::State) -> bool {
+        match state {
+            StartsWithState::Done => true,
+            StartsWithState::Running(state) => self.0.can_match(state),
+        }
+    }
+    fn accept(&self, state: &Self::State, byte: u8) -> Self::State {
+        match state {
+            StartsWithState::Done => StartsWithState::Done,
+            StartsWithState::Running(state) => {
+                let next = self.0.accept(state, byte);
+                if self.0.is_match(&next) {
+                    StartsWithState::Done
+                } else {
+                    StartsWithState::Running(next)
+                }
+            }
+        }
+    }
+}
+
+/// Matches the keys that either of two automata matches.
+///
+/// This `struct` is created by [`Automaton::union`].
+#[derive(Debug, Clone)]
+pub struct Union<A, B>(A, B);
+
+impl<A: Automaton, B: Automaton> Automaton for Union<A, B> {
+    type State = (A::State, B::State);
+    fn start(&self) -> Self::State {
+        (self.0.start(), self.1.start())
+    }
+    fn is_match(&self, (a, b): &Self::State) -> bool {
+        self.0.is_match(a) || self.1.is_match(b)
+    }
+    fn can_match(&self, (a, b): &Self::State) -> bool {
+        self.0.can_match(a) || self.1.can_match(b)
+    }
+    fn accept(&self, (a, b): &Self::State, byte: u8) -> Self::State {
+        (self.0.accept(a, byte), self.1.accept(b, byte))
+    }
+}
+
+/// Matches the keys that both of two automata match.
+///
+/// This `struct` is created by [`Automaton::intersection`].
+#[derive(Debug, Clone)]
+pub struct Intersection<A, B>(A, B);
+
+impl<A: Automaton, B: Automaton> Automaton for Intersection<A, B> {
+    type State = (A::State, B::State);
+    fn start(&self) -> Self::State {
+        (self.0.start(), self.1.start())
+    }
+    fn is_match(&self, (a, b): &Self::State) -> bool {
+        self.0.is_match(a) && self.1.is_match(b)
+    }
+    fn can_match(&self, (a, b): &Self::State) -> bool {
+        self.0.can_match(a) && self.1.can_match(b)
+    }
+    fn accept(&self, (a, b): &Self::State, byte: u8) -> Self::State {
+        (self.0.accept(a, byte), self.1.accept(b, byte))
+    }
+}
+
+/// Matches the keys that an automaton doesn't match.
+///
+/// This `struct` is created by [`Automaton::complement`].
+#[derive(Debug, Clone)]
+pub struct Complement<A>(A);
+
+impl<A: Automaton> Automaton for Complement<A> {
+    type State = A::State;
+    fn start(&self) -> A::State {
+        self.0.start()
+    }
+    fn is_match(&self, state: &A::State) -> bool {
+        !self.0.is_match(state)
+    }
+    // keeps the default `can_match`, since the keys below a dead state of the inner automaton
+    // all match
+    fn accept(&self, state: &A::State, byte: u8) -> A::State {
+        self.0.accept(state, byte)
+    }
+}
+
+/// Runs an [`Automaton`] a byte at a time over the walk of a tree.
+#[derive(Debug)]
+pub(crate) struct Bytewise<A>(pub(crate) A);
+
+impl<A: Automaton> RowAutomaton for Bytewise<A> {
+    type Cell = A::State;
+    type Match = ();
+
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn start(&self, rows: &mut Vec<A::State>) {
+        rows.push(self.0.start());
+    }
+
+    fn step(&self, rows: &mut Vec<A::State>, symbol: &[u8], _previous: Option<&[u8]>) -> bool {
+        let state = self.0.accept(&rows[rows.len() - 1], symbol[0]);
+        let can_match = self.0.can_match(&state);
+        rows.push(state);
+        can_match
+    }
+
+    fn accept(&self, row: &[A::State]) -> Option<()> {
+        self.0.is_match(&row[0]).then_some(())
+    }
+}
+
+/// [`Automaton`] support for the DFAs of the `regex-automata` crate, enabled by the
+/// `regex-automata` feature.
+///
+/// A DFA matches a key when a match ends right at the end of it, searching from its start.
+/// DFAs therefore need anchored start states, and should be built with [`MatchKind::All`] so
+/// that a shorter match doesn't stop the search before a longer one, as [`build`](regex::build) does.
+/// Quit states, if any, count as matching nothing.
+///
+/// # Examples
+///
+/// ```
+/// use fast_radix_trie::StringRadixMap;
+/// use fast_radix_trie::automaton::regex;
+///
+/// let map: StringRadixMap<_> =
+///     vec![("a1", 1), ("a12", 2), ("b3", 3), ("ab", 4)].into_iter().collect();
+/// let dfa = regex::build(r"[a-z]\d+").unwrap();
+///
+/// let mut found = Vec::new();
+/// let mut search = map.search(&dfa);
+/// while let Some((key, value)) = search.next() {
+///     found.push((key.to_owned(), *value));
+/// }
+/// assert_eq!(found, [("a1".to_owned(), 1), ("a12".to_owned(), 2), ("b3".to_owned(), 3)]);
+/// ```
+///
+/// [`MatchKind::All`]: regex_automata::MatchKind::All
+#[cfg(feature = "regex-automata")]
+pub mod regex {
+    use super::Automaton;
+    use regex_automata::{
+        Anchored, MatchKind,
+        dfa::{self, StartKind, dense, sparse},
+        util::{primitives::StateID, start},
+    };
+
+    /// Builds a dense DFA matching `pattern` against whole keys, with anchored start states and
+    /// [`MatchKind::All`] semantics.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pattern` is invalid or the DFA grows too large.
+    // the error is regex-automata's own, as returned by its builders
+    #[allow(clippy::result_large_err)]
+    pub fn build(pattern: &str) -> Result<dense::DFA<alloc::vec::Vec<u32>>, dense::BuildError> {
+        dense::Builder::new()
+            .configure(
+                dense::Config::new()
+                    .match_kind(MatchKind::All)
+                    .start_kind(StartKind::Anchored),
+            )
+            .build(pattern)
+    }
+
+    fn start_state<D: dfa::Automaton>(dfa: &D) -> StateID {
+        dfa.start_state(&start::Config::new().anchored(Anchored::Yes))
+            .expect("searching with a DFA requires anchored start states")
+    }
+
+    fn can_match<D: dfa::Automaton>(dfa: &D, state: StateID) -> bool {
+        !dfa.is_dead_state(state) && !dfa.is_quit_state(state)
+    }
+
+    fn is_match<D: dfa::Automaton>(dfa: &D, state: StateID) -> bool {
+        // matches are reported a byte late, so look past the end of the key
+        dfa.is_match_state(dfa.next_eoi_state(state))
+    }
+
+    /// # Panics
+    ///
+    /// [`start`](Automaton::start) panics if the DFA was built without anchored start states.
+    impl<T: AsRef<[u32]>> Automaton for dense::DFA<T> {
+        type State = StateID;
+        fn start(&self) -> StateID {
+            start_state(self)
+        }
+        fn is_match(&self, &state: &StateID) -> bool {
+            is_match(self, state)
+        }
+        fn can_match(&self, &state: &StateID) -> bool {
+            can_match(self, state)
+        }
+        fn accept(&self, &state: &StateID, byte: u8) -> StateID {
+            dfa::Automaton::next_state(self, state, byte)
+        }
+    }
+
+    /// # Panics
+    ///
+    /// [`start`](Automaton::start) panics if the DFA was built without anchored start states.
+    impl<T: AsRef<[u8]>> Automaton for sparse::DFA<T> {
+        type State = StateID;
+        fn start(&self) -> StateID {
+            start_state(self)
+        }
+        fn is_match(&self, &state: &StateID) -> bool {
+            is_match(self, state)
+        }
+        fn can_match(&self, &state: &StateID) -> bool {
+            can_match(self, state)
+        }
+        fn accept(&self, &state: &StateID, byte: u8) -> StateID {
+            dfa::Automaton::next_state(self, state, byte)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RadixMap, StringRadixSet};
+    use core::cell::Cell;
+
+    /// Counts the bytes fed to an automaton.
+    struct Counting<'a, A>(A, &'a Cell<usize>);
+
+    impl<A: Automaton> Automaton for Counting<'_, A> {
+        type State = A::State;
+        fn start(&self) -> A::State {
+            self.0.start()
+        }
+        fn is_match(&self, state: &A::State) -> bool {
+            self.0.is_match(state)
+        }
+        fn can_match(&self, state: &A::State) -> bool {
+            self.0.can_match(state)
+        }
+        fn accept(&self, state: &A::State, byte: u8) -> A::State {
+            self.1.set(self.1.get() + 1);
+            self.0.accept(state, byte)
+        }
+    }
+
+    fn run<A: Automaton>(automaton: &A, key: &[u8]) -> bool {
+        let state = key.iter().fold(automaton.start(), |state, &byte| {
+            automaton.accept(&state, byte)
+        });
+        automaton.is_match(&state)
+    }
+
+    fn search<A: Automaton>(map: &RadixMap<usize>, automaton: A) -> Vec<(Vec<u8>, usize)> {
+        let mut found = Vec::new();
+        let mut search = map.search(automaton);
+        while let Some((key, value)) = search.next() {
+            found.push((key.to_vec(), *value));
+        }
+        found
+    }
+
+    #[test]
+    fn search_matches_running_every_key() {
+        use rand::Rng;
+
+        let mut rng = rand::rng();
+        for _ in 0..if cfg!(miri) { 3 } else { 100 } {
+            let map: RadixMap<usize> = (0..rng.random_range(0..100))
+                .map(|i| {
+                    let key = (0..rng.random_range(0..6))
+                        .map(|_| b"abc"[rng.random_range(0..3)])
+                        .collect::<Vec<_>>();
+                    (key, i)
+                })
+                .collect();
+            let word = (0..rng.random_range(0..4))
+                .map(|_| b"abc"[rng.random_range(0..3)])
+                .collect::<Vec<_>>();
+            let other = (0..rng.random_range(0..4))
+                .map(|_| b"abc"[rng.random_range(0..3)])
+                .collect::<Vec<_>>();
+
+            macro_rules! check {
+                ($automaton:expr) => {{
+                    let automaton = $automaton;
+                    let expected = map
+                        .iter()
+                        .filter(|(k, _)| run(&automaton, k))
+                        .map(|(k, v)| (k, *v))
+                        .collect::<Vec<_>>();
+                    assert_eq!(search(&map, &automaton), expected, "{word:?} {other:?}");
+                }};
+            }
+            check!(AlwaysMatch);
+            check!(Str::new(&word));
+            check!(Str::new(&word).starts_with());
+            check!(Subsequence::new(&word));
+            check!(
+                Str::new(&word)
+                    .starts_with()
+                    .union(Subsequence::new(&other))
+            );
+            check!(
+                Str::new(&word)
+                    .starts_with()
+                    .intersection(Subsequence::new(&other))
+            );
+            check!(Str::new(&word).starts_with().complement());
+            check!(AlwaysMatch.complement());
+        }
+    }
+
+    #[test]
+    fn search_abandons_subtrees() {
+        let map: RadixMap<usize> = (0..1000)
+            .map(|i| (format!("{:03}/{}", i % 100, i), i))
+            .collect();
+        let fed = Cell::new(0);
+        let found = search(&map, Counting(Str::new("042/").starts_with(), &fed));
+        assert_eq!(found.len(), 10);
+        // the first byte of each sibling at most, then the matching subtree
+        assert!(fed.get() < 100, "{}", fed.get());
+    }
+
+    #[test]
+    fn set_search_lends_borrowed_keys() {
+        let set: StringRadixSet = ["", "a", "ab", "abc", "b"].into_iter().collect();
+        let mut search = set.search(Str::new("ab").complement());
+        let mut found = Vec::new();
+        while let Some(key) = search.next() {
+            found.push(key.to_owned());
+        }
+        assert_eq!(found, ["", "a", "abc", "b"]);
+    }
+
+    #[cfg(feature = "regex-automata")]
+    #[test]
+    fn regex_matches_whole_keys() {
+        use regex_automata::{
+            dfa::{StartKind, dense},
+            util::syntax,
+        };
+
+        let map: RadixMap<usize> = ["", "a", "ab", "abb", "b", "ba", "é", "éa"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, k)| (k, i))
+            .collect();
+        let keys = |found: Vec<(Vec<u8>, usize)>| {
+            found
+                .into_iter()
+                .map(|(k, _)| String::from_utf8(k).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        // shorter alternatives don't hide longer ones
+        let dfa = regex::build("a|ab").unwrap();
+        assert_eq!(keys(search(&map, &dfa)), ["a", "ab"]);
+        let dfa = regex::build("ab*|").unwrap();
+        assert_eq!(keys(search(&map, &dfa)), ["", "a", "ab", "abb"]);
+        let dfa = regex::build(r"\w").unwrap();
+        assert_eq!(keys(search(&map, &dfa)), ["a", "b", "é"]);
+        let sparse = regex::build(".a").unwrap().to_sparse().unwrap();
+        assert_eq!(keys(search(&map, &sparse)), ["ba", "éa"]);
+
+        let bytes = dense::Builder::new()
+            .syntax(syntax::Config::new().unicode(false).utf8(false))
+            .configure(dense::Config::new().start_kind(StartKind::Anchored))
+            .build(r"(?-u:\xC3).*")
+            .unwrap();
+        assert_eq!(keys(search(&map, &bytes)), ["é", "éa"]);
+    }
+
+    #[cfg(feature = "regex-automata")]
+    #[test]
+    #[should_panic(expected = "anchored start states")]
+    fn regex_needs_anchored_start_states() {
+        use regex_automata::dfa::{StartKind, dense};
+
+        let dfa = dense::Builder::new()
+            .configure(dense::Config::new().start_kind(StartKind::Unanchored))
+            .build("a")
+            .unwrap();
+        RadixMap::<()>::new().search(&dfa).next();
+    }
+}
@@ -0,0 +1,977 @@
+//! Bit granular keys, for tries over prefixes that don't end on a byte boundary.
+//!
+//! A [`BitRadixMap`] is a binary radix tree over packed bits: each node branches on a single bit
+//! and stores its label as the bytes spanning it, along with the bit offset of the label in its
+//! first byte and the label's length in bits. Labels line up with the key bits they stand for, so
+//! they are compared against keys a byte at a time. Keys are [`BitKey`]s, or borrowed [`BitStr`]s,
+//! both holding eight bits per byte, most significant bit first.
+//!
+//! See the [`ip`](crate::ip) module for maps keyed by IP prefixes.
+//!
+//! # Examples
+//!
+//! ```
+//! use fast_radix_trie::bits::{BitKey, BitRadixMap, BitStr};
+//!
+//! let mut map = BitRadixMap::new();
+//! // the first 12 bits of 0xABCD, and the first 4
+//! map.insert(&BitKey::from_prefix(&[0xAB, 0xCD], 12), "abc");
+//! map.insert(BitStr::new(&[0xA0], 4), "a");
+//!
+//! let key = BitKey::from_prefix(&[0xAB, 0xFF], 16);
+//! let (prefix, value) = map.get_longest_common_prefix(&key).unwrap();
+//! assert_eq!((prefix.len(), *value), (4, "a"));
+//! assert_eq!(prefix.to_packed(), [0xA0]);
+//! ```
+use crate::node_common::some;
+use alloc::{boxed::Box, vec::Vec};
+use core::{cmp::Ordering, fmt, hash, iter::FromIterator, mem};
+
+/// A borrowed sequence of bits, packed most significant bit first.
+///
+/// Bits in the last byte past the length are ignored.
+#[derive(Clone, Copy)]
+pub struct BitStr<'a> {
+    bytes: &'a [u8],
+    len: usize,
+}
+
+impl<'a> BitStr<'a> {
+    /// Makes a view of the first `bit_len` bits of `bytes`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` holds fewer than `bit_len` bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::bits::BitStr;
+    ///
+    /// let bits = BitStr::new(&[0b1101_1111], 3);
+    /// assert_eq!(bits.to_string(), "110");
+    /// ```
+    pub fn new(bytes: &'a [u8], bit_len: usize) -> Self {
+        assert!(
+            bit_len <= bytes.len() * 8,
+            "bit length {bit_len} exceeds the {} bits given",
+            bytes.len() * 8
+        );
+        BitStr {
+            bytes: &bytes[..bit_len.div_ceil(8)],
+            len: bit_len,
+        }
+    }
+
+    /// Returns the number of bits.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bit at `index`, if it's in bounds.
+    pub fn get(&self, index: usize) -> Option<bool> {
+        (index < self.len).then(|| self.bytes[index / 8] & (0x80 >> (index % 8)) != 0)
+    }
+
+    /// Returns the first `len` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is greater than the number of bits.
+    pub fn prefix(&self, len: usize) -> BitStr<'a> {
+        assert!(len <= self.len, "prefix of {len} bits exceeds {}", self.len);
+        BitStr::new(self.bytes, len)
+    }
+
+    /// Gets an iterator over the bits, first to last.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = bool> + '_ {
+        (0..self.len).map(|i| some!(self.get(i)))
+    }
+
+    /// Gets an iterator over the packed bytes, padding the last byte with zeros.
+    pub fn bytes(&self) -> impl ExactSizeIterator<Item = u8> + 'a {
+        let len = self.len;
+        self.bytes.iter().enumerate().map(move |(i, &byte)| {
+            let bits = len - i * 8;
+            if bits < 8 {
+                byte & !(0xFF >> bits)
+            } else {
+                byte
+            }
+        })
+    }
+
+    /// Packs the bits into bytes, most significant bit first, padding the last byte with zeros.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::bits::BitKey;
+    ///
+    /// let key: BitKey = [true, false, true].into_iter().collect();
+    /// assert_eq!(key.as_bit_str().to_packed(), [0b1010_0000]);
+    /// ```
+    pub fn to_packed(&self) -> Vec<u8> {
+        self.bytes().collect()
+    }
+
+    /// Copies the bits into a new [`BitKey`].
+    pub fn to_key(&self) -> BitKey {
+        BitKey {
+            bytes: self.to_packed(),
+            len: self.len,
+        }
+    }
+}
+
+impl PartialEq for BitStr<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.bytes().eq(other.bytes())
+    }
+}
+
+impl Eq for BitStr<'_> {}
+
+impl PartialOrd for BitStr<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BitStr<'_> {
+    /// Bits compare lexicographically, a prefix before the keys extending it.
+    fn cmp(&self, other: &Self) -> Ordering {
+        // padding bits are zero, so bytes only tie where one key is a prefix of the other
+        self.bytes()
+            .cmp(other.bytes())
+            .then(self.len.cmp(&other.len))
+    }
+}
+
+impl hash::Hash for BitStr<'_> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len);
+        self.bytes().for_each(|byte| state.write_u8(byte));
+    }
+}
+
+impl fmt::Debug for BitStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{self}\"")
+    }
+}
+
+impl fmt::Display for BitStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for bit in self.iter() {
+            f.write_str(if bit { "1" } else { "0" })?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> From<&'a BitKey> for BitStr<'a> {
+    fn from(key: &'a BitKey) -> Self {
+        key.as_bit_str()
+    }
+}
+
+/// An owned sequence of bits, packed most significant bit first.
+///
+/// See the [`bits`](crate::bits) module.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitKey {
+    // bits past `len` are always zero, which keeps the derived comparisons on bits
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitKey {
+    /// Makes a new empty key.
+    pub fn new() -> Self {
+        BitKey::default()
+    }
+
+    /// Makes a key from the first `bit_len` bits of `bytes`, most significant bit first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` holds fewer than `bit_len` bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::bits::BitKey;
+    ///
+    /// let key = BitKey::from_prefix(&[0b1100_0000], 3);
+    /// assert_eq!(key.to_string(), "110");
+    /// ```
+    pub fn from_prefix(bytes: &[u8], bit_len: usize) -> Self {
+        BitStr::new(bytes, bit_len).to_key()
+    }
+
+    /// Returns the number of bits.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends a bit.
+    pub fn push(&mut self, bit: bool) {
+        self.append(&[u8::from(bit) << 7 >> (self.len % 8)], 1);
+    }
+
+    /// Returns the bits as a [`BitStr`].
+    pub fn as_bit_str(&self) -> BitStr<'_> {
+        BitStr {
+            bytes: &self.bytes,
+            len: self.len,
+        }
+    }
+
+    /// Returns the packed bytes, with the last byte padded with zeros.
+    pub fn as_packed(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Appends `len` bits of `bytes`, which start at the bit position this key ends on.
+    fn append(&mut self, bytes: &[u8], len: usize) {
+        let offset = self.len % 8;
+        let bytes = &bytes[..(offset + len).div_ceil(8)];
+        match self.bytes.last_mut() {
+            Some(last) if offset != 0 => {
+                *last |= bytes[0] & (0xFF >> offset);
+                self.bytes.extend_from_slice(&bytes[1..]);
+            }
+            _ => self.bytes.extend_from_slice(bytes),
+        }
+        self.truncate(self.len + len);
+    }
+
+    /// Shortens the key to `len` bits, clearing the bits after them.
+    fn truncate(&mut self, len: usize) {
+        self.len = len;
+        self.bytes.truncate(len.div_ceil(8));
+        if let Some(last) = self.bytes.last_mut().filter(|_| len % 8 != 0) {
+            *last &= !(0xFF >> (len % 8));
+        }
+    }
+}
+
+impl fmt::Debug for BitKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.as_bit_str(), f)
+    }
+}
+
+impl fmt::Display for BitKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.as_bit_str(), f)
+    }
+}
+
+impl From<BitStr<'_>> for BitKey {
+    fn from(bits: BitStr<'_>) -> Self {
+        bits.to_key()
+    }
+}
+
+impl FromIterator<bool> for BitKey {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut key = BitKey::new();
+        key.extend(iter);
+        key
+    }
+}
+
+impl Extend<bool> for BitKey {
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|bit| self.push(bit));
+    }
+}
+
+/// Returns the first bit from `start` up to `end` where `a` and `b` differ, or `end`.
+fn mismatch(a: &[u8], b: &[u8], start: usize, end: usize) -> usize {
+    let mut mask = 0xFF >> (start % 8);
+    for i in start / 8..end.div_ceil(8) {
+        let diff = (a[i] ^ b[i]) & mask;
+        if diff != 0 {
+            return end.min(i * 8 + diff.leading_zeros() as usize);
+        }
+        mask = 0xFF;
+    }
+    end
+}
+
+#[derive(Clone)]
+struct BitNode<V> {
+    /// the bytes spanning the label, which sit where the label's bits do in a key
+    label: Box<[u8]>,
+    /// position of the label's first bit in `label[0]`
+    offset: u8,
+    /// number of bits in the label, only zero at the root
+    len: usize,
+    value: Option<V>,
+    /// indexed by the first bit of their label
+    children: [Option<Box<BitNode<V>>>; 2],
+}
+
+impl<V> BitNode<V> {
+    fn root() -> Self {
+        BitNode {
+            label: Box::default(),
+            offset: 0,
+            len: 0,
+            value: None,
+            children: [None, None],
+        }
+    }
+
+    /// the node for the bits of `key` from `at` on
+    fn leaf(key: BitStr<'_>, at: usize, value: V) -> Self {
+        BitNode {
+            label: key.bytes[at / 8..].into(),
+            offset: (at % 8) as u8,
+            len: key.len - at,
+            value: Some(value),
+            children: [None, None],
+        }
+    }
+
+    /// how many bits of the label `key` matches, for a label starting at bit `at` of the key
+    fn common_len(&self, key: BitStr<'_>, at: usize) -> usize {
+        let start = usize::from(self.offset);
+        let end = start + self.len.min(key.len - at);
+        mismatch(&self.label, &key.bytes[at / 8..], start, end) - start
+    }
+
+    /// the child whose label `key` continues with after its first `at` bits
+    fn child(&self, key: BitStr<'_>, at: usize) -> Option<&Self> {
+        let child = self.children[usize::from(key.get(at)?)].as_deref()?;
+        (child.common_len(key, at) == child.len).then_some(child)
+    }
+
+    fn get(&self, key: BitStr<'_>) -> Option<&V> {
+        let mut node = self;
+        let mut at = 0;
+        while at < key.len {
+            node = node.child(key, at)?;
+            at += node.len;
+        }
+        node.value.as_ref()
+    }
+
+    fn get_mut(&mut self, key: BitStr<'_>) -> Option<&mut V> {
+        let mut node = self;
+        let mut at = 0;
+        while let Some(bit) = key.get(at) {
+            node = node.children[usize::from(bit)].as_deref_mut()?;
+            if node.common_len(key, at) < node.len {
+                return None;
+            }
+            at += node.len;
+        }
+        node.value.as_mut()
+    }
+
+    fn longest_common_prefix_mut(&mut self, key: BitStr<'_>) -> Option<(usize, &mut V)> {
+        let mut found = None;
+        let mut node = self;
+        let mut at = 0;
+        loop {
+            let BitNode {
+                value, children, ..
+            } = node;
+            if let Some(value) = value {
+                found = Some((at, value));
+            }
+            let Some(bit) = key.get(at) else {
+                return found;
+            };
+            match children[usize::from(bit)].as_deref_mut() {
+                Some(child) if child.common_len(key, at) == child.len => {
+                    at += child.len;
+                    node = child;
+                }
+                _ => return found,
+            }
+        }
+    }
+
+    /// inserts the bits of `key` from `at` on below this node
+    fn insert(&mut self, key: BitStr<'_>, at: usize, value: V) -> Option<V> {
+        let Some(bit) = key.get(at) else {
+            return self.value.replace(value);
+        };
+        match &mut self.children[usize::from(bit)] {
+            Some(child) => {
+                let common = child.common_len(key, at);
+                if common < child.len {
+                    child.split(common);
+                }
+                child.insert(key, at + common, value)
+            }
+            slot => {
+                *slot = Some(Box::new(BitNode::leaf(key, at, value)));
+                None
+            }
+        }
+    }
+
+    /// moves everything past the first `at` bits of the label into a new only child
+    fn split(&mut self, at: usize) {
+        let split = usize::from(self.offset) + at;
+        let tail = BitNode {
+            label: self.label[split / 8..].into(),
+            offset: (split % 8) as u8,
+            len: self.len - at,
+            value: self.value.take(),
+            children: mem::take(&mut self.children),
+        };
+        let bit = tail.label[0] & (0x80 >> tail.offset) != 0;
+        self.label = self.label[..split.div_ceil(8)].into();
+        self.len = at;
+        self.children[usize::from(bit)] = Some(Box::new(tail));
+    }
+
+    /// removes the bits of `key` from `at` on below this node, compacting the nodes left
+    /// without a value
+    fn remove(&mut self, key: BitStr<'_>, at: usize) -> Option<V> {
+        let Some(bit) = key.get(at) else {
+            return self.value.take();
+        };
+        let slot = &mut self.children[usize::from(bit)];
+        let child = slot.as_deref_mut()?;
+        if child.common_len(key, at) < child.len {
+            return None;
+        }
+        let value = child.remove(key, at + child.len)?;
+        if child.value.is_none() {
+            match child.children.iter().flatten().count() {
+                0 => *slot = None,
+                1 => child.merge_child(),
+                _ => {}
+            }
+        }
+        Some(value)
+    }
+
+    /// absorbs the only child of a node without a value
+    fn merge_child(&mut self) {
+        let child = *some!(self.children.iter_mut().find_map(Option::take));
+        let split = usize::from(self.offset) + self.len;
+        let mut label = self.label[..split / 8].to_vec();
+        if split % 8 == 0 {
+            label.extend_from_slice(&child.label);
+        } else {
+            // the byte where the labels meet holds bits of both
+            let mask = 0xFF >> (split % 8);
+            label.push(self.label[split / 8] & !mask | child.label[0] & mask);
+            label.extend_from_slice(&child.label[1..]);
+        }
+        self.label = label.into();
+        self.len += child.len;
+        self.value = child.value;
+        self.children = child.children;
+    }
+}
+
+/// Radix tree based map with bit granular keys.
+///
+/// See the [`bits`](crate::bits) module.
+#[derive(Clone)]
+pub struct BitRadixMap<V> {
+    root: BitNode<V>,
+    len: usize,
+}
+
+impl<V> BitRadixMap<V> {
+    /// Makes a new empty map.
+    pub fn new() -> Self {
+        BitRadixMap {
+            root: BitNode::root(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements in this map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Clears this map, removing all values.
+    pub fn clear(&mut self) {
+        *self = BitRadixMap::new();
+    }
+
+    /// Inserts a key-value pair, returning the value the key had before, if any.
+    pub fn insert<'k, K: Into<BitStr<'k>>>(&mut self, key: K, value: V) -> Option<V> {
+        let old = self.root.insert(key.into(), 0, value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    pub fn get<'k, K: Into<BitStr<'k>>>(&self, key: K) -> Option<&V> {
+        self.root.get(key.into())
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    pub fn get_mut<'k, K: Into<BitStr<'k>>>(&mut self, key: K) -> Option<&mut V> {
+        self.root.get_mut(key.into())
+    }
+
+    /// Returns `true` if this map contains a value for the specified key.
+    pub fn contains_key<'k, K: Into<BitStr<'k>>>(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes a key from this map, returning its value.
+    pub fn remove<'k, K: Into<BitStr<'k>>>(&mut self, key: K) -> Option<V> {
+        let old = self.root.remove(key.into(), 0);
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    /// Finds the longest key in this map that `key` starts with, and returns it along with its
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::bits::{BitRadixMap, BitStr};
+    ///
+    /// let mut map = BitRadixMap::new();
+    /// map.insert(BitStr::new(&[0b1000_0000], 1), 1);
+    /// map.insert(BitStr::new(&[0b1010_0000], 3), 3);
+    /// let (prefix, value) = map.get_longest_common_prefix(BitStr::new(&[0b1011_0000], 4)).unwrap();
+    /// assert_eq!((prefix.to_string(), *value), ("101".to_string(), 3));
+    /// assert_eq!(map.get_longest_common_prefix(BitStr::new(&[0], 1)), None);
+    /// ```
+    pub fn get_longest_common_prefix<'k, K: Into<BitStr<'k>>>(
+        &self,
+        key: K,
+    ) -> Option<(BitStr<'k>, &V)> {
+        self.common_prefixes(key).last()
+    }
+
+    /// Mutable version of [`get_longest_common_prefix`](Self::get_longest_common_prefix).
+    pub fn get_longest_common_prefix_mut<'k, K: Into<BitStr<'k>>>(
+        &mut self,
+        key: K,
+    ) -> Option<(BitStr<'k>, &mut V)> {
+        let key = key.into();
+        let (len, value) = self.root.longest_common_prefix_mut(key)?;
+        Some((key.prefix(len), value))
+    }
+
+    /// Gets an iterator over the entries whose keys `key` starts with, shortest first.
+    pub fn common_prefixes<'a, 'k, K: Into<BitStr<'k>>>(
+        &'a self,
+        key: K,
+    ) -> CommonPrefixesIter<'a, 'k, V> {
+        CommonPrefixesIter {
+            walk: self.prefix_walk(),
+            key: key.into(),
+        }
+    }
+
+    /// a walk down the path to a key, see [`PrefixWalk`]
+    pub(crate) fn prefix_walk(&self) -> PrefixWalk<'_, V> {
+        PrefixWalk {
+            node: Some(&self.root),
+            len: 0,
+        }
+    }
+
+    /// Gets an iterator over the entries of this map, sorted by key.
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter {
+            stack: vec![(0, &self.root)],
+            key: BitKey::new(),
+        }
+    }
+
+    /// Gets an iterator over the keys of this map, in sorted order.
+    pub fn keys(&self) -> Keys<'_, V> {
+        Keys(self.iter())
+    }
+
+    /// Gets an iterator over the values of this map, in order by key.
+    pub fn values(&self) -> Values<'_, V> {
+        Values {
+            stack: vec![&self.root],
+        }
+    }
+}
+
+impl<V> Default for BitRadixMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: fmt::Debug> fmt::Debug for BitRadixMap<V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'k, K: Into<BitStr<'k>>, V> FromIterator<(K, V)> for BitRadixMap<V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = BitRadixMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<'k, K: Into<BitStr<'k>>, V> Extend<(K, V)> for BitRadixMap<V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, V> IntoIterator for &'a BitRadixMap<V> {
+    type Item = (BitKey, &'a V);
+    type IntoIter = Iter<'a, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Radix tree based set with bit granular keys.
+///
+/// See the [`bits`](crate::bits) module.
+#[derive(Clone, Default)]
+pub struct BitRadixSet {
+    map: BitRadixMap<()>,
+}
+
+impl BitRadixSet {
+    /// Makes a new empty set.
+    pub fn new() -> Self {
+        BitRadixSet::default()
+    }
+
+    /// Returns the number of elements in this set.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if this set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Clears this set, removing all values.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Adds a key to this set, returning `false` if it was already present.
+    pub fn insert<'k, K: Into<BitStr<'k>>>(&mut self, key: K) -> bool {
+        self.map.insert(key, ()).is_none()
+    }
+
+    /// Returns `true` if this set contains `key`.
+    pub fn contains<'k, K: Into<BitStr<'k>>>(&self, key: K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Removes a key from this set, returning `true` if it was present.
+    pub fn remove<'k, K: Into<BitStr<'k>>>(&mut self, key: K) -> bool {
+        self.map.remove(key).is_some()
+    }
+
+    /// Finds the longest key in this set that `key` starts with.
+    pub fn get_longest_common_prefix<'k, K: Into<BitStr<'k>>>(&self, key: K) -> Option<BitStr<'k>> {
+        self.map
+            .get_longest_common_prefix(key)
+            .map(|(prefix, _)| prefix)
+    }
+
+    /// Gets an iterator over the keys of this set, in sorted order.
+    pub fn iter(&self) -> Keys<'_, ()> {
+        self.map.keys()
+    }
+}
+
+impl fmt::Debug for BitRadixSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'k, K: Into<BitStr<'k>>> FromIterator<K> for BitRadixSet {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut set = BitRadixSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<'k, K: Into<BitStr<'k>>> Extend<K> for BitRadixSet {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a BitRadixSet {
+    type Item = BitKey;
+    type IntoIter = Keys<'a, ()>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A walk down the nodes on the path to a key, yielding the values of its prefixes.
+///
+/// The key is passed to each step, so the walk doesn't borrow it.
+pub(crate) struct PrefixWalk<'a, V> {
+    // the next node on the path, and the length of the key it ends
+    node: Option<&'a BitNode<V>>,
+    len: usize,
+}
+
+impl<'a, V> PrefixWalk<'a, V> {
+    /// the length and value of the next prefix of `key` in the map
+    pub(crate) fn next_match(&mut self, key: BitStr<'_>) -> Option<(usize, &'a V)> {
+        while let Some(node) = self.node.take() {
+            let len = self.len;
+            if let Some(child) = node.child(key, len) {
+                self.node = Some(child);
+                self.len += child.len;
+            }
+            if let Some(value) = &node.value {
+                return Some((len, value));
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over the entries of a [`BitRadixMap`] whose keys are prefixes of a given key.
+pub struct CommonPrefixesIter<'a, 'k, V> {
+    walk: PrefixWalk<'a, V>,
+    key: BitStr<'k>,
+}
+
+impl<V> fmt::Debug for CommonPrefixesIter<'_, '_, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CommonPrefixesIter")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, 'k, V> Iterator for CommonPrefixesIter<'a, 'k, V> {
+    type Item = (BitStr<'k>, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (len, value) = self.walk.next_match(self.key)?;
+        Some((self.key.prefix(len), value))
+    }
+}
+
+/// An iterator over a [`BitRadixMap`]'s entries, sorted by key.
+pub struct Iter<'a, V> {
+    // nodes still to visit, with the key length of their parent
+    stack: Vec<(usize, &'a BitNode<V>)>,
+    key: BitKey,
+}
+
+impl<V> fmt::Debug for Iter<'_, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Iter").field("key", &self.key).finish()
+    }
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (BitKey, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((key_len, node)) = self.stack.pop() {
+            self.key.truncate(key_len);
+            self.key.append(&node.label, node.len);
+            let key_len = self.key.len();
+            self.stack.extend(
+                node.children
+                    .iter()
+                    .rev()
+                    .flatten()
+                    .map(|child| (key_len, &**child)),
+            );
+            if let Some(value) = &node.value {
+                return Some((self.key.clone(), value));
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over a [`BitRadixMap`]'s keys, in sorted order.
+#[derive(Debug)]
+pub struct Keys<'a, V>(Iter<'a, V>);
+
+impl<V> Iterator for Keys<'_, V> {
+    type Item = BitKey;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, _)| k)
+    }
+}
+
+/// An iterator over a [`BitRadixMap`]'s values, in order by key.
+pub struct Values<'a, V> {
+    stack: Vec<&'a BitNode<V>>,
+}
+
+impl<V> fmt::Debug for Values<'_, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Values").finish_non_exhaustive()
+    }
+}
+
+impl<'a, V> Iterator for Values<'a, V> {
+    type Item = &'a V;
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            self.stack
+                .extend(node.children.iter().rev().flatten().map(|child| &**child));
+            if let Some(value) = &node.value {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use std::collections::BTreeMap;
+
+    fn assert_compressed<V>(node: &BitNode<V>, key_len: usize, is_root: bool) {
+        assert_eq!(usize::from(node.offset), key_len % 8);
+        assert!(node.label.len() >= (usize::from(node.offset) + node.len).div_ceil(8));
+        if !is_root {
+            assert!(node.len > 0);
+            assert!(node.value.is_some() || node.children.iter().flatten().count() == 2);
+        }
+        for (bit, child) in node.children.iter().enumerate() {
+            if let Some(child) = child {
+                assert_eq!(child.label[0] & (0x80 >> child.offset) != 0, bit == 1);
+                assert_compressed(child, key_len + node.len, false);
+            }
+        }
+    }
+
+    #[test]
+    fn packs_and_unpacks() {
+        let bytes = [0xDE, 0xAD, 0xBE, 0xEF];
+        for bit_len in 0..=32 {
+            let key = BitKey::from_prefix(&bytes, bit_len);
+            assert_eq!(key.len(), bit_len);
+            let packed = key.as_packed();
+            assert_eq!(packed.len(), bit_len.div_ceil(8));
+            assert_eq!(BitKey::from_prefix(packed, bit_len), key);
+            assert_eq!(BitStr::new(&bytes, bit_len), key.as_bit_str());
+            assert_eq!(key.as_bit_str().iter().collect::<BitKey>(), key);
+        }
+    }
+
+    #[test]
+    fn nodes_branch_on_bits() {
+        let mut map = BitRadixMap::new();
+        for (byte, len) in [(0b1010_0000, 3), (0b1011_0000, 4), (0b1000_0000, 1), (0, 0)] {
+            map.insert(&BitKey::from_prefix(&[byte], len), len);
+        }
+        assert_compressed(&map.root, 0, true);
+        assert!(
+            map.keys()
+                .map(|k| k.to_string())
+                .eq(["", "1", "101", "1011"])
+        );
+
+        let probe = BitKey::from_prefix(&[0b1011_1111], 8);
+        assert_eq!(map.get_longest_common_prefix(&probe).unwrap().1, &4);
+        assert!(
+            map.common_prefixes(&probe)
+                .map(|(_, v)| v)
+                .eq(&[0, 1, 3, 4])
+        );
+        let probe = BitKey::from_prefix(&[0b1001_1111], 8);
+        assert_eq!(map.get_longest_common_prefix(&probe).unwrap().1, &1);
+    }
+
+    #[test]
+    fn map_matches_btree_map() {
+        let mut rng = rand::rng();
+        let mut map = BitRadixMap::new();
+        let mut expected = BTreeMap::new();
+        let random_key = |rng: &mut rand::rngs::ThreadRng| {
+            // few distinct high bits, so keys share prefixes
+            let bytes = (rng.random::<u32>() & 0xF0F0_FFFF).to_be_bytes();
+            BitKey::from_prefix(&bytes, rng.random_range(0..=32))
+        };
+        for i in 0..if cfg!(miri) { 100 } else { 3000 } {
+            let key = random_key(&mut rng);
+            if rng.random_ratio(1, 3) {
+                assert_eq!(map.remove(&key), expected.remove(&key));
+            } else {
+                assert_eq!(map.insert(&key, i), expected.insert(key, i));
+            }
+            assert_eq!(map.len(), expected.len());
+        }
+        assert_compressed(&map.root, 0, true);
+        assert!(map.iter().eq(expected.iter().map(|(k, v)| (k.clone(), v))));
+        assert!(map.values().eq(expected.values()));
+
+        for _ in 0..if cfg!(miri) { 30 } else { 1000 } {
+            let key = random_key(&mut rng);
+            assert_eq!(map.get(&key), expected.get(&key));
+            let prefixes = expected
+                .iter()
+                .filter(|(k, _)| {
+                    k.len() <= key.len() && key.as_bit_str().prefix(k.len()) == k.as_bit_str()
+                })
+                .map(|(k, v)| (k.as_bit_str(), *v))
+                .collect::<Vec<_>>();
+            assert!(
+                map.common_prefixes(&key)
+                    .map(|(k, v)| (k, *v))
+                    .eq(prefixes.iter().copied())
+            );
+            let longest = prefixes.last().copied();
+            assert_eq!(
+                map.get_longest_common_prefix(&key).map(|(k, v)| (k, *v)),
+                longest
+            );
+            assert_eq!(
+                map.get_longest_common_prefix_mut(&key)
+                    .map(|(k, v)| (k, *v)),
+                longest
+            );
+        }
+    }
+}
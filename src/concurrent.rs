@@ -0,0 +1,318 @@
+//! A map that many threads read without locking while a single writer updates it, enabled by
+//! the `concurrent` feature.
+//!
+//! [`GenericConcurrentRadixMap`] publishes versions of a [`GenericPersistentRadixMap`] through an
+//! atomically swapped pointer, RCU style. Readers load the current version without taking a
+//! lock and keep a consistent snapshot for as long as they hold on to it. Writers take turns
+//! through a mutex that readers never touch, build the next version by path copying, and swap
+//! it in. Loads are protected by the hazard pointer like debts of [`arc_swap`], and a version
+//! is freed once the last snapshot of it is dropped.
+//!
+//! # Examples
+//!
+//! ```
+//! use fast_radix_trie::StringConcurrentRadixMap;
+//!
+//! let map = StringConcurrentRadixMap::new();
+//! map.insert("example.com", 1);
+//!
+//! let before = map.snapshot();
+//! {
+//!     // a batch is published all at once when the guard is dropped
+//!     let mut batch = map.write();
+//!     batch.insert("example.org", 2);
+//!     batch.remove("example.com");
+//! }
+//! assert_eq!(before.get("example.com"), Some(&1));
+//! assert_eq!(map.get_cloned("example.com"), None);
+//! assert_eq!(map.snapshot().keys().collect::<Vec<_>>(), ["example.org"]);
+//! ```
+use crate::{BorrowedBytes, Bytes, GenericPersistentRadixMap};
+use alloc::{string::String, sync::Arc, vec::Vec};
+use arc_swap::ArcSwap;
+use core::{fmt, iter::FromIterator, mem, ops::Deref};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Concurrent radix tree based map with [`Vec<u8>`] as key.
+pub type ConcurrentRadixMap<V> = GenericConcurrentRadixMap<Vec<u8>, V>;
+
+/// Concurrent radix tree based map with [`String`] as key.
+pub type StringConcurrentRadixMap<V> = GenericConcurrentRadixMap<String, V>;
+
+/// Radix tree based map with lock-free reads, updated by one writer at a time.
+///
+/// See the [`concurrent`](crate::concurrent) module.
+pub struct GenericConcurrentRadixMap<K, V> {
+    current: ArcSwap<GenericPersistentRadixMap<K, V>>,
+    // only held by writers, so they build on the version the last one published
+    writer: Mutex<()>,
+}
+
+impl<K, V> GenericConcurrentRadixMap<K, V> {
+    /// Makes a new empty concurrent map.
+    pub fn new() -> Self {
+        Self::from(GenericPersistentRadixMap::new())
+    }
+
+    /// Returns the current version of this map, which stays unchanged while writers publish
+    /// new ones.
+    ///
+    /// This never blocks and costs a reference count increment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::ConcurrentRadixMap;
+    ///
+    /// let map = ConcurrentRadixMap::new();
+    /// map.insert("foo", 1);
+    /// let snapshot = map.snapshot();
+    /// map.insert("foo", 2);
+    /// assert_eq!(snapshot.get("foo"), Some(&1));
+    /// assert_eq!(map.snapshot().get("foo"), Some(&2));
+    /// ```
+    pub fn snapshot(&self) -> GenericPersistentRadixMap<K, V> {
+        GenericPersistentRadixMap::clone(&self.current.load())
+    }
+
+    /// Returns the number of elements in the current version.
+    pub fn len(&self) -> usize {
+        self.current.load().len()
+    }
+
+    /// Returns `true` if the current version contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Waits for the other writers, then returns a guard holding the next version.
+    ///
+    /// Changes made through the guard are invisible to readers until it's dropped, at which
+    /// point they're published together. Nothing is published if the writer panics.
+    pub fn write(&self) -> WriteGuard<'_, K, V> {
+        // the next version is thrown away on panic, so a poisoned lock guards nothing broken
+        let lock = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        WriteGuard {
+            next: self.snapshot(),
+            map: self,
+            _lock: lock,
+        }
+    }
+}
+
+impl<K: Bytes, V> GenericConcurrentRadixMap<K, V> {
+    /// Returns `true` if the current version contains a value for the specified key.
+    pub fn contains_key<Q: AsRef<K::Borrowed>>(&self, key: Q) -> bool {
+        self.current.load().contains_key(key)
+    }
+
+    /// Returns a clone of the value corresponding to the key in the current version.
+    ///
+    /// Unlike going through [`snapshot`](Self::snapshot), this doesn't touch any reference
+    /// count.
+    pub fn get_cloned<Q: AsRef<K::Borrowed>>(&self, key: Q) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.current.load().get(key).cloned()
+    }
+}
+
+impl<K: Bytes, V: Clone> GenericConcurrentRadixMap<K, V> {
+    /// Inserts a key-value pair and publishes the new version right away.
+    ///
+    /// To publish several changes at once, use [`write`](Self::write).
+    pub fn insert<Q: AsRef<K::Borrowed>>(&self, key: Q, value: V) -> Option<V> {
+        self.write().insert(key, value)
+    }
+
+    /// Removes a key and publishes the new version right away, if the key was present.
+    pub fn remove<Q: AsRef<K::Borrowed>>(&self, key: Q) -> Option<V> {
+        self.write().remove(key)
+    }
+}
+
+impl<K, V> Default for GenericConcurrentRadixMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> fmt::Debug for GenericConcurrentRadixMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenericConcurrentRadixMap")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, V> From<GenericPersistentRadixMap<K, V>> for GenericConcurrentRadixMap<K, V> {
+    fn from(map: GenericPersistentRadixMap<K, V>) -> Self {
+        GenericConcurrentRadixMap {
+            current: ArcSwap::from_pointee(map),
+            writer: Mutex::new(()),
+        }
+    }
+}
+
+impl<K, Q, V> FromIterator<(Q, V)> for GenericConcurrentRadixMap<K, V>
+where
+    K: Bytes,
+    Q: AsRef<K::Borrowed>,
+    V: Clone,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (Q, V)>,
+    {
+        Self::from(
+            iter.into_iter()
+                .collect::<GenericPersistentRadixMap<K, V>>(),
+        )
+    }
+}
+
+/// The next version of a [`GenericConcurrentRadixMap`], published when dropped.
+///
+/// Dereferences to the pending version, so the writer sees its own changes.
+///
+/// This `struct` is created by the [`write`](GenericConcurrentRadixMap::write) method on
+/// [`GenericConcurrentRadixMap`].
+pub struct WriteGuard<'a, K, V> {
+    map: &'a GenericConcurrentRadixMap<K, V>,
+    next: GenericPersistentRadixMap<K, V>,
+    // dropped after `drop` has published, so writers can't interleave
+    _lock: MutexGuard<'a, ()>,
+}
+
+impl<K: Bytes, V: Clone> WriteGuard<'_, K, V> {
+    /// Inserts a key-value pair into the pending version.
+    ///
+    /// The path to `key` is copied the first time this batch touches it, later changes under
+    /// the same nodes are made in place.
+    pub fn insert<Q: AsRef<K::Borrowed>>(&mut self, key: Q, value: V) -> Option<V> {
+        self.next.insert_in_place(key.as_ref().as_bytes(), value)
+    }
+
+    /// Removes a key from the pending version.
+    pub fn remove<Q: AsRef<K::Borrowed>>(&mut self, key: Q) -> Option<V> {
+        self.next.remove_in_place(key.as_ref().as_bytes())
+    }
+}
+
+impl<K, V> Deref for WriteGuard<'_, K, V> {
+    type Target = GenericPersistentRadixMap<K, V>;
+    fn deref(&self) -> &Self::Target {
+        &self.next
+    }
+}
+
+impl<K, V> fmt::Debug for WriteGuard<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WriteGuard")
+            .field("len", &self.next.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, V> Drop for WriteGuard<'_, K, V> {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            let next = mem::take(&mut self.next);
+            self.map.current.store(Arc::new(next));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        thread,
+    };
+
+    const KEYS: usize = 64;
+
+    fn key(i: usize) -> String {
+        // shared prefixes, so batches rewrite overlapping paths
+        format!("zone/{}/{}", i % 7, i)
+    }
+
+    #[test]
+    fn readers_see_whole_batches() {
+        let map = StringConcurrentRadixMap::<usize>::from_iter((0..KEYS).map(|i| (key(i), 0)));
+        let done = AtomicBool::new(false);
+        let batches = if cfg!(miri) { 5 } else { 500 };
+
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    let mut last = 0;
+                    while !done.load(Ordering::Relaxed) {
+                        let snapshot = map.snapshot();
+                        let generation = *snapshot.get(key(0)).unwrap();
+                        assert!(generation >= last, "versions went backwards");
+                        last = generation;
+                        // every batch flips one extra key, so the length follows the generation
+                        assert_eq!(snapshot.len(), KEYS + generation % 2);
+                        assert!(snapshot.values().take(KEYS).all(|v| *v == generation));
+                        assert!(map.get_cloned(key(KEYS - 1)).is_some());
+                    }
+                });
+            }
+            s.spawn(|| {
+                for generation in 1..=batches {
+                    let mut batch = map.write();
+                    for i in 0..KEYS {
+                        batch.insert(key(i), generation);
+                    }
+                    if generation % 2 == 1 {
+                        batch.insert("zzz", generation);
+                    } else {
+                        batch.remove("zzz");
+                    }
+                }
+                done.store(true, Ordering::Relaxed);
+            });
+        });
+        assert_eq!(map.len(), KEYS + batches % 2);
+    }
+
+    #[test]
+    fn concurrent_writers_take_turns() {
+        let map = ConcurrentRadixMap::<usize>::new();
+        let per_thread = if cfg!(miri) { 10 } else { 1000 };
+        thread::scope(|s| {
+            for t in 0..4 {
+                let map = &map;
+                s.spawn(move || {
+                    for i in 0..per_thread {
+                        map.insert(format!("{t}/{i}"), i);
+                    }
+                });
+            }
+        });
+        assert_eq!(map.len(), 4 * per_thread);
+        assert_eq!(map.snapshot().iter().count(), 4 * per_thread);
+    }
+
+    #[test]
+    fn panicking_writer_publishes_nothing() {
+        let map = ConcurrentRadixMap::from_iter([("a", 1)]);
+        let result = thread::scope(|s| {
+            s.spawn(|| {
+                let mut batch = map.write();
+                batch.insert("b", 2);
+                panic!("writer failed");
+            })
+            .join()
+        });
+        assert!(result.is_err());
+        assert_eq!(map.len(), 1);
+        assert!(!map.contains_key("b"));
+        // the lock is usable again
+        map.insert("c", 3);
+        assert_eq!(map.len(), 2);
+    }
+}
@@ -0,0 +1,1057 @@
+//! Read-only maps and sets that live inside a single contiguous byte buffer.
+//!
+//! [`GenericRadixMap::freeze`] and [`GenericRadixSet::freeze`] write a tree into a flat buffer,
+//! which can be saved to a file and later memory mapped. A [`GenericFrozenRadixMap`] answers
+//! lookups by following offsets inside that buffer, without deserializing anything, so several
+//! processes can share a single copy of a large trie through the page cache.
+//!
+//! The buffer is never trusted: opening it checks that its nodes form a tree, that every key is
+//! valid for the key type and that every value can be decoded as the value type, so a damaged
+//! buffer is rejected up front rather than panicking on a later lookup. Values are still only
+//! decoded when read, see [`FrozenValue::thaw`].
+//!
+//! # Examples
+//!
+//! ```
+//! use fast_radix_trie::{StringFrozenRadixMap, StringRadixMap};
+//!
+//! let map: StringRadixMap<u32> = [("foo", 1), ("foobar", 2), ("baz", 3)].into_iter().collect();
+//! let bytes = map.freeze();
+//!
+//! let frozen = StringFrozenRadixMap::<u32>::new(&bytes).unwrap();
+//! assert_eq!(frozen.len(), 3);
+//! assert_eq!(frozen.get("foobar"), Some(2));
+//! assert_eq!(frozen.get_longest_common_prefix("fooba"), Some(("foo", 1)));
+//! assert_eq!(
+//!     frozen.iter_prefix("foo").collect::<Vec<_>>(),
+//!     [("foo".to_string(), 1), ("foobar".to_string(), 2)]
+//! );
+//! ```
+use crate::{BorrowedBytes, Bytes, GenericRadixMap, GenericRadixSet, Node};
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::{fmt, marker::PhantomData};
+
+const MAGIC: &[u8; 4] = b"FRTZ";
+const VERSION: u8 = 1;
+// magic, version and the number of entries as a u64
+const HEADER_LEN: usize = 13;
+const OFFSET_LEN: usize = 8;
+
+/// A value that can be written into a frozen buffer by [`GenericRadixMap::freeze`].
+pub trait FreezeValue {
+    /// Appends the encoding of this value to `buf`.
+    fn freeze(&self, buf: &mut Vec<u8>);
+}
+
+/// A value that can be read out of a frozen buffer, possibly borrowing from it.
+pub trait FrozenValue<'a>: Sized {
+    /// Decodes a value from exactly the bytes written by [`FreezeValue::freeze`].
+    ///
+    /// # Panics
+    ///
+    /// May panic if [`is_valid_bytes`](Self::is_valid_bytes) returns `false` for `bytes`.
+    fn thaw(bytes: &'a [u8]) -> Self;
+
+    /// Returns `true` if [`thaw`](Self::thaw) can decode `bytes`, which opening a frozen buffer
+    /// checks for every value.
+    fn is_valid_bytes(bytes: &[u8]) -> bool;
+}
+
+impl<T: ?Sized + FreezeValue> FreezeValue for &T {
+    fn freeze(&self, buf: &mut Vec<u8>) {
+        (**self).freeze(buf)
+    }
+}
+
+impl FreezeValue for () {
+    fn freeze(&self, _buf: &mut Vec<u8>) {}
+}
+impl FrozenValue<'_> for () {
+    fn thaw(_bytes: &[u8]) -> Self {}
+
+    fn is_valid_bytes(bytes: &[u8]) -> bool {
+        bytes.is_empty()
+    }
+}
+
+impl FreezeValue for [u8] {
+    fn freeze(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self);
+    }
+}
+impl FreezeValue for Vec<u8> {
+    fn freeze(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self);
+    }
+}
+impl<'a> FrozenValue<'a> for &'a [u8] {
+    fn thaw(bytes: &'a [u8]) -> Self {
+        bytes
+    }
+
+    fn is_valid_bytes(_bytes: &[u8]) -> bool {
+        true
+    }
+}
+
+impl FreezeValue for str {
+    fn freeze(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self.as_bytes());
+    }
+}
+impl FreezeValue for String {
+    fn freeze(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self.as_bytes());
+    }
+}
+impl<'a> FrozenValue<'a> for &'a str {
+    fn thaw(bytes: &'a [u8]) -> Self {
+        core::str::from_utf8(bytes).expect("frozen value is not valid UTF-8")
+    }
+
+    fn is_valid_bytes(bytes: &[u8]) -> bool {
+        core::str::from_utf8(bytes).is_ok()
+    }
+}
+
+macro_rules! le_bytes_value {
+    ($($ty:ty),*) => {
+        $(
+            impl FreezeValue for $ty {
+                fn freeze(&self, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+            impl FrozenValue<'_> for $ty {
+                fn thaw(bytes: &[u8]) -> Self {
+                    <$ty>::from_le_bytes(bytes.try_into().expect("frozen value has the wrong size"))
+                }
+
+                fn is_valid_bytes(bytes: &[u8]) -> bool {
+                    bytes.len() == size_of::<$ty>()
+                }
+            }
+        )*
+    };
+}
+le_bytes_value!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
+);
+
+/// Errors raised when opening a frozen buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrozenError {
+    /// The buffer does not start with the frozen trie magic bytes.
+    InvalidMagic,
+    /// The buffer was written by an unsupported version of the format.
+    UnsupportedVersion(u8),
+    /// The buffer is too short to hold a trie.
+    Truncated,
+    /// The nodes of the buffer don't form the tree written by [`GenericRadixMap::freeze`].
+    Corrupt,
+    /// A key is not valid for the key type, such as a `String` key that is not UTF-8.
+    InvalidKey,
+    /// A value can't be decoded as the value type, see [`FrozenValue::is_valid_bytes`].
+    InvalidValue,
+}
+
+impl fmt::Display for FrozenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FrozenError::InvalidMagic => f.write_str("not a frozen radix trie"),
+            FrozenError::UnsupportedVersion(v) => write!(f, "unsupported frozen trie version {v}"),
+            FrozenError::Truncated => f.write_str("frozen radix trie is truncated"),
+            FrozenError::Corrupt => f.write_str("frozen radix trie is corrupt"),
+            FrozenError::InvalidKey => f.write_str("frozen radix trie has an invalid key"),
+            FrozenError::InvalidValue => f.write_str("frozen radix trie has an invalid value"),
+        }
+    }
+}
+
+impl core::error::Error for FrozenError {}
+
+/// Writes `root` depth first. Each node is laid out as `label_len: u8`, the label,
+/// `children_len: u8`, a value tag (`0`, or `1` followed by a `u32` length and the value), the
+/// first byte of every child and finally the `u64` offset of every child.
+fn freeze_tree<V: FreezeValue>(root: &Node<V>, len: usize) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(MAGIC);
+    buf.push(VERSION);
+    buf.extend_from_slice(&(len as u64).to_le_bytes());
+
+    // offset slots still waiting for their child; in depth first order the next node written
+    // always belongs to the slot on top
+    let mut slots: Vec<usize> = Vec::new();
+    let mut value_buf = Vec::new();
+    for (_, node) in root.iter() {
+        let offset = buf.len() as u64;
+        if let Some(slot) = slots.pop() {
+            buf[slot..slot + OFFSET_LEN].copy_from_slice(&offset.to_le_bytes());
+        }
+        buf.push(node.label_len() as u8);
+        buf.extend_from_slice(node.label());
+        let children = node.children();
+        buf.push(children.len() as u8);
+        match node.value() {
+            None => buf.push(0),
+            Some(value) => {
+                value_buf.clear();
+                value.freeze(&mut value_buf);
+                let value_len =
+                    u32::try_from(value_buf.len()).expect("frozen values must be under 4GiB");
+                buf.push(1);
+                buf.extend_from_slice(&value_len.to_le_bytes());
+                buf.extend_from_slice(&value_buf);
+            }
+        }
+        buf.extend(children.iter().map(|child| child.label()[0]));
+        let first_slot = buf.len();
+        buf.resize(first_slot + OFFSET_LEN * children.len(), 0);
+        slots.extend(
+            (0..children.len())
+                .rev()
+                .map(|i| first_slot + OFFSET_LEN * i),
+        );
+    }
+    buf
+}
+
+/// A decoded view of a single node inside the buffer.
+#[derive(Clone, Copy)]
+struct RawNode<'a> {
+    offset: usize,
+    label: &'a [u8],
+    value: Option<&'a [u8]>,
+    first_bytes: &'a [u8],
+    offsets: &'a [u8],
+}
+impl RawNode<'_> {
+    fn children_len(&self) -> usize {
+        self.first_bytes.len()
+    }
+
+    fn child(&self, i: usize) -> usize {
+        let at = OFFSET_LEN * i;
+        u64::from_le_bytes(some_bytes(&self.offsets[at..at + OFFSET_LEN])) as usize
+    }
+
+    fn child_with_first(&self, byte: u8) -> Option<usize> {
+        memchr::memchr(byte, self.first_bytes).map(|i| self.child(i))
+    }
+}
+
+fn some_bytes<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    bytes.try_into().expect("slice has the array length")
+}
+
+#[derive(Clone, Copy)]
+struct FrozenTrie<'a> {
+    bytes: &'a [u8],
+}
+impl<'a> FrozenTrie<'a> {
+    fn root(self) -> RawNode<'a> {
+        self.node(HEADER_LEN)
+    }
+
+    fn node(self, offset: usize) -> RawNode<'a> {
+        let (node, _) = self.parse(offset).expect("frozen radix trie is validated");
+        node
+    }
+
+    /// Decodes the node at `offset` along with the offset right after it, or `None` if it
+    /// doesn't fit in the buffer.
+    fn parse(self, offset: usize) -> Option<(RawNode<'a>, usize)> {
+        let bytes = self.bytes;
+        let label_len = *bytes.get(offset)? as usize;
+        let mut at = offset + 1;
+        let label = bytes.get(at..at + label_len)?;
+        at += label_len;
+        let children_len = *bytes.get(at)? as usize;
+        at += 1;
+        let value = if *bytes.get(at)? == 0 {
+            at += 1;
+            None
+        } else {
+            let value_len = u32::from_le_bytes(some_bytes(bytes.get(at + 1..at + 5)?)) as usize;
+            at += 5;
+            let value = bytes.get(at..at.checked_add(value_len)?)?;
+            at += value_len;
+            Some(value)
+        };
+        let first_bytes = bytes.get(at..at + children_len)?;
+        at += children_len;
+        let offsets = bytes.get(at..at + OFFSET_LEN * children_len)?;
+        at += offsets.len();
+        let node = RawNode {
+            offset,
+            label,
+            value,
+            first_bytes,
+            offsets,
+        };
+        Some((node, at))
+    }
+
+    /// Checks that the buffer holds exactly the nodes of a tree of `len` values, laid out depth
+    /// first as [`freeze_tree`] writes them, so every offset followed later leads to a node, and
+    /// that every key and value decodes as `K` and `V`.
+    fn validate<K: Bytes, V: FrozenValue<'a>>(self, len: usize) -> Result<(), FrozenError> {
+        // nodes still to come, with the first byte their label must start with and the key
+        // length of their parent
+        let mut expected = vec![(HEADER_LEN, None, 0)];
+        let mut key = Vec::new();
+        let mut at = HEADER_LEN;
+        let mut values = 0;
+        while let Some((offset, first, key_len)) = expected.pop() {
+            if offset != at {
+                return Err(FrozenError::Corrupt);
+            }
+            let (node, end) = self.parse(offset).ok_or(FrozenError::Truncated)?;
+            if first.is_some() && node.label.first() != first.as_ref() {
+                return Err(FrozenError::Corrupt);
+            }
+            key.truncate(key_len);
+            key.extend_from_slice(node.label);
+            if let Some(value) = node.value {
+                if !K::Borrowed::is_valid_bytes(&key) {
+                    return Err(FrozenError::InvalidKey);
+                }
+                if !V::is_valid_bytes(value) {
+                    return Err(FrozenError::InvalidValue);
+                }
+                values += 1;
+            }
+            expected.extend(
+                (0..node.children_len())
+                    .rev()
+                    .map(|i| (node.child(i), Some(node.first_bytes[i]), key.len())),
+            );
+            at = end;
+        }
+        if at != self.bytes.len() || values != len {
+            return Err(FrozenError::Corrupt);
+        }
+        Ok(())
+    }
+
+    fn get_node(self, mut key: &[u8]) -> Option<RawNode<'a>> {
+        let mut node = self.root();
+        loop {
+            key = crate::strip_prefix(key, node.label)?;
+            let Some(&first) = key.first() else {
+                return Some(node);
+            };
+            node = self.node(node.child_with_first(first)?);
+        }
+    }
+
+    /// Returns the length of the longest key that is a prefix of `key`, with its value.
+    fn get_longest_common_prefix(self, key: &[u8]) -> Option<(usize, &'a [u8])> {
+        let mut node = self.root();
+        let mut consumed = 0;
+        let mut longest = None;
+        while let Some(rest) = crate::strip_prefix(&key[consumed..], node.label) {
+            consumed += node.label.len();
+            if let Some(value) = node.value {
+                longest = Some((consumed, value));
+            }
+            let Some(child) = rest.first().and_then(|&b| node.child_with_first(b)) else {
+                break;
+            };
+            node = self.node(child);
+        }
+        longest
+    }
+
+    /// Finds the topmost node holding every key starting with `prefix`, and the length of the
+    /// key leading to that node.
+    fn prefix_node(self, prefix: &[u8]) -> Option<(usize, usize)> {
+        let mut node = self.root();
+        let mut consumed = 0;
+        loop {
+            let rest = &prefix[consumed..];
+            let Some(&first) = rest.first() else {
+                return Some((node.offset, consumed - node.label.len()));
+            };
+            let child = self.node(node.child_with_first(first)?);
+            if child.label.starts_with(rest) {
+                return Some((child.offset, consumed));
+            }
+            crate::strip_prefix(rest, child.label)?;
+            consumed += child.label.len();
+            node = child;
+        }
+    }
+}
+
+/// Read-only radix tree based map with [`Vec<u8>`] as key, stored in a byte buffer.
+pub type FrozenRadixMap<'a, V> = GenericFrozenRadixMap<'a, Vec<u8>, V>;
+
+/// Read-only radix tree based map with [`String`] as key, stored in a byte buffer.
+pub type StringFrozenRadixMap<'a, V> = GenericFrozenRadixMap<'a, String, V>;
+
+/// Read-only radix tree based map, stored in a byte buffer written by
+/// [`GenericRadixMap::freeze`].
+pub struct GenericFrozenRadixMap<'a, K, V> {
+    trie: FrozenTrie<'a>,
+    len: usize,
+    _marker: PhantomData<(K, fn() -> V)>,
+}
+
+impl<K, V> Clone for GenericFrozenRadixMap<'_, K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<K, V> Copy for GenericFrozenRadixMap<'_, K, V> {}
+
+impl<K, V> fmt::Debug for GenericFrozenRadixMap<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GenericFrozenRadixMap")
+            .field("len", &self.len)
+            .field("bytes", &self.trie.bytes.len())
+            .finish()
+    }
+}
+
+impl<'a, K, V> GenericFrozenRadixMap<'a, K, V> {
+    /// Opens a buffer written by [`GenericRadixMap::freeze`].
+    ///
+    /// Every node is checked once, along with every key and value, in time linear in the size of
+    /// the buffer and the length of the keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{FrozenRadixMap, RadixMap, frozen::FrozenError};
+    ///
+    /// assert_eq!(FrozenRadixMap::<u32>::new(b"nope").unwrap_err(), FrozenError::Truncated);
+    ///
+    /// let mut bytes = RadixMap::<u32>::new().freeze();
+    /// bytes.push(0);
+    /// assert_eq!(FrozenRadixMap::<u32>::new(&bytes).unwrap_err(), FrozenError::Corrupt);
+    ///
+    /// // opened with the wrong value type
+    /// let bytes = RadixMap::from_iter([("foo", 1u32)]).freeze();
+    /// assert_eq!(FrozenRadixMap::<u64>::new(&bytes).unwrap_err(), FrozenError::InvalidValue);
+    /// ```
+    pub fn new(bytes: &'a [u8]) -> Result<Self, FrozenError>
+    where
+        K: Bytes,
+        V: FrozenValue<'a>,
+    {
+        // the header and a root node without label, children or value
+        if bytes.len() < HEADER_LEN + 3 {
+            return Err(FrozenError::Truncated);
+        }
+        if &bytes[..MAGIC.len()] != MAGIC {
+            return Err(FrozenError::InvalidMagic);
+        }
+        if bytes[4] != VERSION {
+            return Err(FrozenError::UnsupportedVersion(bytes[4]));
+        }
+        let len = u64::from_le_bytes(some_bytes(&bytes[5..HEADER_LEN]));
+        let len = usize::try_from(len).map_err(|_| FrozenError::Truncated)?;
+        let trie = FrozenTrie { bytes };
+        trie.validate::<K, V>(len)?;
+        Ok(GenericFrozenRadixMap {
+            trie,
+            len,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the number of elements in this map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the underlying buffer.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.trie.bytes
+    }
+}
+
+impl<'a, K: Bytes, V: FrozenValue<'a>> GenericFrozenRadixMap<'a, K, V> {
+    /// Returns `true` if this map contains a value for the specified key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{FrozenRadixMap, RadixMap};
+    ///
+    /// let map: RadixMap<u32> = [("foo", 1)].into_iter().collect();
+    /// let bytes = map.freeze();
+    /// let frozen = FrozenRadixMap::<u32>::new(&bytes).unwrap();
+    /// assert!(frozen.contains_key("foo"));
+    /// assert!(!frozen.contains_key("bar"));
+    /// ```
+    pub fn contains_key<Q: AsRef<K::Borrowed>>(&self, key: Q) -> bool {
+        self.trie
+            .get_node(key.as_ref().as_bytes())
+            .is_some_and(|node| node.value.is_some())
+    }
+
+    /// Returns the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{FrozenRadixMap, RadixMap};
+    ///
+    /// let map: RadixMap<&str> = [("foo", "one")].into_iter().collect();
+    /// let bytes = map.freeze();
+    /// let frozen = FrozenRadixMap::<&str>::new(&bytes).unwrap();
+    /// assert_eq!(frozen.get("foo"), Some("one"));
+    /// assert_eq!(frozen.get("fo"), None);
+    /// ```
+    pub fn get<Q: AsRef<K::Borrowed>>(&self, key: Q) -> Option<V> {
+        self.trie
+            .get_node(key.as_ref().as_bytes())?
+            .value
+            .map(V::thaw)
+    }
+
+    /// Finds the longest common prefix of `key` and the keys in this map,
+    /// and returns a reference to the entry whose key matches the prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{FrozenRadixMap, RadixMap};
+    ///
+    /// let map: RadixMap<u32> = [("foo", 1), ("foobar", 2)].into_iter().collect();
+    /// let bytes = map.freeze();
+    /// let frozen = FrozenRadixMap::<u32>::new(&bytes).unwrap();
+    /// assert_eq!(frozen.get_longest_common_prefix("fo"), None);
+    /// assert_eq!(frozen.get_longest_common_prefix("fooba"), Some(("foo".as_bytes(), 1)));
+    /// assert_eq!(frozen.get_longest_common_prefix("foobarbaz"), Some(("foobar".as_bytes(), 2)));
+    /// ```
+    pub fn get_longest_common_prefix<'b, Q>(&self, key: &'b Q) -> Option<(&'b K::Borrowed, V)>
+    where
+        Q: ?Sized + AsRef<K::Borrowed>,
+    {
+        let key = key.as_ref().as_bytes();
+        let (len, value) = self.trie.get_longest_common_prefix(key)?;
+        Some((K::Borrowed::from_bytes(&key[..len]), V::thaw(value)))
+    }
+
+    /// Returns an iterator over the entries whose keys are prefixes of `key`, shortest first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{FrozenRadixMap, RadixMap};
+    ///
+    /// let map: RadixMap<u32> = [("a", 1), ("ab", 2), ("abc", 3), ("b", 4)].into_iter().collect();
+    /// let bytes = map.freeze();
+    /// let frozen = FrozenRadixMap::<u32>::new(&bytes).unwrap();
+    /// assert_eq!(
+    ///     frozen.common_prefixes(b"abd").collect::<Vec<_>>(),
+    ///     [(&b"a"[..], 1), (b"ab", 2)]
+    /// );
+    /// ```
+    pub fn common_prefixes<'b, Q>(&self, key: &'b Q) -> CommonPrefixes<'a, 'b, K::Borrowed, V>
+    where
+        Q: ?Sized + AsRef<K::Borrowed>,
+    {
+        CommonPrefixes {
+            trie: self.trie,
+            key: key.as_ref().as_bytes(),
+            consumed: 0,
+            next: Some(self.trie.root()),
+            _key: PhantomData,
+            _value: PhantomData,
+        }
+    }
+
+    /// Gets an iterator over the entries of this map, sorted by key.
+    pub fn iter(&self) -> Iter<'a, K, V> {
+        Iter {
+            trie: self.trie,
+            stack: vec![(HEADER_LEN, 0)],
+            key: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Gets an iterator over the entries having the given prefix of this map, sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{StringFrozenRadixMap, StringRadixMap};
+    ///
+    /// let map: StringRadixMap<u32> = [("foo", 1), ("bar", 2), ("baz", 3)].into_iter().collect();
+    /// let bytes = map.freeze();
+    /// let frozen = StringFrozenRadixMap::<u32>::new(&bytes).unwrap();
+    /// assert_eq!(
+    ///     frozen.iter_prefix("ba").collect::<Vec<_>>(),
+    ///     [("bar".to_string(), 2), ("baz".to_string(), 3)]
+    /// );
+    /// ```
+    pub fn iter_prefix(&self, prefix: &K::Borrowed) -> Iter<'a, K, V> {
+        let prefix = prefix.as_bytes();
+        let (stack, key) = match self.trie.prefix_node(prefix) {
+            Some((offset, key_len)) => (vec![(offset, key_len)], prefix[..key_len].to_vec()),
+            None => (Vec::new(), Vec::new()),
+        };
+        Iter {
+            trie: self.trie,
+            stack,
+            key,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// An iterator over a frozen map's entries, sorted by key.
+pub struct Iter<'a, K, V> {
+    trie: FrozenTrie<'a>,
+    // node offsets still to visit, with the key length of their parent
+    stack: Vec<(usize, usize)>,
+    key: Vec<u8>,
+    _marker: PhantomData<(K, fn() -> V)>,
+}
+impl<K, V> fmt::Debug for Iter<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Iter").field("key", &self.key).finish()
+    }
+}
+impl<'a, K: Bytes, V: FrozenValue<'a>> Iterator for Iter<'a, K, V> {
+    type Item = (K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((offset, key_len)) = self.stack.pop() {
+            let node = self.trie.node(offset);
+            self.key.truncate(key_len);
+            self.key.extend_from_slice(node.label);
+            let key_len = self.key.len();
+            self.stack.extend(
+                (0..node.children_len())
+                    .rev()
+                    .map(|i| (node.child(i), key_len)),
+            );
+            if let Some(value) = node.value {
+                return Some((
+                    K::Borrowed::from_bytes(&self.key).to_owned(),
+                    V::thaw(value),
+                ));
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over the entries of a frozen map whose keys are prefixes of a given key.
+///
+/// This `struct` is created by the [`common_prefixes`](GenericFrozenRadixMap::common_prefixes)
+/// method on [`GenericFrozenRadixMap`].
+pub struct CommonPrefixes<'a, 'b, K: ?Sized, V> {
+    trie: FrozenTrie<'a>,
+    key: &'b [u8],
+    consumed: usize,
+    next: Option<RawNode<'a>>,
+    _key: PhantomData<&'b K>,
+    _value: PhantomData<fn() -> V>,
+}
+impl<K: ?Sized, V> fmt::Debug for CommonPrefixes<'_, '_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CommonPrefixes")
+            .field("key", &self.key)
+            .field("consumed", &self.consumed)
+            .finish()
+    }
+}
+impl<'a, 'b, K, V> Iterator for CommonPrefixes<'a, 'b, K, V>
+where
+    K: 'b + ?Sized + BorrowedBytes,
+    V: FrozenValue<'a>,
+{
+    type Item = (&'b K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.next.take() {
+            let rest = crate::strip_prefix(&self.key[self.consumed..], node.label)?;
+            self.consumed += node.label.len();
+            self.next = rest
+                .first()
+                .and_then(|&b| node.child_with_first(b))
+                .map(|offset| self.trie.node(offset));
+            if let Some(value) = node.value {
+                return Some((K::from_bytes(&self.key[..self.consumed]), V::thaw(value)));
+            }
+        }
+        None
+    }
+}
+
+/// Read-only radix tree based set with [`Vec<u8>`] as value, stored in a byte buffer.
+pub type FrozenRadixSet<'a> = GenericFrozenRadixSet<'a, Vec<u8>>;
+
+/// Read-only radix tree based set with [`String`] as value, stored in a byte buffer.
+pub type StringFrozenRadixSet<'a> = GenericFrozenRadixSet<'a, String>;
+
+/// Read-only radix tree based set, stored in a byte buffer written by
+/// [`GenericRadixSet::freeze`].
+#[derive(Debug)]
+pub struct GenericFrozenRadixSet<'a, T> {
+    map: GenericFrozenRadixMap<'a, T, ()>,
+}
+
+impl<T> Clone for GenericFrozenRadixSet<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for GenericFrozenRadixSet<'_, T> {}
+
+impl<'a, T> GenericFrozenRadixSet<'a, T> {
+    /// Opens a buffer written by [`GenericRadixSet::freeze`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{FrozenRadixSet, RadixSet};
+    ///
+    /// let set: RadixSet = ["foo", "bar"].into_iter().collect();
+    /// let bytes = set.freeze();
+    /// let frozen = FrozenRadixSet::new(&bytes).unwrap();
+    /// assert!(frozen.contains("foo"));
+    /// assert_eq!(frozen.get_longest_common_prefix("foobar"), Some("foo".as_bytes()));
+    /// ```
+    pub fn new(bytes: &'a [u8]) -> Result<Self, FrozenError>
+    where
+        T: Bytes,
+    {
+        Ok(GenericFrozenRadixSet {
+            map: GenericFrozenRadixMap::new(bytes)?,
+        })
+    }
+
+    /// Returns the number of elements in this set.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if this set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the underlying buffer.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.map.as_bytes()
+    }
+}
+
+impl<'a, T: Bytes> GenericFrozenRadixSet<'a, T> {
+    /// Returns `true` if this set contains a value.
+    pub fn contains<U: AsRef<T::Borrowed>>(&self, value: U) -> bool {
+        self.map.contains_key(value)
+    }
+
+    /// Finds the longest common prefix of `value` and the elements in this set.
+    pub fn get_longest_common_prefix<'b, U>(&self, value: &'b U) -> Option<&'b T::Borrowed>
+    where
+        U: ?Sized + AsRef<T::Borrowed>,
+    {
+        self.map.get_longest_common_prefix(value).map(|x| x.0)
+    }
+
+    /// Returns an iterator over the items that are prefixes of `value`, shortest first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{FrozenRadixSet, RadixSet};
+    ///
+    /// let set: RadixSet = ["a", "ab", "abc", "b"].into_iter().collect();
+    /// let bytes = set.freeze();
+    /// let frozen = FrozenRadixSet::new(&bytes).unwrap();
+    /// assert_eq!(frozen.common_prefixes(b"abd").collect::<Vec<_>>(), [&b"a"[..], b"ab"]);
+    /// ```
+    pub fn common_prefixes<'b, U>(&self, value: &'b U) -> SetCommonPrefixes<'a, 'b, T::Borrowed>
+    where
+        U: ?Sized + AsRef<T::Borrowed>,
+    {
+        SetCommonPrefixes(self.map.common_prefixes(value))
+    }
+
+    /// Gets an iterator over the contents of this set, in sorted order.
+    pub fn iter(&self) -> SetIter<'a, T> {
+        SetIter(self.map.iter())
+    }
+
+    /// Gets an iterator over the contents having the given prefix of this set, in sorted order.
+    pub fn iter_prefix(&self, prefix: &T::Borrowed) -> SetIter<'a, T> {
+        SetIter(self.map.iter_prefix(prefix))
+    }
+}
+
+/// An iterator over a frozen set's items, in sorted order.
+#[derive(Debug)]
+pub struct SetIter<'a, T>(Iter<'a, T, ()>);
+impl<T: Bytes> Iterator for SetIter<'_, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, _)| k)
+    }
+}
+
+/// An iterator over the items of a frozen set that are prefixes of a given value.
+///
+/// This `struct` is created by the [`common_prefixes`](GenericFrozenRadixSet::common_prefixes)
+/// method on [`GenericFrozenRadixSet`].
+#[derive(Debug)]
+pub struct SetCommonPrefixes<'a, 'b, T: ?Sized>(CommonPrefixes<'a, 'b, T, ()>);
+impl<'b, T: 'b + ?Sized + BorrowedBytes> Iterator for SetCommonPrefixes<'_, 'b, T> {
+    type Item = &'b T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, _)| k)
+    }
+}
+
+impl<K, V: FreezeValue> GenericRadixMap<K, V> {
+    /// Writes this map into a flat buffer that can be opened with
+    /// [`GenericFrozenRadixMap::new`], see the [`frozen`](crate::frozen) module.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value encodes to 4GiB or more.
+    pub fn freeze(&self) -> Vec<u8> {
+        freeze_tree(self.as_node(), self.len())
+    }
+}
+
+impl<T> GenericRadixSet<T> {
+    /// Writes this set into a flat buffer that can be opened with
+    /// [`GenericFrozenRadixSet::new`], see the [`frozen`](crate::frozen) module.
+    pub fn freeze(&self) -> Vec<u8> {
+        freeze_tree(self.as_node(), self.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RadixMap, RadixSet, StringRadixMap, StringRadixSet};
+
+    fn map() -> StringRadixMap<u64> {
+        let keys = crate::test::test_keys(if cfg!(miri) { 30 } else { 1000 }, 5);
+        let mut map: StringRadixMap<u64> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, k)| (k, i as u64))
+            .collect();
+        // leave valueless nodes behind
+        for k in keys.iter().step_by(3) {
+            map.remove(k);
+        }
+        map
+    }
+
+    #[test]
+    fn frozen_map_matches_map() {
+        let map = map();
+        let bytes = map.freeze();
+        let frozen = StringFrozenRadixMap::<u64>::new(&bytes).unwrap();
+        assert_eq!(frozen.len(), map.len());
+        let set_bytes = map.keys().collect::<StringRadixSet>().freeze();
+        let frozen_set = StringFrozenRadixSet::new(&set_bytes).unwrap();
+        assert!(frozen.iter().eq(map.iter().map(|(k, v)| (k, *v))));
+
+        let mut probes = map.keys().collect::<Vec<_>>();
+        probes.extend(["", "1", "14", "140", "1400000", "a", "zzz"].map(String::from));
+        probes.push("a".repeat(450));
+        probes.push(format!("{}3zz", "x".repeat(256)));
+        for probe in &probes {
+            assert_eq!(frozen.get(probe), map.get(probe).copied(), "{probe}");
+            assert_eq!(frozen.contains_key(probe), map.contains_key(probe));
+            assert_eq!(
+                frozen.get_longest_common_prefix(probe),
+                map.get_longest_common_prefix(probe).map(|(k, v)| (k, *v))
+            );
+            assert!(
+                frozen
+                    .common_prefixes(probe)
+                    .eq(map.common_prefixes(probe).map(|(k, v)| (k, *v)))
+            );
+            assert!(
+                frozen_set
+                    .common_prefixes(probe)
+                    .eq(map.common_prefixes(probe).map(|(k, _)| k))
+            );
+            let prefix = &probe[..probe.len().min(2)];
+            assert!(
+                frozen
+                    .iter_prefix(prefix)
+                    .eq(map.iter_prefix(prefix).map(|(k, v)| (k, *v))),
+                "{prefix}"
+            );
+            assert!(
+                frozen
+                    .iter_prefix(probe)
+                    .eq(map.iter_prefix(probe).map(|(k, v)| (k, *v))),
+                "{probe}"
+            );
+        }
+    }
+
+    #[test]
+    fn frozen_borrows_values() {
+        let map: RadixMap<String> = [("a", "x"), ("ab", "yy"), ("b", "")]
+            .into_iter()
+            .map(|(k, v)| (k, String::from(v)))
+            .collect();
+        let bytes = map.freeze();
+        let frozen = FrozenRadixMap::<&str>::new(&bytes).unwrap();
+        assert_eq!(frozen.get("ab"), Some("yy"));
+        assert_eq!(frozen.get("b"), Some(""));
+        let raw = FrozenRadixMap::<&[u8]>::new(&bytes).unwrap();
+        assert_eq!(raw.get("a"), Some(&b"x"[..]));
+
+        let empty = RadixSet::new().freeze();
+        let frozen = FrozenRadixSet::new(&empty).unwrap();
+        assert!(frozen.is_empty());
+        assert_eq!(frozen.iter().next(), None);
+        assert!(!frozen.contains(""));
+    }
+
+    #[test]
+    fn frozen_rejects_foreign_buffers() {
+        let bytes = map().freeze();
+        let mut bad = bytes.clone();
+        bad[0] = b'X';
+        assert_eq!(
+            FrozenRadixMap::<u64>::new(&bad).unwrap_err(),
+            FrozenError::InvalidMagic
+        );
+        bad = bytes.clone();
+        bad[4] = 9;
+        assert_eq!(
+            FrozenRadixMap::<u64>::new(&bad).unwrap_err(),
+            FrozenError::UnsupportedVersion(9)
+        );
+        assert_eq!(
+            FrozenRadixMap::<u64>::new(&bytes[..HEADER_LEN]).unwrap_err(),
+            FrozenError::Truncated
+        );
+        assert_eq!(
+            FrozenRadixMap::<u64>::new(&bytes[..bytes.len() - 1]).unwrap_err(),
+            FrozenError::Truncated
+        );
+        bad = bytes.clone();
+        bad.push(0);
+        assert_eq!(
+            FrozenRadixMap::<u64>::new(&bad).unwrap_err(),
+            FrozenError::Corrupt
+        );
+        bad = bytes.clone();
+        bad[5] ^= 1;
+        assert_eq!(
+            FrozenRadixMap::<u64>::new(&bad).unwrap_err(),
+            FrozenError::Corrupt
+        );
+    }
+
+    #[test]
+    fn frozen_damaged_buffers_never_panic() {
+        // damage that keeps the layout intact, e.g. in a label, may go unnoticed, but every
+        // damaged buffer is either rejected or read without panicking
+        fn damage(bytes: &[u8], mut f: impl FnMut(&[u8])) {
+            for i in 0..bytes.len() {
+                for flip in [1, 0x80, 0xff] {
+                    let mut bad = bytes.to_vec();
+                    bad[i] ^= flip;
+                    f(&bad);
+                }
+            }
+        }
+        let probes = ["", "a", "ab", "abc", "abd", "abx", "b", "c"];
+
+        let map: RadixMap<u32> = [("", 0), ("ab", 1), ("abc", 2), ("abd", 3), ("b", 4)]
+            .into_iter()
+            .collect();
+        damage(&map.freeze(), |bad| {
+            let Ok(frozen) = FrozenRadixMap::<&[u8]>::new(bad) else {
+                return;
+            };
+            assert_eq!(frozen.iter().count(), frozen.len());
+            for probe in probes {
+                frozen.get(probe);
+                frozen.get_longest_common_prefix(probe);
+                frozen.common_prefixes(probe).for_each(drop);
+                frozen.iter_prefix(probe.as_bytes()).for_each(drop);
+            }
+        });
+
+        let map: RadixMap<u64> = [("", 0), ("ab", 1), ("abc", 2), ("abd", 3), ("b", 4)]
+            .into_iter()
+            .collect();
+        damage(&map.freeze(), |bad| {
+            let Ok(frozen) = FrozenRadixMap::<u64>::new(bad) else {
+                return;
+            };
+            assert_eq!(frozen.iter().count(), frozen.len());
+            for probe in probes {
+                frozen.get(probe);
+                frozen.get_longest_common_prefix(probe);
+                frozen.common_prefixes(probe).for_each(drop);
+                frozen.iter_prefix(probe.as_bytes()).for_each(drop);
+            }
+        });
+
+        let map: StringRadixMap<&str> =
+            [("", ""), ("ab", "é"), ("abc", "x"), ("aé", "ab"), ("b", "")]
+                .into_iter()
+                .map(|(k, v)| (k.to_owned(), v))
+                .collect();
+        damage(&map.freeze(), |bad| {
+            let Ok(frozen) = StringFrozenRadixMap::<&str>::new(bad) else {
+                return;
+            };
+            assert_eq!(frozen.iter().count(), frozen.len());
+            for probe in probes {
+                frozen.get(probe);
+                frozen.get_longest_common_prefix(probe);
+                frozen.common_prefixes(probe).for_each(drop);
+                frozen.iter_prefix(probe).for_each(drop);
+            }
+        });
+    }
+
+    #[test]
+    fn frozen_rejects_mistyped_buffers() {
+        let bytes = RadixMap::from_iter([("a", 1u32), ("b", 2)]).freeze();
+        assert_eq!(
+            FrozenRadixMap::<u64>::new(&bytes).unwrap_err(),
+            FrozenError::InvalidValue
+        );
+        assert_eq!(
+            FrozenRadixMap::<()>::new(&bytes).unwrap_err(),
+            FrozenError::InvalidValue
+        );
+        assert!(FrozenRadixMap::<&[u8]>::new(&bytes).is_ok());
+
+        let bytes = RadixMap::from_iter([(&b"a"[..], &[0xff][..])]).freeze();
+        assert_eq!(
+            FrozenRadixMap::<&str>::new(&bytes).unwrap_err(),
+            FrozenError::InvalidValue
+        );
+
+        let bytes = RadixMap::from_iter([(&b"a\xff"[..], ())]).freeze();
+        assert_eq!(
+            StringFrozenRadixMap::<()>::new(&bytes).unwrap_err(),
+            FrozenError::InvalidKey
+        );
+        assert_eq!(
+            StringFrozenRadixSet::new(&bytes).unwrap_err(),
+            FrozenError::InvalidKey
+        );
+        assert!(FrozenRadixSet::new(&bytes).is_ok());
+    }
+}
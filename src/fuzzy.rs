@@ -0,0 +1,88 @@
+//! Edit distance search, computing one row of the Levenshtein matrix per symbol of the keys
+//! walked.
+//!
+//! A subtree is skipped as soon as every entry of the last row exceeds the bound, since rows
+//! below it can only be larger.
+
+use alloc::vec::Vec;
+
+use crate::walk::RowAutomaton;
+
+/// Matches the keys within `max` edits of a query, reporting their distance.
+#[derive(Debug)]
+pub(crate) struct Levenshtein {
+    query: Vec<u8>,
+    /// end offset of each symbol of the query
+    query_ends: Vec<usize>,
+    max: usize,
+    transpositions: bool,
+}
+
+impl Levenshtein {
+    pub(crate) fn new(
+        query: &[u8],
+        max: usize,
+        transpositions: bool,
+        symbol_len: fn(&[u8]) -> Option<usize>,
+    ) -> Self {
+        let mut query_ends = Vec::new();
+        let mut end = 0;
+        while let Some(len) = symbol_len(&query[end..]) {
+            end += len;
+            query_ends.push(end);
+        }
+        Levenshtein {
+            query: query.to_vec(),
+            query_ends,
+            max,
+            transpositions,
+        }
+    }
+
+    fn query_symbol(&self, i: usize) -> &[u8] {
+        let start = if i == 0 { 0 } else { self.query_ends[i - 1] };
+        &self.query[start..self.query_ends[i]]
+    }
+}
+
+impl RowAutomaton for Levenshtein {
+    type Cell = usize;
+    type Match = usize;
+
+    fn width(&self) -> usize {
+        self.query_ends.len() + 1
+    }
+
+    fn start(&self, rows: &mut Vec<usize>) {
+        rows.extend(0..self.width());
+    }
+
+    fn step(&self, rows: &mut Vec<usize>, symbol: &[u8], previous: Option<&[u8]>) -> bool {
+        let width = self.width();
+        let prev = rows.len() - width;
+        let mut min = rows[prev] + 1;
+        rows.push(min);
+        for j in 1..width {
+            let same = symbol == self.query_symbol(j - 1);
+            let mut d = (rows[prev + j] + 1)
+                .min(rows[prev + width + j - 1] + 1)
+                .min(rows[prev + j - 1] + usize::from(!same));
+            if self.transpositions
+                && j > 1
+                && previous.is_some_and(|previous| {
+                    symbol == self.query_symbol(j - 2) && previous == self.query_symbol(j - 1)
+                })
+            {
+                d = d.min(rows[prev - width + j - 2] + 1);
+            }
+            min = min.min(d);
+            rows.push(d);
+        }
+        min <= self.max
+    }
+
+    fn accept(&self, row: &[usize]) -> Option<usize> {
+        let distance = row[row.len() - 1];
+        (distance <= self.max).then_some(distance)
+    }
+}
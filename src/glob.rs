@@ -0,0 +1,198 @@
+//! Glob pattern matching, simulating the pattern's NFA one symbol of the keys walked at a time.
+//!
+//! A row holds the set of active pattern positions, so a subtree is skipped as soon as none are
+//! left, e.g. right after a literal mismatch.
+
+use alloc::vec::Vec;
+
+use crate::walk::RowAutomaton;
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    /// a single symbol
+    Literal(Vec<u8>),
+    /// `?`
+    Any,
+    /// `*`
+    Star,
+    /// `[...]`, as inclusive ranges of symbols
+    Class {
+        negated: bool,
+        ranges: Vec<(Vec<u8>, Vec<u8>)>,
+    },
+}
+
+impl Token {
+    /// Returns `true` if this token consumes `symbol`, `Star` aside.
+    fn matches(&self, symbol: &[u8]) -> bool {
+        match self {
+            Token::Literal(literal) => literal == symbol,
+            Token::Any => true,
+            Token::Star => false,
+            // UTF-8 preserves the order of chars, so ranges compare encoded symbols
+            Token::Class { negated, ranges } => {
+                ranges
+                    .iter()
+                    .any(|(lo, hi)| &lo[..] <= symbol && symbol <= &hi[..])
+                    != *negated
+            }
+        }
+    }
+}
+
+/// Matches the keys of a glob pattern.
+#[derive(Debug)]
+pub(crate) struct Glob {
+    tokens: Vec<Token>,
+}
+
+impl Glob {
+    pub(crate) fn new(pattern: &[u8], symbol_len: fn(&[u8]) -> Option<usize>) -> Self {
+        let mut symbols = Vec::new();
+        let mut start = 0;
+        while let Some(len) = symbol_len(&pattern[start..]) {
+            symbols.push(&pattern[start..start + len]);
+            start += len;
+        }
+
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < symbols.len() {
+            let token = match symbols[i] {
+                b"*" if tokens.last() == Some(&Token::Star) => {
+                    i += 1;
+                    continue;
+                }
+                b"*" => Token::Star,
+                b"?" => Token::Any,
+                b"\\" if i + 1 < symbols.len() => {
+                    i += 1;
+                    Token::Literal(symbols[i].to_vec())
+                }
+                b"[" => match parse_class(&symbols[i + 1..]) {
+                    Some((class, len)) => {
+                        i += len;
+                        class
+                    }
+                    None => Token::Literal(b"[".to_vec()),
+                },
+                symbol => Token::Literal(symbol.to_vec()),
+            };
+            tokens.push(token);
+            i += 1;
+        }
+        Glob { tokens }
+    }
+
+    /// Activates the positions reachable from active ones without consuming a symbol.
+    fn close(&self, row: &mut [bool]) {
+        for (j, token) in self.tokens.iter().enumerate() {
+            if row[j] && *token == Token::Star {
+                row[j + 1] = true;
+            }
+        }
+    }
+}
+
+/// Parses the class following a `[`, returning it with the number of symbols it spans, or
+/// `None` if it's never closed.
+fn parse_class(symbols: &[&[u8]]) -> Option<(Token, usize)> {
+    let negated = matches!(symbols.first(), Some(&(b"!" | b"^")));
+    let mut i = usize::from(negated);
+    let mut ranges = Vec::new();
+    loop {
+        let lo = match *symbols.get(i)? {
+            // a `]` right after the opening bracket is a member
+            b"]" if !ranges.is_empty() => return Some((Token::Class { negated, ranges }, i + 1)),
+            b"\\" if i + 1 < symbols.len() => {
+                i += 1;
+                symbols[i]
+            }
+            lo => lo,
+        };
+        let hi = match symbols.get(i + 1..i + 3) {
+            Some(&[b"-", hi]) if hi != b"]" => {
+                i += 2;
+                hi
+            }
+            _ => lo,
+        };
+        ranges.push((lo.to_vec(), hi.to_vec()));
+        i += 1;
+    }
+}
+
+impl RowAutomaton for Glob {
+    type Cell = bool;
+    type Match = ();
+
+    fn width(&self) -> usize {
+        self.tokens.len() + 1
+    }
+
+    fn start(&self, rows: &mut Vec<bool>) {
+        let start = rows.len();
+        rows.resize(start + self.width(), false);
+        rows[start] = true;
+        self.close(&mut rows[start..]);
+    }
+
+    fn step(&self, rows: &mut Vec<bool>, symbol: &[u8], _previous: Option<&[u8]>) -> bool {
+        let width = self.width();
+        let prev = rows.len() - width;
+        rows.resize(rows.len() + width, false);
+        let (before, row) = rows.split_at_mut(prev + width);
+        for (j, token) in self.tokens.iter().enumerate() {
+            if before[prev + j] {
+                if *token == Token::Star {
+                    row[j] = true;
+                } else if token.matches(symbol) {
+                    row[j + 1] = true;
+                }
+            }
+        }
+        self.close(row);
+        row.contains(&true)
+    }
+
+    fn accept(&self, row: &[bool]) -> Option<()> {
+        row[self.tokens.len()].then_some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RadixMap, StringRadixSet};
+
+    #[test]
+    fn special_symbols() {
+        let set: StringRadixSet = ["*", "?", "[", "[a]", "]", "-", "a", "a*b", "ab", "b", "é"]
+            .into_iter()
+            .collect();
+        let matches = |pattern| set.match_pattern(pattern).collect::<Vec<_>>();
+        assert_eq!(matches(r"\*"), ["*"]);
+        assert_eq!(matches(r"a\*b"), ["a*b"]);
+        assert_eq!(matches(r"\?"), ["?"]);
+        // unclosed classes are literal
+        assert_eq!(matches("[a*"), ["[a]"]);
+        assert_eq!(matches("[a]"), ["a"]);
+        assert_eq!(matches(r"\[a]"), ["[a]"]);
+        assert_eq!(matches("[[]"), ["["]);
+        assert_eq!(matches("[]]"), ["]"]);
+        assert_eq!(matches("[!]]"), ["*", "-", "?", "[", "a", "b", "é"]);
+        assert_eq!(matches("[a-]"), ["-", "a"]);
+        assert_eq!(matches("[^a-b*?[]"), ["-", "]", "é"]);
+        assert_eq!(matches(r"[\]]"), ["]"]);
+        assert_eq!(matches("**"), set.iter().collect::<Vec<_>>());
+        assert_eq!(matches(""), Vec::<String>::new());
+    }
+
+    #[test]
+    fn byte_keys_match_bytes() {
+        let map: RadixMap<_> = [("é", 1), ("e", 2), ("ée", 3)].into_iter().collect();
+        // `?` is a single byte, so it can't match a two byte char
+        assert!(map.match_pattern("?").map(|(_, v)| *v).eq([2]));
+        assert!(map.match_pattern("??").map(|(_, v)| *v).eq([1]));
+        assert!(map.match_pattern(b"\xC3*").map(|(_, v)| *v).eq([1, 3]));
+    }
+}
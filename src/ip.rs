@@ -0,0 +1,497 @@
+//! Maps keyed by IP prefixes, for routing and ACL tables.
+//!
+//! An [`IpPrefixMap`] keeps an IPv4 and an IPv6 [`BitRadixMap`], keyed by the network bits of
+//! each [`IpPrefix`]. Because keys are bit granular, a `/20` is stored as exactly 20 bits, and
+//! [`longest_match`](IpPrefixMap::longest_match) finds the most specific prefix containing an
+//! address in a single descent, without allocating.
+//!
+//! # Examples
+//!
+//! ```
+//! use fast_radix_trie::ip::{IpPrefix, IpPrefixMap};
+//! use std::net::Ipv4Addr;
+//!
+//! let mut routes = IpPrefixMap::new();
+//! routes.insert("0.0.0.0/0".parse::<IpPrefix>().unwrap(), "default");
+//! routes.insert("10.0.0.0/8".parse::<IpPrefix>().unwrap(), "corp");
+//! routes.insert("10.16.0.0/20".parse::<IpPrefix>().unwrap(), "lab");
+//!
+//! let (prefix, hop) = routes.longest_match(Ipv4Addr::new(10, 16, 15, 1)).unwrap();
+//! assert_eq!((prefix.to_string(), *hop), ("10.16.0.0/20".to_string(), "lab"));
+//! assert_eq!(routes.longest_match(Ipv4Addr::new(10, 16, 16, 1)).unwrap().1, &"corp");
+//! assert_eq!(routes.longest_match(Ipv4Addr::new(192, 0, 2, 1)).unwrap().1, &"default");
+//! ```
+use crate::bits::{BitRadixMap, BitStr, PrefixWalk};
+use core::{
+    fmt,
+    iter::FromIterator,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+/// An IP network: an address with the given number of leading network bits.
+///
+/// Host bits past the prefix length are always zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IpPrefix {
+    addr: IpAddr,
+    len: u8,
+}
+
+impl IpPrefix {
+    /// Makes a prefix of the first `len` bits of `addr`, clearing the bits after them.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `len` is longer than the address, 32 bits for IPv4 and 128 for IPv6.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::ip::{IpPrefix, IpPrefixError};
+    /// use std::net::{IpAddr, Ipv4Addr};
+    ///
+    /// let prefix = IpPrefix::new(IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3)), 8).unwrap();
+    /// assert_eq!(prefix.addr(), Ipv4Addr::new(10, 0, 0, 0));
+    /// assert_eq!(
+    ///     IpPrefix::new(Ipv4Addr::LOCALHOST.into(), 33),
+    ///     Err(IpPrefixError::InvalidLength(33))
+    /// );
+    /// ```
+    pub fn new(addr: IpAddr, len: u8) -> Result<Self, IpPrefixError> {
+        match addr {
+            IpAddr::V4(addr) => Self::v4(addr, len),
+            IpAddr::V6(addr) => Self::v6(addr, len),
+        }
+    }
+
+    /// Makes an IPv4 prefix, see [`new`](Self::new).
+    pub fn v4(addr: Ipv4Addr, len: u8) -> Result<Self, IpPrefixError> {
+        if len > 32 {
+            return Err(IpPrefixError::InvalidLength(len));
+        }
+        let mask = u32::MAX.checked_shl(32 - u32::from(len)).unwrap_or(0);
+        Ok(IpPrefix {
+            addr: IpAddr::V4(Ipv4Addr::from(u32::from(addr) & mask)),
+            len,
+        })
+    }
+
+    /// Makes an IPv6 prefix, see [`new`](Self::new).
+    pub fn v6(addr: Ipv6Addr, len: u8) -> Result<Self, IpPrefixError> {
+        if len > 128 {
+            return Err(IpPrefixError::InvalidLength(len));
+        }
+        let mask = u128::MAX.checked_shl(128 - u32::from(len)).unwrap_or(0);
+        Ok(IpPrefix {
+            addr: IpAddr::V6(Ipv6Addr::from(u128::from(addr) & mask)),
+            len,
+        })
+    }
+
+    /// Returns the network address, with every host bit cleared.
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Returns the number of network bits.
+    pub fn prefix_len(&self) -> u8 {
+        self.len
+    }
+
+    /// Returns `true` if `addr` lies within this network.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::ip::IpPrefix;
+    /// use std::net::Ipv4Addr;
+    ///
+    /// let prefix: IpPrefix = "192.168.0.0/16".parse().unwrap();
+    /// assert!(prefix.contains(Ipv4Addr::new(192, 168, 7, 1)));
+    /// assert!(!prefix.contains(Ipv4Addr::new(192, 169, 0, 1)));
+    /// ```
+    pub fn contains<A: Into<IpAddr>>(&self, addr: A) -> bool {
+        // an address of the other family never masks down to this prefix
+        IpPrefix::new(addr.into(), self.len) == Ok(*self)
+    }
+
+    /// the network bits, most significant first
+    fn bits(&self) -> NetworkBits {
+        let mut octets = [0; 16];
+        match self.addr {
+            IpAddr::V4(addr) => octets[..4].copy_from_slice(&addr.octets()),
+            IpAddr::V6(addr) => octets = addr.octets(),
+        }
+        NetworkBits {
+            octets,
+            len: self.len,
+        }
+    }
+
+    /// inverse of [`bits`](Self::bits)
+    fn from_bits(v6: bool, bits: BitStr<'_>) -> Self {
+        // keys in a map are never longer than the address, so neither is the prefix
+        let len = bits.len() as u8;
+        let mut octets = [0; 16];
+        octets
+            .iter_mut()
+            .zip(bits.bytes())
+            .for_each(|(o, b)| *o = b);
+        let addr = if v6 {
+            IpAddr::V6(octets.into())
+        } else {
+            IpAddr::V4(Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]))
+        };
+        IpPrefix { addr, len }
+    }
+}
+
+/// The network bits of a prefix, held inline so lookups don't allocate.
+///
+/// Bits past `len` are zero, so the derived order is the order of the bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct NetworkBits {
+    octets: [u8; 16],
+    len: u8,
+}
+
+impl NetworkBits {
+    fn as_bit_str(&self) -> BitStr<'_> {
+        BitStr::new(&self.octets, usize::from(self.len))
+    }
+}
+
+impl From<IpAddr> for IpPrefix {
+    /// The prefix holding just `addr`, a `/32` or `/128`.
+    fn from(addr: IpAddr) -> Self {
+        let len = if addr.is_ipv4() { 32 } else { 128 };
+        IpPrefix { addr, len }
+    }
+}
+
+impl From<Ipv4Addr> for IpPrefix {
+    fn from(addr: Ipv4Addr) -> Self {
+        IpPrefix::from(IpAddr::V4(addr))
+    }
+}
+
+impl From<Ipv6Addr> for IpPrefix {
+    fn from(addr: Ipv6Addr) -> Self {
+        IpPrefix::from(IpAddr::V6(addr))
+    }
+}
+
+impl fmt::Display for IpPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.len)
+    }
+}
+
+impl FromStr for IpPrefix {
+    type Err = IpPrefixError;
+
+    /// Parses `addr/len`, or a bare address as a host prefix. Host bits are cleared.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, len) = match s.split_once('/') {
+            Some((addr, len)) => (addr, Some(len)),
+            None => (s, None),
+        };
+        let addr = addr.parse::<IpAddr>().map_err(|_| IpPrefixError::Invalid)?;
+        match len {
+            Some(len) => IpPrefix::new(addr, len.parse().map_err(|_| IpPrefixError::Invalid)?),
+            None => Ok(IpPrefix::from(addr)),
+        }
+    }
+}
+
+/// Errors raised when making or parsing an [`IpPrefix`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpPrefixError {
+    /// The prefix length is longer than the address.
+    InvalidLength(u8),
+    /// The string is not an address optionally followed by `/` and a prefix length.
+    Invalid,
+}
+
+impl fmt::Display for IpPrefixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IpPrefixError::InvalidLength(len) => {
+                write!(f, "prefix length {len} is longer than the address")
+            }
+            IpPrefixError::Invalid => f.write_str("invalid IP prefix syntax"),
+        }
+    }
+}
+
+impl core::error::Error for IpPrefixError {}
+
+/// Map from IP prefixes to values, with longest prefix match lookups.
+///
+/// See the [`ip`](crate::ip) module.
+#[derive(Debug, Clone)]
+pub struct IpPrefixMap<V> {
+    v4: BitRadixMap<V>,
+    v6: BitRadixMap<V>,
+}
+
+impl<V> IpPrefixMap<V> {
+    /// Makes a new empty map.
+    pub fn new() -> Self {
+        IpPrefixMap {
+            v4: BitRadixMap::new(),
+            v6: BitRadixMap::new(),
+        }
+    }
+
+    /// Returns the number of prefixes in this map, of both families.
+    pub fn len(&self) -> usize {
+        self.v4.len() + self.v6.len()
+    }
+
+    /// Returns `true` if this map contains no prefixes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn family(&self, addr: IpAddr) -> &BitRadixMap<V> {
+        if addr.is_ipv4() { &self.v4 } else { &self.v6 }
+    }
+
+    fn family_mut(&mut self, addr: IpAddr) -> &mut BitRadixMap<V> {
+        if addr.is_ipv4() {
+            &mut self.v4
+        } else {
+            &mut self.v6
+        }
+    }
+
+    /// Inserts a prefix, returning the value it had before, if any.
+    pub fn insert<P: Into<IpPrefix>>(&mut self, prefix: P, value: V) -> Option<V> {
+        let prefix = prefix.into();
+        self.family_mut(prefix.addr)
+            .insert(prefix.bits().as_bit_str(), value)
+    }
+
+    /// Returns the value of exactly this prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::ip::{IpPrefix, IpPrefixMap};
+    ///
+    /// let prefix: IpPrefix = "2001:db8::/32".parse().unwrap();
+    /// let mut map = IpPrefixMap::new();
+    /// map.insert(prefix, 1);
+    /// assert_eq!(map.get(prefix), Some(&1));
+    /// assert_eq!(map.get("2001:db8::/48".parse::<IpPrefix>().unwrap()), None);
+    /// ```
+    pub fn get<P: Into<IpPrefix>>(&self, prefix: P) -> Option<&V> {
+        let prefix = prefix.into();
+        self.family(prefix.addr).get(prefix.bits().as_bit_str())
+    }
+
+    /// Returns a mutable reference to the value of exactly this prefix.
+    pub fn get_mut<P: Into<IpPrefix>>(&mut self, prefix: P) -> Option<&mut V> {
+        let prefix = prefix.into();
+        self.family_mut(prefix.addr)
+            .get_mut(prefix.bits().as_bit_str())
+    }
+
+    /// Returns `true` if this map contains exactly this prefix.
+    pub fn contains_key<P: Into<IpPrefix>>(&self, prefix: P) -> bool {
+        self.get(prefix).is_some()
+    }
+
+    /// Removes a prefix, returning its value.
+    pub fn remove<P: Into<IpPrefix>>(&mut self, prefix: P) -> Option<V> {
+        let prefix = prefix.into();
+        self.family_mut(prefix.addr)
+            .remove(prefix.bits().as_bit_str())
+    }
+
+    /// Returns the most specific prefix containing `addr`, along with its value.
+    pub fn longest_match<A: Into<IpAddr>>(&self, addr: A) -> Option<(IpPrefix, &V)> {
+        let addr = addr.into();
+        let bits = IpPrefix::from(addr).bits();
+        let (matched, value) = self
+            .family(addr)
+            .get_longest_common_prefix(bits.as_bit_str())?;
+        Some((IpPrefix::from_bits(addr.is_ipv6(), matched), value))
+    }
+
+    /// Mutable version of [`longest_match`](Self::longest_match).
+    pub fn longest_match_mut<A: Into<IpAddr>>(&mut self, addr: A) -> Option<(IpPrefix, &mut V)> {
+        let addr = addr.into();
+        let bits = IpPrefix::from(addr).bits();
+        let (matched, value) = self
+            .family_mut(addr)
+            .get_longest_common_prefix_mut(bits.as_bit_str())?;
+        Some((IpPrefix::from_bits(addr.is_ipv6(), matched), value))
+    }
+
+    /// Gets an iterator over every prefix containing `addr`, least specific first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::ip::{IpPrefix, IpPrefixMap};
+    /// use std::net::Ipv4Addr;
+    ///
+    /// let map: IpPrefixMap<_> = ["10.0.0.0/8", "10.1.0.0/16", "10.2.0.0/16"]
+    ///     .into_iter()
+    ///     .map(|p| (p.parse::<IpPrefix>().unwrap(), p))
+    ///     .collect();
+    /// assert_eq!(
+    ///     map.matches(Ipv4Addr::new(10, 1, 2, 3)).map(|(_, v)| *v).collect::<Vec<_>>(),
+    ///     ["10.0.0.0/8", "10.1.0.0/16"]
+    /// );
+    /// ```
+    pub fn matches<A: Into<IpAddr>>(&self, addr: A) -> Matches<'_, V> {
+        let addr = addr.into();
+        Matches {
+            walk: self.family(addr).prefix_walk(),
+            bits: IpPrefix::from(addr).bits(),
+            v6: addr.is_ipv6(),
+        }
+    }
+
+    /// Gets an iterator over the prefixes of this map, IPv4 first, each family sorted by
+    /// address then length.
+    pub fn iter(&self) -> impl Iterator<Item = (IpPrefix, &V)> {
+        let v4 = self
+            .v4
+            .iter()
+            .map(|(bits, v)| (IpPrefix::from_bits(false, bits.as_bit_str()), v));
+        let v6 = self
+            .v6
+            .iter()
+            .map(|(bits, v)| (IpPrefix::from_bits(true, bits.as_bit_str()), v));
+        v4.chain(v6)
+    }
+}
+
+impl<V> Default for IpPrefixMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Into<IpPrefix>, V> FromIterator<(P, V)> for IpPrefixMap<V> {
+    fn from_iter<I: IntoIterator<Item = (P, V)>>(iter: I) -> Self {
+        let mut map = IpPrefixMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<P: Into<IpPrefix>, V> Extend<(P, V)> for IpPrefixMap<V> {
+    fn extend<I: IntoIterator<Item = (P, V)>>(&mut self, iter: I) {
+        for (prefix, value) in iter {
+            self.insert(prefix, value);
+        }
+    }
+}
+
+/// An iterator over the prefixes of an [`IpPrefixMap`] containing an address, least specific
+/// first.
+///
+/// This `struct` is created by the [`matches`](IpPrefixMap::matches) method on [`IpPrefixMap`].
+pub struct Matches<'a, V> {
+    walk: PrefixWalk<'a, V>,
+    // all the address bits, since the walk borrows them one step at a time
+    bits: NetworkBits,
+    v6: bool,
+}
+
+impl<V> fmt::Debug for Matches<'_, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Matches")
+            .field("bits", &self.bits.as_bit_str())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, V> Iterator for Matches<'a, V> {
+    type Item = (IpPrefix, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let bits = self.bits.as_bit_str();
+        let (len, value) = self.walk.next_match(bits)?;
+        Some((IpPrefix::from_bits(self.v6, bits.prefix(len)), value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn prefixes_parse_and_mask() {
+        let prefix: IpPrefix = "10.1.2.3/12".parse().unwrap();
+        assert_eq!(prefix.to_string(), "10.0.0.0/12");
+        assert_eq!(
+            "fe80::1/10".parse::<IpPrefix>().unwrap().to_string(),
+            "fe80::/10"
+        );
+        assert_eq!("::/0".parse::<IpPrefix>().unwrap().prefix_len(), 0);
+        assert_eq!("1.2.3.4".parse::<IpPrefix>().unwrap().prefix_len(), 32);
+        assert_eq!(
+            "1.2.3.4/33".parse::<IpPrefix>(),
+            Err(IpPrefixError::InvalidLength(33))
+        );
+        assert_eq!("1.2.3/8".parse::<IpPrefix>(), Err(IpPrefixError::Invalid));
+        assert_eq!("1.2.3.4/x".parse::<IpPrefix>(), Err(IpPrefixError::Invalid));
+    }
+
+    #[test]
+    fn longest_match_matches_linear_scan() {
+        let mut rng = rand::rng();
+        let mut prefixes = Vec::new();
+        for i in 0..if cfg!(miri) { 30 } else { 2000 } {
+            // few distinct high bits, so prefixes nest
+            let v4 = Ipv4Addr::from(rng.random::<u32>() & 0xF0F0_FFFF);
+            let v6 = Ipv6Addr::from(rng.random::<u128>() & !(u128::MAX >> 8 & !0xFFFF));
+            prefixes.push((IpPrefix::v4(v4, rng.random_range(0..=32)).unwrap(), i));
+            prefixes.push((IpPrefix::v6(v6, rng.random_range(0..=128)).unwrap(), i));
+        }
+        let map = prefixes.iter().copied().collect::<IpPrefixMap<_>>();
+
+        let mut expected = prefixes.clone();
+        expected.sort_by_key(|(p, _)| (p.addr().is_ipv6(), p.bits()));
+        expected.dedup_by(|b, a| {
+            // later inserts win, like the map
+            if a.0 == b.0 {
+                a.1 = a.1.max(b.1);
+            }
+            a.0 == b.0
+        });
+        assert_eq!(map.len(), expected.len());
+        assert!(
+            map.iter()
+                .map(|(p, v)| (p, *v))
+                .eq(expected.iter().copied())
+        );
+
+        for _ in 0..if cfg!(miri) { 30 } else { 2000 } {
+            let addr = if rng.random() {
+                IpAddr::V4(Ipv4Addr::from(rng.random::<u32>() & 0xF0F0_FFFF))
+            } else {
+                IpAddr::V6(Ipv6Addr::from(
+                    rng.random::<u128>() & !(u128::MAX >> 8 & !0xFFFF),
+                ))
+            };
+            let covering = expected
+                .iter()
+                .filter(|(p, _)| p.contains(addr))
+                .map(|(p, v)| (*p, v))
+                .collect::<Vec<_>>();
+            let longest = covering.iter().max_by_key(|(p, _)| p.prefix_len()).copied();
+            assert_eq!(map.longest_match(addr), longest, "{addr}");
+            let mut by_len = covering.clone();
+            by_len.sort_by_key(|(p, _)| p.prefix_len());
+            assert!(map.matches(addr).eq(by_len), "{addr}");
+        }
+    }
+}
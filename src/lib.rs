@@ -80,14 +80,42 @@ extern crate alloc;
 use alloc::{borrow::ToOwned, string::String, vec::Vec};
 use core::cmp::Ordering;
 
+#[cfg(feature = "concurrent")]
+pub use concurrent::{ConcurrentRadixMap, GenericConcurrentRadixMap, StringConcurrentRadixMap};
+pub use frozen::{
+    FrozenRadixMap, FrozenRadixSet, GenericFrozenRadixMap, GenericFrozenRadixSet,
+    StringFrozenRadixMap, StringFrozenRadixSet,
+};
 pub use map::{GenericRadixMap, RadixMap, StringRadixMap};
+#[cfg(feature = "persistent")]
+pub use persistent::{GenericPersistentRadixMap, PersistentRadixMap, StringPersistentRadixMap};
 pub use set::{GenericRadixSet, RadixSet, StringRadixSet};
-
+pub use stats::MemoryStats;
+
+pub mod automaton;
+pub mod bits;
+#[cfg(feature = "concurrent")]
+pub mod concurrent;
+pub mod frozen;
+pub mod ip;
 pub mod map;
+#[cfg(feature = "persistent")]
+pub mod persistent;
+#[cfg(feature = "rayon")]
+pub mod rayon;
+pub mod scanner;
+#[cfg(feature = "serde")]
+pub mod serde;
 pub mod set;
+#[cfg(feature = "std")]
+pub mod snapshot;
+pub mod stats;
 
+mod fuzzy;
+mod glob;
 mod node_common;
 mod tree;
+mod walk;
 
 #[cfg(feature = "realloc")]
 pub mod node;
@@ -137,6 +165,15 @@ pub trait BorrowedBytes {
     fn is_empty(&self) -> bool {
         self.as_bytes().is_empty()
     }
+
+    /// Returns the length in bytes of the symbol `bytes` starts with, or `None` if `bytes` is
+    /// empty or ends partway through it.
+    ///
+    /// Symbols are the unit edit distances are measured in, see
+    /// [`fuzzy_search`](crate::GenericRadixMap::fuzzy_search). Defaults to single bytes.
+    fn symbol_len(bytes: &[u8]) -> Option<usize> {
+        (!bytes.is_empty()).then_some(1)
+    }
 }
 
 impl BorrowedBytes for [u8] {
@@ -179,6 +216,17 @@ impl BorrowedBytes for str {
     fn from_bytes(bytes: &[u8]) -> &Self {
         core::str::from_utf8(bytes).expect("unreachable")
     }
+
+    /// Symbols are chars.
+    fn symbol_len(bytes: &[u8]) -> Option<usize> {
+        let len = match bytes.first()? {
+            0..=0x7F => 1,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            _ => 4,
+        };
+        (bytes.len() >= len).then_some(len)
+    }
 }
 
 /// uses memchr to strip the prefix from the haystack if it is a valid prefix
@@ -240,6 +288,19 @@ pub fn longest_common_prefix(a: &[u8], b: &[u8]) -> (usize, Option<Ordering>) {
 mod test {
     use super::*;
 
+    /// Keys for tests, in random order: the empty key, keys long enough to be chained through
+    /// several nodes, `numbers` short keys with a shared fanout and `long` keys sharing a 256 byte
+    /// prefix.
+    pub(crate) fn test_keys(numbers: usize, long: usize) -> Vec<String> {
+        use rand::seq::SliceRandom;
+
+        let mut keys = vec![String::new(), "a".repeat(300), "a".repeat(600)];
+        keys.extend((0..numbers).map(|i| format!("{}", i * 7)));
+        keys.extend((0..long).map(|i| format!("{}{}", "x".repeat(256), i)));
+        keys.shuffle(&mut rand::rng());
+        keys
+    }
+
     #[test]
     fn test_longest_common_prefix() {
         // short common prefix
@@ -1,8 +1,19 @@
 //! A map based on a patricia tree.
+use crate::automaton::{Automaton, Bytewise};
+use crate::fuzzy::Levenshtein;
+use crate::glob::Glob;
+use crate::node_common::{NodeEntry, NodeSlot, some};
+use crate::scanner::Scanner;
 use crate::tree::{self, RadixTrie};
-use crate::{BorrowedBytes, Bytes};
+use crate::walk::Walk;
+use crate::{BorrowedBytes, Bytes, Node};
 use alloc::{borrow::ToOwned, string::String, vec::Vec};
-use core::{iter::FromIterator, marker::PhantomData};
+use core::{
+    fmt,
+    iter::FromIterator,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+};
 
 /// Radix tree based map with [`Vec<u8>`] as key.
 pub type RadixMap<V> = GenericRadixMap<Vec<u8>, V>;
@@ -102,11 +113,26 @@ impl<K, V> GenericRadixMap<K, V> {
         }
     }
 
+    /// create map from a root node holding `len` values
+    #[cfg(any(feature = "std", feature = "serde"))]
+    pub(crate) fn from_root(root: crate::Node<V>, len: usize) -> Self {
+        Self {
+            tree: RadixTrie::from_root(root, len),
+            _key: PhantomData,
+        }
+    }
+
     /// get ref to root node  (NOTE: calling methods on node directly seriously mess up your tree)
     pub fn as_node(&self) -> &crate::Node<V> {
         self.tree.root()
     }
 
+    /// get mut ref to root node, the tree's shape must be left untouched
+    #[cfg(feature = "rayon")]
+    pub(crate) fn root_mut(&mut self) -> &mut crate::Node<V> {
+        self.tree.root_mut()
+    }
+
     /// get root node out of map (NOTE: calling methods on node directly seriously mess up your tree)
     pub fn into_node(self) -> crate::Node<V> {
         self.tree.into_root()
@@ -278,6 +304,192 @@ impl<K: Bytes, V> GenericRadixMap<K, V> {
         self.tree.remove(key.as_ref())
     }
 
+    /// Returns the first key-value pair in the map, the key being the minimum key in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    ///
+    /// let mut map = RadixMap::new();
+    /// assert_eq!(map.first_key_value(), None);
+    /// map.insert("foo", 1);
+    /// map.insert("bar", 2);
+    /// assert_eq!(map.first_key_value(), Some((Vec::from("bar"), &2)));
+    /// ```
+    pub fn first_key_value(&self) -> Option<(K, &V)> {
+        let (key, value) = self.tree.first()?;
+        Some((Self::owned_key(&key), value))
+    }
+
+    /// Returns the last key-value pair in the map, the key being the maximum key in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    ///
+    /// let mut map = RadixMap::new();
+    /// assert_eq!(map.last_key_value(), None);
+    /// map.insert("foo", 1);
+    /// map.insert("foobar", 2);
+    /// map.insert("bar", 3);
+    /// assert_eq!(map.last_key_value(), Some((Vec::from("foobar"), &2)));
+    /// ```
+    pub fn last_key_value(&self) -> Option<(K, &V)> {
+        let (key, value) = self.tree.last()?;
+        Some((Self::owned_key(&key), value))
+    }
+
+    /// Removes and returns the first element in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    ///
+    /// let mut map = RadixMap::new();
+    /// map.insert("foo", 1);
+    /// map.insert("bar", 2);
+    /// assert_eq!(map.pop_first(), Some((Vec::from("bar"), 2)));
+    /// assert_eq!(map.pop_first(), Some((Vec::from("foo"), 1)));
+    /// assert_eq!(map.pop_first(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let key = self.tree.first()?.0;
+        let value = some!(self.tree.remove(&key[..]));
+        Some((Self::owned_key(&key), value))
+    }
+
+    /// Removes and returns the last element in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    ///
+    /// let mut map = RadixMap::new();
+    /// map.insert("foo", 1);
+    /// map.insert("bar", 2);
+    /// assert_eq!(map.pop_last(), Some((Vec::from("foo"), 1)));
+    /// assert_eq!(map.pop_last(), Some((Vec::from("bar"), 2)));
+    /// assert_eq!(map.pop_last(), None);
+    /// ```
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let key = self.tree.last()?.0;
+        let value = some!(self.tree.remove(&key[..]));
+        Some((Self::owned_key(&key), value))
+    }
+
+    /// Returns the entry with the greatest key less than or equal to `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let map: StringRadixMap<_> =
+    ///     vec![("foo", 1), ("foobar", 2), ("qux", 3)].into_iter().collect();
+    /// assert_eq!(map.floor("foo"), Some(("foo".into(), &1)));
+    /// assert_eq!(map.floor("foobaz"), Some(("foobar".into(), &2)));
+    /// assert_eq!(map.floor("goo"), Some(("foobar".into(), &2)));
+    /// assert_eq!(map.floor("bar"), None);
+    /// ```
+    pub fn floor<Q: AsRef<K::Borrowed>>(&self, key: Q) -> Option<(K, &V)> {
+        let (key, value) = self.tree.floor(key.as_ref(), true)?;
+        Some((Self::owned_key(&key), value))
+    }
+
+    /// Returns the entry with the smallest key greater than or equal to `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let map: StringRadixMap<_> =
+    ///     vec![("foo", 1), ("foobar", 2), ("qux", 3)].into_iter().collect();
+    /// assert_eq!(map.ceiling("foo"), Some(("foo".into(), &1)));
+    /// assert_eq!(map.ceiling("foob"), Some(("foobar".into(), &2)));
+    /// assert_eq!(map.ceiling("goo"), Some(("qux".into(), &3)));
+    /// assert_eq!(map.ceiling("quxx"), None);
+    /// ```
+    pub fn ceiling<Q: AsRef<K::Borrowed>>(&self, key: Q) -> Option<(K, &V)> {
+        let (key, value) = self.tree.ceiling(key.as_ref(), true)?;
+        Some((Self::owned_key(&key), value))
+    }
+
+    /// Returns the entry with the greatest key strictly less than `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let map: StringRadixMap<_> =
+    ///     vec![("foo", 1), ("foobar", 2), ("qux", 3)].into_iter().collect();
+    /// assert_eq!(map.predecessor("foobar"), Some(("foo".into(), &1)));
+    /// assert_eq!(map.predecessor("foo"), None);
+    /// ```
+    pub fn predecessor<Q: AsRef<K::Borrowed>>(&self, key: Q) -> Option<(K, &V)> {
+        let (key, value) = self.tree.floor(key.as_ref(), false)?;
+        Some((Self::owned_key(&key), value))
+    }
+
+    /// Returns the entry with the smallest key strictly greater than `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let map: StringRadixMap<_> =
+    ///     vec![("foo", 1), ("foobar", 2), ("qux", 3)].into_iter().collect();
+    /// assert_eq!(map.successor("foo"), Some(("foobar".into(), &2)));
+    /// assert_eq!(map.successor("qux"), None);
+    /// ```
+    pub fn successor<Q: AsRef<K::Borrowed>>(&self, key: Q) -> Option<(K, &V)> {
+        let (key, value) = self.tree.ceiling(key.as_ref(), false)?;
+        Some((Self::owned_key(&key), value))
+    }
+
+    fn owned_key(bytes: &[u8]) -> K {
+        K::Borrowed::from_bytes(bytes).to_owned()
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place manipulation.
+    ///
+    /// The tree is only descended once, no matter which operations are performed on the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let mut counts = StringRadixMap::new();
+    /// for word in ["foo", "bar", "foo", "baz", "foo"] {
+    ///     *counts.entry(word).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(counts.get("foo"), Some(&3));
+    /// assert_eq!(counts.get("bar"), Some(&1));
+    /// assert_eq!(counts.len(), 3);
+    /// ```
+    pub fn entry<'k, Q>(&mut self, key: &'k Q) -> Entry<'_, 'k, K, V>
+    where
+        Q: ?Sized + AsRef<K::Borrowed>,
+    {
+        let key = key.as_ref();
+        match self.tree.entry(key) {
+            (NodeEntry::Occupied(slot), len) => Entry::Occupied(OccupiedEntry { key, slot, len }),
+            (NodeEntry::Vacant { node, offset }, len) => Entry::Vacant(VacantEntry {
+                key,
+                node,
+                offset,
+                len,
+            }),
+        }
+    }
+
     /// Returns an iterator that collects all entries in the map up to a certain key.
     ///
     /// # Example
@@ -366,6 +578,29 @@ impl<K: Bytes, V> GenericRadixMap<K, V> {
             .common_prefixes_owned(key)
             .filter_map(|(_, n)| n.value())
     }
+
+    /// Compiles the keys of this map into a [`Scanner`] that finds every occurrence of every
+    /// key in a text, in time linear in the length of the text plus the number of occurrences.
+    ///
+    /// Unlike calling [`common_prefixes`](Self::common_prefixes) at every offset, a scan never
+    /// reads a byte of the text twice. Compiling takes time and memory linear in the number of
+    /// distinct prefixes of the keys. See the [`scanner`](crate::scanner) module.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let map: StringRadixMap<_> =
+    ///     vec![("error", 1), ("err", 2), ("warn", 3)].into_iter().collect();
+    /// let scanner = map.compile();
+    /// let log = "warn: error, error";
+    /// assert_eq!(scanner.scan(log).map(|(start, end, _)| &log[start..end]).collect::<Vec<_>>(),
+    ///            ["warn", "err", "error", "err", "error"]);
+    /// ```
+    pub fn compile(&self) -> Scanner<'_, V> {
+        Scanner::new(self.tree.root())
+    }
     /// Splits the map into two at the given prefix.
     ///
     /// The returned map contains all the entries of which keys are prefixed by `prefix`.
@@ -397,8 +632,233 @@ impl<K: Bytes, V> GenericRadixMap<K, V> {
         }
     }
 
+    /// Removes every entry whose key starts with `prefix`, returning the number of entries
+    /// removed.
+    ///
+    /// Unlike dropping the result of [`split_by_prefix`](Self::split_by_prefix), no new map is
+    /// built for the removed entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let mut map: StringRadixMap<_> =
+    ///     [("rust", 1), ("ruby", 2), ("python", 3)].into_iter().collect();
+    /// assert_eq!(map.remove_prefix("ru"), 2);
+    /// assert_eq!(map.remove_prefix("ru"), 0);
+    /// assert_eq!(map.keys().collect::<Vec<_>>(), ["python"]);
+    /// ```
+    pub fn remove_prefix<Q: AsRef<K::Borrowed>>(&mut self, prefix: Q) -> usize {
+        self.tree.remove_prefix(prefix.as_ref().as_bytes())
+    }
+
+    /// Retains only the entries whose key starts with `prefix`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let mut map: StringRadixMap<_> =
+    ///     [("rust", 1), ("ruby", 2), ("python", 3)].into_iter().collect();
+    /// map.retain_prefix("ru");
+    /// assert_eq!(map.keys().collect::<Vec<_>>(), ["ruby", "rust"]);
+    /// ```
+    pub fn retain_prefix<Q: AsRef<K::Borrowed>>(&mut self, prefix: Q) {
+        self.tree = self.tree.split_by_prefix(prefix.as_ref());
+    }
+
+    /// Retains only the entries for which `f` returns `true`, visiting them in key order.
+    ///
+    /// The trie is walked once, removing emptied nodes and merging the rest as it goes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let mut map: StringRadixMap<_> = (0..8).map(|i| (i.to_string(), i)).collect();
+    /// map.retain(|_, v| *v % 2 == 0);
+    /// assert_eq!(map.keys().collect::<Vec<_>>(), ["0", "2", "4", "6"]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K::Borrowed, &mut V) -> bool,
+    {
+        self.tree.extract_if(
+            |key, value| !f(K::Borrowed::from_bytes(key), value),
+            |_, _| (),
+        );
+    }
+
+    /// Removes every entry for which `pred` returns `true` and returns them in key order.
+    ///
+    /// Unlike `BTreeMap::extract_if`, the matching entries are removed in a single pass up front,
+    /// so they are gone even if the returned iterator is dropped early.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let mut map: StringRadixMap<_> = (0..8).map(|i| (i.to_string(), i)).collect();
+    /// let odds = map.extract_if(|_, v| *v % 2 == 1).collect::<Vec<_>>();
+    /// assert_eq!(odds, [("1".to_owned(), 1), ("3".to_owned(), 3), ("5".to_owned(), 5), ("7".to_owned(), 7)]);
+    /// assert_eq!(map.len(), 4);
+    /// ```
+    pub fn extract_if<F>(&mut self, mut pred: F) -> ExtractIf<K, V>
+    where
+        F: FnMut(&K::Borrowed, &mut V) -> bool,
+    {
+        let mut entries = Vec::new();
+        self.tree.extract_if(
+            |key, value| pred(K::Borrowed::from_bytes(key), value),
+            |key, value| entries.push((K::Borrowed::from_bytes(key).to_owned(), value)),
+        );
+        ExtractIf {
+            entries: entries.into_iter(),
+        }
+    }
+
+    /// Clears the map, returning all entries as an iterator sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let mut map: StringRadixMap<_> = [("a", 1), ("b", 2)].into_iter().collect();
+    /// assert_eq!(map.drain().collect::<Vec<_>>(), [("a".to_owned(), 1), ("b".to_owned(), 2)]);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> IntoIter<K, V> {
+        core::mem::take(self).into_iter()
+    }
+
+    /// Moves all entries of `other` into `self` with `prefix` prepended to their keys.
+    ///
+    /// `other`'s root is relabeled rather than re-inserting every key, so this is the inverse of
+    /// [`split_by_prefix`](Self::split_by_prefix) even when the prefix changes. Entries of `other`
+    /// replace existing entries of `self` with the same key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let mut map = StringRadixMap::new();
+    /// map.insert("tenant-a/config", 1);
+    ///
+    /// let mut tenant = StringRadixMap::new();
+    /// tenant.insert("config", 2);
+    /// tenant.insert("users", 3);
+    ///
+    /// map.insert_subtree("tenant-b/", tenant);
+    /// assert_eq!(
+    ///     map.keys().collect::<Vec<_>>(),
+    ///     ["tenant-a/config", "tenant-b/config", "tenant-b/users"]
+    /// );
+    /// ```
+    pub fn insert_subtree<Q: AsRef<K::Borrowed>>(&mut self, prefix: Q, other: Self) {
+        self.tree
+            .insert_subtree(prefix.as_ref().as_bytes(), other.tree);
+    }
+
+    /// Moves every entry whose key starts with `from` so that its key starts with `to` instead,
+    /// returning the number of entries moved.
+    ///
+    /// The subtree under `from` is detached and grafted back under `to` as a whole. Moved entries
+    /// replace existing entries with the same key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let mut map = StringRadixMap::new();
+    /// map.insert("acme/users", 1);
+    /// map.insert("acme/config", 2);
+    /// map.insert("other/users", 3);
+    ///
+    /// assert_eq!(map.rename_prefix("acme/", "globex/"), 2);
+    /// assert_eq!(
+    ///     map.keys().collect::<Vec<_>>(),
+    ///     ["globex/config", "globex/users", "other/users"]
+    /// );
+    /// ```
+    pub fn rename_prefix<Q, R>(&mut self, from: Q, to: R) -> usize
+    where
+        Q: AsRef<K::Borrowed>,
+        R: AsRef<K::Borrowed>,
+    {
+        self.tree
+            .rename_prefix(from.as_ref().as_bytes(), to.as_ref().as_bytes())
+    }
+
+    /// Moves all entries from `other` into `self`, leaving `other` empty.
+    ///
+    /// If a key is present in both maps, the value from `other` wins. Subtrees of `other` that
+    /// don't overlap with `self` are moved over whole, so this is the inverse of
+    /// [`split_by_prefix`](Self::split_by_prefix).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let mut a = StringRadixMap::new();
+    /// a.insert("rust", 1);
+    /// a.insert("ruby", 2);
+    ///
+    /// let mut b = StringRadixMap::new();
+    /// b.insert("ruby", 3);
+    /// b.insert("erlang", 4);
+    ///
+    /// a.append(&mut b);
+    /// assert!(b.is_empty());
+    /// assert_eq!(a.len(), 3);
+    /// assert_eq!(a.get("ruby"), Some(&3));
+    /// assert_eq!(a.keys().collect::<Vec<_>>(), ["erlang", "ruby", "rust"]);
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        let other = core::mem::take(other);
+        self.tree.merge(other.tree, |_, _, b| b);
+    }
+
+    /// Moves all entries from `other` into `self`, combining the values of keys present in both
+    /// maps with `f(key, self_value, other_value)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let mut a = StringRadixMap::new();
+    /// a.insert("apple", 1);
+    /// a.insert("banana", 2);
+    ///
+    /// let mut b = StringRadixMap::new();
+    /// b.insert("apple", 10);
+    /// b.insert("cherry", 3);
+    ///
+    /// a.merge_with(b, |_key, a, b| a + b);
+    /// assert_eq!(a.get("apple"), Some(&11));
+    /// assert_eq!(a.len(), 3);
+    /// ```
+    pub fn merge_with<F>(&mut self, other: Self, mut f: F)
+    where
+        F: FnMut(&K::Borrowed, V, V) -> V,
+    {
+        self.tree.merge(other.tree, |key, a, b| {
+            f(K::Borrowed::from_bytes(key), a, b)
+        });
+    }
+
     /// Gets an iterator over the entries of this map, sorted by key.
     ///
+    /// The iterator is double ended, `map.iter().rev()` yields the entries in descending order.
+    ///
     /// # Examples
     ///
     /// ```
@@ -408,11 +868,67 @@ impl<K: Bytes, V> GenericRadixMap<K, V> {
     ///     vec![("foo", 1), ("bar", 2), ("baz", 3)].into_iter().collect();
     /// assert_eq!(vec![(Vec::from("bar"), &2), ("baz".into(), &3), ("foo".into(), &1)],
     ///            map.iter().collect::<Vec<_>>());
+    /// assert_eq!(vec![(Vec::from("foo"), &1), ("baz".into(), &3), ("bar".into(), &2)],
+    ///            map.iter().rev().collect::<Vec<_>>());
     /// ```
     pub fn iter(&self) -> Iter<'_, K, V> {
         Iter::new(self.tree.nodes(), Vec::new())
     }
 
+    /// Gets a lending iterator over the entries of this map, sorted by key.
+    ///
+    /// Each key is borrowed from a buffer owned by the iterator, so no key is allocated per
+    /// entry. Because of that, [`RawIter`] cannot implement [`Iterator`]; drive it with
+    /// `while let` instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let map: StringRadixMap<_> =
+    ///     vec![("foo", 1), ("bar", 2), ("baz", 3)].into_iter().collect();
+    /// let mut iter = map.raw_iter();
+    /// let mut total_len = 0;
+    /// while let Some((key, _)) = iter.next() {
+    ///     total_len += key.len();
+    /// }
+    /// assert_eq!(total_len, 9);
+    ///
+    /// let mut iter = map.raw_iter();
+    /// assert_eq!(iter.next_back(), Some(("foo", &1)));
+    /// assert_eq!(iter.next(), Some(("bar", &2)));
+    /// ```
+    pub fn raw_iter(&self) -> RawIter<'_, K, V> {
+        RawIter(self.iter())
+    }
+
+    /// Calls `f` on each entry of this map, sorted by key, lending the key as `&K::Borrowed`.
+    ///
+    /// This is the closure form of [`raw_iter`](Self::raw_iter), it does not allocate an owned
+    /// key per entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let map: StringRadixMap<_> =
+    ///     vec![("foo", 1), ("bar", 2), ("baz", 3)].into_iter().collect();
+    /// let mut keys = String::new();
+    /// map.for_each_ref(|key: &str, _| keys.push_str(key));
+    /// assert_eq!(keys, "barbazfoo");
+    /// ```
+    pub fn for_each_ref<F>(&self, mut f: F)
+    where
+        F: FnMut(&K::Borrowed, &V),
+    {
+        let mut iter = self.raw_iter();
+        while let Some((key, value)) = iter.next() {
+            f(key, value);
+        }
+    }
+
     /// Gets a mutable iterator over the entries of this map, soretd by key.
     ///
     /// # Examples
@@ -498,8 +1014,13 @@ impl<K: Bytes, V> GenericRadixMap<K, V> {
     ///     vec![("foo", 1), ("bar", 2), ("baz", 3)].into_iter().collect();
     /// assert_eq!(vec![(Vec::from("bar"), &2), ("baz".into(), &3)],
     ///            map.iter_prefix(b"ba").collect::<Vec<_>>());
+    /// // the last entry under a prefix
+    /// assert_eq!(Some((Vec::from("baz"), &3)), map.iter_prefix(b"ba").next_back());
     /// ```
-    pub fn iter_prefix<'a>(&'a self, prefix: &K::Borrowed) -> impl Iterator<Item = (K, &'a V)> {
+    pub fn iter_prefix<'a>(
+        &'a self,
+        prefix: &K::Borrowed,
+    ) -> impl DoubleEndedIterator<Item = (K, &'a V)> {
         self.tree
             .iter_prefix(prefix)
             .into_iter()
@@ -523,7 +1044,7 @@ impl<K: Bytes, V> GenericRadixMap<K, V> {
     pub fn iter_prefix_mut<'a>(
         &'a mut self,
         prefix: &K::Borrowed,
-    ) -> impl Iterator<Item = (K, &'a mut V)> {
+    ) -> impl DoubleEndedIterator<Item = (K, &'a mut V)> {
         self.tree
             .iter_prefix_mut(prefix)
             .into_iter()
@@ -531,24 +1052,273 @@ impl<K: Bytes, V> GenericRadixMap<K, V> {
                 IterMut::<K, V>::new(nodes, Vec::from(&prefix.as_bytes()[..prefix_len]))
             })
     }
-}
 
-// impl<K: Bytes + fmt::Debug, V: fmt::Debug> fmt::Debug for GenericRadixMap<K, V> {
-//     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-//         f.debug_struct("RadixMap")
-//             .field("root", &self.tree.root())
-//             .finish()
-//     }
-// }
-
-impl<K, V: Clone> Clone for GenericRadixMap<K, V> {
-    fn clone(&self) -> Self {
-        Self {
-            tree: self.tree.clone(),
-            _key: PhantomData,
-        }
-    }
-}
+    /// Gets an iterator over a sub-range of entries in this map, sorted by key.
+    ///
+    /// Subtrees that fall entirely outside of the range are skipped without being visited.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`, or if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let map: StringRadixMap<_> =
+    ///     vec![("foo", 1), ("bar", 2), ("baz", 3), ("qux", 4)].into_iter().collect();
+    /// assert_eq!(vec![("baz".into(), &3), ("foo".into(), &1)],
+    ///            map.range("bas".."qux").collect::<Vec<_>>());
+    /// assert_eq!(vec![("foo".into(), &1), ("qux".into(), &4)],
+    ///            map.range("foo"..).collect::<Vec<_>>());
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        Q: ?Sized + AsRef<K::Borrowed>,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = byte_bounds::<K, Q, R>(&range);
+        let (nodes, key_bytes) = self.tree.range_nodes(start, end);
+        Range {
+            nodes,
+            key_bytes,
+            bounds: (start.map(Vec::from), end.map(Vec::from)),
+            _key: PhantomData,
+        }
+    }
+
+    /// Gets a mutable iterator over a sub-range of entries in this map, sorted by key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`, or if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    ///
+    /// let mut map: RadixMap<_> =
+    ///     vec![("foo", 1), ("bar", 2), ("baz", 3), ("qux", 4)].into_iter().collect();
+    /// for (_, v) in map.range_mut("baz"..="foo") {
+    ///     *v += 10;
+    /// }
+    /// assert_eq!(vec![2, 13, 11, 4], map.values().cloned().collect::<Vec<_>>());
+    /// ```
+    pub fn range_mut<Q, R>(&mut self, range: R) -> RangeMut<'_, K, V>
+    where
+        Q: ?Sized + AsRef<K::Borrowed>,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = byte_bounds::<K, Q, R>(&range);
+        let (nodes, key_bytes) = self.tree.range_nodes_mut(start, end);
+        RangeMut {
+            nodes,
+            key_bytes,
+            bounds: (start.map(Vec::from), end.map(Vec::from)),
+            _key: PhantomData,
+        }
+    }
+
+    /// Gets an iterator over the entries whose keys are within `max_distance` edits of `key`,
+    /// sorted by key, each along with its Levenshtein distance to `key`.
+    ///
+    /// Distances count symbols as defined by [`BorrowedBytes::symbol_len`]: bytes for byte
+    /// keys and chars for `str` keys. Subtrees that can't hold a key within the distance are
+    /// skipped without being visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let map: StringRadixMap<_> =
+    ///     vec![("mail", 1), ("main", 2), ("maps", 3), ("mäil", 4)].into_iter().collect();
+    /// assert_eq!(vec![("mail".into(), &1, 0), ("main".into(), &2, 1), ("mäil".into(), &4, 1)],
+    ///            map.fuzzy_search("mail", 1).collect::<Vec<_>>());
+    /// ```
+    pub fn fuzzy_search<Q>(&self, key: Q, max_distance: usize) -> FuzzySearch<'_, K, V>
+    where
+        Q: AsRef<K::Borrowed>,
+    {
+        self.fuzzy(key.as_ref(), max_distance, false)
+    }
+
+    /// Like [`fuzzy_search`](Self::fuzzy_search), but also counts swapping two adjacent
+    /// symbols as a single edit (the optimal string alignment variant of the Damerau-Levenshtein
+    /// distance).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let map: StringRadixMap<_> = vec![("mail", 1), ("mali", 2)].into_iter().collect();
+    /// assert_eq!(map.fuzzy_search("mial", 1).count(), 0);
+    /// assert_eq!(vec![("mail".into(), &1, 1)],
+    ///            map.fuzzy_search_damerau("mial", 1).collect::<Vec<_>>());
+    /// ```
+    pub fn fuzzy_search_damerau<Q>(&self, key: Q, max_distance: usize) -> FuzzySearch<'_, K, V>
+    where
+        Q: AsRef<K::Borrowed>,
+    {
+        self.fuzzy(key.as_ref(), max_distance, true)
+    }
+
+    fn fuzzy(
+        &self,
+        key: &K::Borrowed,
+        max_distance: usize,
+        transpositions: bool,
+    ) -> FuzzySearch<'_, K, V> {
+        let levenshtein = Levenshtein::new(
+            key.as_bytes(),
+            max_distance,
+            transpositions,
+            K::Borrowed::symbol_len,
+        );
+        FuzzySearch {
+            search: Walk::new(self.tree.root(), levenshtein, K::Borrowed::symbol_len),
+            _key: PhantomData,
+        }
+    }
+
+    /// Gets an iterator over the entries whose keys match a glob `pattern`, sorted by key.
+    ///
+    /// The pattern syntax is:
+    ///
+    /// - `?` matches any single symbol, a byte for byte keys and a char for `str` keys
+    ///   (see [`BorrowedBytes::symbol_len`])
+    /// - `*` matches any run of symbols, including none
+    /// - `[...]` matches any single symbol in the class, made of symbols and `a-z` style
+    ///   inclusive ranges, and `[!...]` or `[^...]` any symbol outside of it. A `]` right after
+    ///   the opening bracket is part of the class, and an unclosed `[` is taken literally
+    /// - `\` matches the symbol after it literally
+    /// - any other symbol matches itself
+    ///
+    /// Subtrees are skipped as soon as no key below them can match, e.g. on a literal mismatch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let map: StringRadixMap<_> = vec![
+    ///     ("www.example.com", 1),
+    ///     ("mail.example.com", 2),
+    ///     ("www.example.org", 3),
+    ///     ("www.example.com.au", 4),
+    /// ].into_iter().collect();
+    /// assert_eq!(vec![("mail.example.com".into(), &2), ("www.example.com".into(), &1)],
+    ///            map.match_pattern("*.example.??m").collect::<Vec<_>>());
+    /// assert_eq!(vec![("www.example.org".into(), &3)],
+    ///            map.match_pattern("www.*.[n-z]rg").collect::<Vec<_>>());
+    /// ```
+    pub fn match_pattern<Q>(&self, pattern: Q) -> MatchPattern<'_, K, V>
+    where
+        Q: AsRef<K::Borrowed>,
+    {
+        let glob = Glob::new(pattern.as_ref().as_bytes(), K::Borrowed::symbol_len);
+        MatchPattern {
+            search: Walk::new(self.tree.root(), glob, K::Borrowed::symbol_len),
+            _key: PhantomData,
+        }
+    }
+
+    /// Gets a lending iterator over the entries whose keys `automaton` matches, sorted by key.
+    ///
+    /// The automaton is fed the bytes of the node labels as the trie is walked, and each subtree
+    /// is abandoned as soon as [`can_match`](Automaton::can_match) is `false`. Like [`RawIter`],
+    /// the returned [`Search`] lends each key from an internal buffer, so it's driven with
+    /// `while let`.
+    ///
+    /// See the [`automaton`](crate::automaton) module for the automata provided.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    /// use fast_radix_trie::automaton::{Automaton, Str};
+    ///
+    /// let map: StringRadixMap<_> =
+    ///     vec![("foo", 1), ("foobar", 2), ("bar", 3)].into_iter().collect();
+    ///
+    /// let mut search = map.search(Str::new("fo").starts_with());
+    /// assert_eq!(search.next(), Some(("foo", &1)));
+    /// assert_eq!(search.next(), Some(("foobar", &2)));
+    /// assert_eq!(search.next(), None);
+    /// ```
+    pub fn search<A: Automaton>(&self, automaton: A) -> Search<'_, K, V, A> {
+        Search {
+            walk: Walk::new(
+                self.tree.root(),
+                Bytewise(automaton),
+                <[u8] as BorrowedBytes>::symbol_len,
+            ),
+            _key: PhantomData,
+        }
+    }
+}
+
+/// Converts `range` to bounds over key bytes, panicking on an invalid range like `BTreeMap::range`.
+fn byte_bounds<'a, K, Q, R>(range: &'a R) -> (Bound<&'a [u8]>, Bound<&'a [u8]>)
+where
+    K: Bytes,
+    K::Borrowed: 'a,
+    Q: ?Sized + AsRef<K::Borrowed> + 'a,
+    R: RangeBounds<Q>,
+{
+    let start = range.start_bound().map(|q| q.as_ref().as_bytes());
+    let end = range.end_bound().map(|q| q.as_ref().as_bytes());
+    match (start, end) {
+        (Bound::Excluded(s), Bound::Excluded(e)) if s == e => {
+            panic!("range start and end are equal and excluded in RadixMap")
+        }
+        (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e))
+            if s > e =>
+        {
+            panic!("range start is greater than range end in RadixMap")
+        }
+        _ => {}
+    }
+    (start, end)
+}
+
+/// Returns `true` if `key` lies before the `start` bound of a range.
+fn before_start(key: &[u8], start: &Bound<Vec<u8>>) -> bool {
+    match start {
+        Bound::Included(start) => key < start.as_slice(),
+        Bound::Excluded(start) => key <= start.as_slice(),
+        Bound::Unbounded => false,
+    }
+}
+
+/// Returns `true` if `key` lies after the `end` bound of a range.
+fn past_end(key: &[u8], end: &Bound<Vec<u8>>) -> bool {
+    match end {
+        Bound::Included(end) => key > end.as_slice(),
+        Bound::Excluded(end) => key >= end.as_slice(),
+        Bound::Unbounded => false,
+    }
+}
+
+// impl<K: Bytes + fmt::Debug, V: fmt::Debug> fmt::Debug for GenericRadixMap<K, V> {
+//     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+//         f.debug_struct("RadixMap")
+//             .field("root", &self.tree.root())
+//             .finish()
+//     }
+// }
+
+impl<K, V: Clone> Clone for GenericRadixMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            tree: self.tree.clone(),
+            _key: PhantomData,
+        }
+    }
+}
 impl<K, V> Default for GenericRadixMap<K, V> {
     fn default() -> Self {
         Self::new()
@@ -567,6 +1337,103 @@ impl<K: Bytes, V> IntoIterator for GenericRadixMap<K, V> {
     }
 }
 
+impl<K: Bytes, V> GenericRadixMap<K, V> {
+    /// Builds a map from entries sorted by strictly ascending key.
+    ///
+    /// The tree is built bottom up, allocating each node once at its final size, which is
+    /// much faster than inserting the entries one by one.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a key is smaller than or equal to the key before it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{StringRadixMap, map::FromSortedIterError};
+    ///
+    /// let map = StringRadixMap::from_sorted_iter([("apple", 1), ("apply", 2), ("box", 3)]).unwrap();
+    /// assert_eq!(map.get("apply"), Some(&2));
+    /// assert_eq!(map.len(), 3);
+    ///
+    /// let err = StringRadixMap::from_sorted_iter([("b", 1), ("a", 2)]).unwrap_err();
+    /// assert_eq!(err, FromSortedIterError::Unsorted(1));
+    /// let err = StringRadixMap::from_sorted_iter([("a", 1), ("a", 2)]).unwrap_err();
+    /// assert_eq!(err, FromSortedIterError::Duplicate(1));
+    /// ```
+    pub fn from_sorted_iter<I, Q>(iter: I) -> Result<Self, FromSortedIterError>
+    where
+        I: IntoIterator<Item = (Q, V)>,
+        Q: AsRef<K::Borrowed>,
+    {
+        Self::build_sorted(iter, false)
+    }
+
+    /// Builds a map from entries sorted by ascending key, where the last of several entries with
+    /// the same key wins.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a key is smaller than the key before it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let map = StringRadixMap::from_sorted_iter_dedup([("a", 1), ("a", 2), ("b", 3)]).unwrap();
+    /// assert_eq!(map.get("a"), Some(&2));
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    pub fn from_sorted_iter_dedup<I, Q>(iter: I) -> Result<Self, FromSortedIterError>
+    where
+        I: IntoIterator<Item = (Q, V)>,
+        Q: AsRef<K::Borrowed>,
+    {
+        Self::build_sorted(iter, true)
+    }
+
+    fn build_sorted<I, Q>(iter: I, dedup: bool) -> Result<Self, FromSortedIterError>
+    where
+        I: IntoIterator<Item = (Q, V)>,
+        Q: AsRef<K::Borrowed>,
+    {
+        let mut builder = tree::SortedBuilder::new();
+        for (index, (key, value)) in iter.into_iter().enumerate() {
+            builder
+                .push(key.as_ref().as_bytes(), value, dedup)
+                .map_err(|err| match err {
+                    tree::SortedPushError::Unsorted => FromSortedIterError::Unsorted(index),
+                    tree::SortedPushError::Duplicate => FromSortedIterError::Duplicate(index),
+                })?;
+        }
+        Ok(GenericRadixMap {
+            tree: builder.finish(),
+            _key: PhantomData,
+        })
+    }
+}
+
+/// Errors raised by [`GenericRadixMap::from_sorted_iter`] and its set and dedup variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromSortedIterError {
+    /// The item at this index is smaller than the one before it.
+    Unsorted(usize),
+    /// The item at this index is equal to the one before it.
+    Duplicate(usize),
+}
+
+impl fmt::Display for FromSortedIterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FromSortedIterError::Unsorted(i) => write!(f, "item {i} is out of order"),
+            FromSortedIterError::Duplicate(i) => write!(f, "item {i} is a duplicate"),
+        }
+    }
+}
+
+impl core::error::Error for FromSortedIterError {}
+
 impl<K, Q, V> FromIterator<(Q, V)> for GenericRadixMap<K, V>
 where
     K: Bytes,
@@ -604,32 +1471,172 @@ pub struct Iter<'a, K, V: 'a> {
     nodes: tree::Nodes<'a, V>,
     key_bytes: Vec<u8>,
     key_offset: usize,
+    back_key_bytes: Vec<u8>,
     _key: PhantomData<K>,
 }
 impl<'a, K, V: 'a> Iter<'a, K, V> {
-    fn new(nodes: tree::Nodes<'a, V>, key: Vec<u8>) -> Self {
+    pub(crate) fn new(nodes: tree::Nodes<'a, V>, key: Vec<u8>) -> Self {
         let key_offset = key.len();
         Self {
             nodes,
             key_bytes: key,
             key_offset,
+            back_key_bytes: Vec::new(),
             _key: PhantomData,
         }
     }
 }
+impl<'a, K, V: 'a> Iter<'a, K, V> {
+    fn next_raw(&mut self) -> Option<(&[u8], &'a V)> {
+        for (key_len, node) in &mut self.nodes {
+            self.key_bytes.truncate(self.key_offset + key_len);
+            self.key_bytes.extend(node.label());
+            if let Some(value) = node.value() {
+                return Some((&self.key_bytes, value));
+            }
+        }
+        None
+    }
+
+    fn next_back_raw(&mut self) -> Option<(&[u8], &'a V)> {
+        while let Some((_, node)) = self.nodes.next_back() {
+            if let Some(value) = node.value() {
+                self.back_key_bytes.clear();
+                self.back_key_bytes
+                    .extend_from_slice(&self.key_bytes[..self.key_offset]);
+                self.back_key_bytes.extend_from_slice(self.nodes.back_key());
+                return Some((&self.back_key_bytes, value));
+            }
+        }
+        None
+    }
+}
 impl<'a, K: Bytes, V: 'a> Iterator for Iter<'a, K, V> {
     type Item = (K, &'a V);
     fn next(&mut self) -> Option<Self::Item> {
-        for (key_len, node) in &mut self.nodes {
-            self.key_bytes.truncate(self.key_offset + key_len);
+        self.next_raw()
+            .map(|(key, value)| (K::Borrowed::from_bytes(key).to_owned(), value))
+    }
+}
+impl<'a, K: Bytes, V: 'a> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.next_back_raw()
+            .map(|(key, value)| (K::Borrowed::from_bytes(key).to_owned(), value))
+    }
+}
+
+/// A lending iterator over a `RadixMap`'s entries that borrows each key from an internal buffer.
+///
+/// Unlike [`Iter`], no owned key is allocated per entry; the key returned by
+/// [`next`](RawIter::next) is only valid until the next call.
+///
+/// This `struct` is created by the [`raw_iter`](GenericRadixMap::raw_iter) method on [`GenericRadixMap`].
+#[derive(Debug)]
+pub struct RawIter<'a, K, V: 'a>(Iter<'a, K, V>);
+impl<'a, K: Bytes, V: 'a> RawIter<'a, K, V> {
+    /// Advances the iterator and returns the next entry, in sorted order.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<(&K::Borrowed, &'a V)> {
+        self.0
+            .next_raw()
+            .map(|(key, value)| (K::Borrowed::from_bytes(key), value))
+    }
+
+    /// Returns the next entry from the back of the iterator, in reverse sorted order.
+    pub fn next_back(&mut self) -> Option<(&K::Borrowed, &'a V)> {
+        self.0
+            .next_back_raw()
+            .map(|(key, value)| (K::Borrowed::from_bytes(key), value))
+    }
+}
+
+/// An iterator over a sub-range of a `RadixMap`'s entries.
+///
+/// This `struct` is created by the [`range`](GenericRadixMap::range) method on [`GenericRadixMap`].
+#[derive(Debug)]
+pub struct Range<'a, K, V: 'a> {
+    nodes: tree::Nodes<'a, V>,
+    key_bytes: Vec<u8>,
+    bounds: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+    _key: PhantomData<K>,
+}
+impl<'a, K: Bytes, V: 'a> Iterator for Range<'a, K, V> {
+    type Item = (K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((key_len, node)) = self.nodes.next() {
+            self.key_bytes.truncate(key_len);
             self.key_bytes.extend(node.label());
+            if past_end(&self.key_bytes, &self.bounds.1) {
+                // every remaining node sorts after this one
+                self.nodes.clear();
+                break;
+            }
+            if let Some(value) = node.value() {
+                return Some((K::Borrowed::from_bytes(&self.key_bytes).to_owned(), value));
+            }
+        }
+        None
+    }
+}
+impl<'a, K: Bytes, V: 'a> DoubleEndedIterator for Range<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some((_, node)) = self.nodes.next_back() {
+            let key = self.nodes.back_key();
+            if before_start(key, &self.bounds.0) {
+                // every remaining node sorts before this one
+                self.nodes.clear();
+                break;
+            }
             if let Some(value) = node.value() {
+                return Some((K::Borrowed::from_bytes(key).to_owned(), value));
+            }
+        }
+        None
+    }
+}
+
+/// A mutable iterator over a sub-range of a `RadixMap`'s entries.
+///
+/// This `struct` is created by the [`range_mut`](GenericRadixMap::range_mut) method on [`GenericRadixMap`].
+#[derive(Debug)]
+pub struct RangeMut<'a, K, V: 'a> {
+    nodes: tree::NodesMut<'a, V>,
+    key_bytes: Vec<u8>,
+    bounds: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+    _key: PhantomData<K>,
+}
+impl<'a, K: Bytes, V: 'a> Iterator for RangeMut<'a, K, V> {
+    type Item = (K, &'a mut V);
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((key_len, node)) = self.nodes.next() {
+            self.key_bytes.truncate(key_len);
+            self.key_bytes.extend(node.label());
+            if past_end(&self.key_bytes, &self.bounds.1) {
+                self.nodes.clear();
+                break;
+            }
+            if let Some(value) = node.into_value_mut() {
                 return Some((K::Borrowed::from_bytes(&self.key_bytes).to_owned(), value));
             }
         }
         None
     }
 }
+impl<'a, K: Bytes, V: 'a> DoubleEndedIterator for RangeMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some((_, node)) = self.nodes.next_back() {
+            let key = self.nodes.back_key();
+            if before_start(key, &self.bounds.0) {
+                self.nodes.clear();
+                break;
+            }
+            if let Some(value) = node.into_value_mut() {
+                return Some((K::Borrowed::from_bytes(key).to_owned(), value));
+            }
+        }
+        None
+    }
+}
 
 /// An owning iterator over a `RadixMap`'s entries.
 #[derive(Debug)]
@@ -652,21 +1659,44 @@ impl<K: Bytes, V> Iterator for IntoIter<K, V> {
     }
 }
 
+/// An iterator over the entries removed by [`GenericRadixMap::extract_if`].
+#[derive(Debug)]
+pub struct ExtractIf<K, V> {
+    entries: alloc::vec::IntoIter<(K, V)>,
+}
+impl<K, V> Iterator for ExtractIf<K, V> {
+    type Item = (K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+impl<K, V> DoubleEndedIterator for ExtractIf<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries.next_back()
+    }
+}
+impl<K, V> ExactSizeIterator for ExtractIf<K, V> {}
+
 /// A mutable iterator over a `RadixMap`'s entries.
 #[derive(Debug)]
 pub struct IterMut<'a, K, V: 'a> {
     nodes: tree::NodesMut<'a, V>,
     key_bytes: Vec<u8>,
     key_offset: usize,
+    back_key_bytes: Vec<u8>,
     _key: PhantomData<K>,
 }
 impl<'a, K, V: 'a> IterMut<'a, K, V> {
-    fn new(nodes: tree::NodesMut<'a, V>, key: Vec<u8>) -> Self {
+    pub(crate) fn new(nodes: tree::NodesMut<'a, V>, key: Vec<u8>) -> Self {
         let key_offset = key.len();
         Self {
             nodes,
             key_bytes: key,
             key_offset,
+            back_key_bytes: Vec::new(),
             _key: PhantomData,
         }
     }
@@ -683,73 +1713,484 @@ impl<'a, K: Bytes, V: 'a> Iterator for IterMut<'a, K, V> {
         }
         None
     }
-}
+}
+impl<'a, K: Bytes, V: 'a> DoubleEndedIterator for IterMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some((_, node)) = self.nodes.next_back() {
+            if let Some(value) = node.into_value_mut() {
+                self.back_key_bytes.clear();
+                self.back_key_bytes
+                    .extend_from_slice(&self.key_bytes[..self.key_offset]);
+                self.back_key_bytes.extend_from_slice(self.nodes.back_key());
+                return Some((
+                    K::Borrowed::from_bytes(&self.back_key_bytes).to_owned(),
+                    value,
+                ));
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over the entries of a `RadixMap` within an edit distance of a key, along with
+/// their distances.
+///
+/// This `struct` is created by the [`fuzzy_search`](GenericRadixMap::fuzzy_search) and
+/// [`fuzzy_search_damerau`](GenericRadixMap::fuzzy_search_damerau) methods on
+/// [`GenericRadixMap`].
+#[derive(Debug)]
+pub struct FuzzySearch<'a, K, V: 'a> {
+    search: Walk<'a, V, Levenshtein>,
+    _key: PhantomData<K>,
+}
+impl<'a, K: Bytes, V: 'a> Iterator for FuzzySearch<'a, K, V> {
+    type Item = (K, &'a V, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.search.next().map(|(key, value, distance)| {
+            (K::Borrowed::from_bytes(key).to_owned(), value, distance)
+        })
+    }
+}
+
+/// An iterator over the entries of a `RadixMap` whose keys match a glob pattern.
+///
+/// This `struct` is created by the [`match_pattern`](GenericRadixMap::match_pattern) method on
+/// [`GenericRadixMap`].
+#[derive(Debug)]
+pub struct MatchPattern<'a, K, V: 'a> {
+    search: Walk<'a, V, Glob>,
+    _key: PhantomData<K>,
+}
+impl<'a, K: Bytes, V: 'a> Iterator for MatchPattern<'a, K, V> {
+    type Item = (K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.search
+            .next()
+            .map(|(key, value, ())| (K::Borrowed::from_bytes(key).to_owned(), value))
+    }
+}
+
+/// A lending iterator over the entries of a `RadixMap` whose keys an [`Automaton`] matches.
+///
+/// Like [`RawIter`], the key returned by [`next`](Search::next) is only valid until the next
+/// call.
+///
+/// This `struct` is created by the [`search`](GenericRadixMap::search) method on
+/// [`GenericRadixMap`].
+pub struct Search<'a, K, V: 'a, A: Automaton> {
+    walk: Walk<'a, V, Bytewise<A>>,
+    _key: PhantomData<K>,
+}
+impl<K, V, A: Automaton> fmt::Debug for Search<'_, K, V, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Search").finish_non_exhaustive()
+    }
+}
+impl<'a, K: Bytes, V: 'a, A: Automaton> Search<'a, K, V, A> {
+    /// Advances the search and returns the next match, in sorted order.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<(&K::Borrowed, &'a V)> {
+        self.walk
+            .next()
+            .map(|(key, value, ())| (K::Borrowed::from_bytes(key), value))
+    }
+}
+
+/// An iterator over a `RadixMap`'s keys.
+#[derive(Debug)]
+pub struct Keys<'a, K, V: 'a>(Iter<'a, K, V>);
+impl<'a, K: Bytes, V: 'a> Iterator for Keys<'a, K, V> {
+    type Item = K;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, _)| k)
+    }
+}
+impl<'a, K: Bytes, V: 'a> DoubleEndedIterator for Keys<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(k, _)| k)
+    }
+}
+
+/// An iterator over a `RadixMap`'s values.
+#[derive(Debug)]
+pub struct Values<'a, V: 'a> {
+    nodes: tree::Nodes<'a, V>,
+}
+impl<'a, V: 'a> Iterator for Values<'a, V> {
+    type Item = &'a V;
+    fn next(&mut self) -> Option<Self::Item> {
+        for (_, node) in &mut self.nodes {
+            if let Some(value) = node.value() {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+impl<'a, V: 'a> DoubleEndedIterator for Values<'a, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some((_, node)) = self.nodes.next_back() {
+            if let Some(value) = node.value() {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+/// A mutable iterator over a `RadixMap`'s values.
+#[derive(Debug)]
+pub struct ValuesMut<'a, V: 'a> {
+    nodes: tree::NodesMut<'a, V>,
+}
+impl<'a, V: 'a> Iterator for ValuesMut<'a, V> {
+    type Item = &'a mut V;
+    fn next(&mut self) -> Option<Self::Item> {
+        for (_, node) in &mut self.nodes {
+            if let Some(value) = node.into_value_mut() {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+impl<'a, V: 'a> DoubleEndedIterator for ValuesMut<'a, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some((_, node)) = self.nodes.next_back() {
+            if let Some(value) = node.into_value_mut() {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over entries in a `RadixMap` that share a common prefix with
+/// a given key.
+#[derive(Debug)]
+pub struct CommonPrefixesIter<'a, 'b, K: ?Sized, V> {
+    key_bytes: &'b [u8],
+    iterator: crate::node_common::CommonPrefixesIter<'a, 'b, K, V>,
+}
+impl<'a, 'b, K, V> Iterator for CommonPrefixesIter<'a, 'b, K, V>
+where
+    K: 'b + ?Sized + BorrowedBytes,
+{
+    type Item = (&'b K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (prefix_len, n) in self.iterator.by_ref() {
+            if let Some(v) = n.value() {
+                return Some((K::from_bytes(&self.key_bytes[..prefix_len]), v));
+            }
+        }
+
+        None
+    }
+}
+
+/// A view into a single entry in a map, which may either be vacant or occupied.
+///
+/// This `enum` is constructed from the [`entry`](GenericRadixMap::entry) method on [`GenericRadixMap`].
+pub enum Entry<'a, 'k, K: Bytes, V> {
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, 'k, K, V>),
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, 'k, K, V>),
+}
+
+impl<'a, 'k, K: Bytes, V> Entry<'a, 'k, K, V> {
+    /// Returns a reference to this entry's key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let mut map = StringRadixMap::<usize>::new();
+    /// assert_eq!(map.entry("foo").key(), "foo");
+    /// ```
+    pub fn key(&self) -> &'k K::Borrowed {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default if empty,
+    /// and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    ///
+    /// let mut map = RadixMap::new();
+    /// map.entry("foo").or_insert(3);
+    /// *map.entry("foo").or_insert(10) *= 2;
+    /// assert_eq!(map.get("foo"), Some(&6));
+    /// ```
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    ///
+    /// let mut map = RadixMap::new();
+    /// map.entry("foo").or_insert_with(Vec::new).push(1);
+    /// map.entry("foo").or_insert_with(Vec::new).push(2);
+    /// assert_eq!(map.get("foo"), Some(&vec![1, 2]));
+    /// ```
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting, if empty, the result of the default function
+    /// called with the entry's key, and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    ///
+    /// let mut map = StringRadixMap::new();
+    /// map.entry("foo").or_insert_with_key(|key| key.len());
+    /// assert_eq!(map.get("foo"), Some(&3));
+    /// ```
+    pub fn or_insert_with_key<F: FnOnce(&K::Borrowed) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    ///
+    /// let mut map = RadixMap::new();
+    /// map.entry("foo").and_modify(|v| *v += 1).or_insert(42);
+    /// assert_eq!(map.get("foo"), Some(&42));
+    /// map.entry("foo").and_modify(|v| *v += 1).or_insert(42);
+    /// assert_eq!(map.get("foo"), Some(&43));
+    /// ```
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+
+    /// Sets the value of the entry, and returns a mutable reference to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    ///
+    /// let mut map = RadixMap::new();
+    /// map.entry("foo").insert(1);
+    /// *map.entry("foo").insert(2) += 1;
+    /// assert_eq!(map.get("foo"), Some(&3));
+    /// ```
+    pub fn insert(self, value: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(mut entry) => {
+                entry.insert(value);
+                entry.into_mut()
+            }
+            Entry::Vacant(entry) => entry.insert(value),
+        }
+    }
+}
+
+impl<'a, 'k, K: Bytes, V: Default> Entry<'a, 'k, K, V> {
+    /// Ensures a value is in the entry by inserting the default value if empty,
+    /// and returns a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    ///
+    /// let mut map = RadixMap::<usize>::new();
+    /// *map.entry("foo").or_default() += 1;
+    /// assert_eq!(map.get("foo"), Some(&1));
+    /// ```
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<K: Bytes, V: fmt::Debug> fmt::Debug for Entry<'_, '_, K, V>
+where
+    K::Borrowed: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Occupied(entry) => f.debug_tuple("Entry").field(entry).finish(),
+            Entry::Vacant(entry) => f.debug_tuple("Entry").field(entry).finish(),
+        }
+    }
+}
+
+/// A view into an occupied entry in a `RadixMap`. It is part of the [`Entry`] enum.
+pub struct OccupiedEntry<'a, 'k, K: Bytes, V> {
+    key: &'k K::Borrowed,
+    slot: NodeSlot<'a, V>,
+    len: &'a mut usize,
+}
+
+impl<'a, 'k, K: Bytes, V> OccupiedEntry<'a, 'k, K, V> {
+    /// Returns a reference to this entry's key.
+    pub fn key(&self) -> &'k K::Borrowed {
+        self.key
+    }
+
+    /// Gets a reference to the value in the entry.
+    pub fn get(&self) -> &V {
+        some!(self.slot.node().value())
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    ///
+    /// If you need a reference to the `OccupiedEntry` which may outlive the destruction
+    /// of the `Entry` value, see [`into_mut`](Self::into_mut).
+    pub fn get_mut(&mut self) -> &mut V {
+        some!(self.slot.node_mut().value_mut())
+    }
+
+    /// Converts the entry into a mutable reference to its value.
+    pub fn into_mut(self) -> &'a mut V {
+        some!(self.slot.into_node_mut().value_mut())
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{RadixMap, map::Entry};
+    ///
+    /// let mut map = RadixMap::new();
+    /// map.insert("foo", 1);
+    /// if let Entry::Occupied(mut entry) = map.entry("foo") {
+    ///     assert_eq!(entry.insert(2), 1);
+    /// }
+    /// assert_eq!(map.get("foo"), Some(&2));
+    /// ```
+    pub fn insert(&mut self, value: V) -> V {
+        core::mem::replace(self.get_mut(), value)
+    }
+
+    /// Takes the value of the entry out of the map, and returns it.
+    pub fn remove(self) -> V {
+        *self.len -= 1;
+        some!(self.slot.remove_value())
+    }
 
-/// An iterator over a `RadixMap`'s keys.
-#[derive(Debug)]
-pub struct Keys<'a, K, V: 'a>(Iter<'a, K, V>);
-impl<'a, K: Bytes, V: 'a> Iterator for Keys<'a, K, V> {
-    type Item = K;
-    fn next(&mut self) -> Option<Self::Item> {
-        self.0.next().map(|(k, _)| k)
+    /// Takes the key and value of the entry out of the map, and returns them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{RadixMap, map::Entry};
+    ///
+    /// let mut map = RadixMap::new();
+    /// map.insert("foo", 1);
+    /// map.insert("foobar", 2);
+    /// if let Entry::Occupied(entry) = map.entry("foo") {
+    ///     assert_eq!(entry.remove_entry(), (Vec::from("foo"), 1));
+    /// }
+    /// assert_eq!(map.get("foo"), None);
+    /// assert_eq!(map.get("foobar"), Some(&2));
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    pub fn remove_entry(self) -> (K, V) {
+        let key = self.key.to_owned();
+        (key, self.remove())
     }
 }
 
-/// An iterator over a `RadixMap`'s values.
-#[derive(Debug)]
-pub struct Values<'a, V: 'a> {
-    nodes: tree::Nodes<'a, V>,
-}
-impl<'a, V: 'a> Iterator for Values<'a, V> {
-    type Item = &'a V;
-    fn next(&mut self) -> Option<Self::Item> {
-        for (_, node) in &mut self.nodes {
-            if let Some(value) = node.value() {
-                return Some(value);
-            }
-        }
-        None
+impl<K: Bytes, V: fmt::Debug> fmt::Debug for OccupiedEntry<'_, '_, K, V>
+where
+    K::Borrowed: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", &self.key)
+            .field("value", self.get())
+            .finish()
     }
 }
 
-/// A mutable iterator over a `RadixMap`'s values.
-#[derive(Debug)]
-pub struct ValuesMut<'a, V: 'a> {
-    nodes: tree::NodesMut<'a, V>,
+/// A view into a vacant entry in a `RadixMap`. It is part of the [`Entry`] enum.
+pub struct VacantEntry<'a, 'k, K: Bytes, V> {
+    key: &'k K::Borrowed,
+    // node the descent stopped at, the key continues from `key[offset..]`
+    node: &'a mut Node<V>,
+    offset: usize,
+    len: &'a mut usize,
 }
-impl<'a, V: 'a> Iterator for ValuesMut<'a, V> {
-    type Item = &'a mut V;
-    fn next(&mut self) -> Option<Self::Item> {
-        for (_, node) in &mut self.nodes {
-            if let Some(value) = node.into_value_mut() {
-                return Some(value);
-            }
-        }
-        None
+
+impl<'a, 'k, K: Bytes, V> VacantEntry<'a, 'k, K, V> {
+    /// Returns a reference to this entry's key.
+    pub fn key(&self) -> &'k K::Borrowed {
+        self.key
     }
-}
 
-/// An iterator over entries in a `RadixMap` that share a common prefix with
-/// a given key.
-#[derive(Debug)]
-pub struct CommonPrefixesIter<'a, 'b, K: ?Sized, V> {
-    key_bytes: &'b [u8],
-    iterator: crate::node_common::CommonPrefixesIter<'a, 'b, K, V>,
+    /// Takes ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key.to_owned()
+    }
+
+    /// Sets the value of the entry, and returns a mutable reference to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{RadixMap, map::Entry};
+    ///
+    /// let mut map = RadixMap::new();
+    /// if let Entry::Vacant(entry) = map.entry("foo") {
+    ///     *entry.insert(1) += 1;
+    /// }
+    /// assert_eq!(map.get("foo"), Some(&2));
+    /// ```
+    pub fn insert(self, value: V) -> &'a mut V {
+        let suffix = &self.key.as_bytes()[self.offset..];
+        let (node, old) = self.node.insert_node(suffix, value);
+        debug_assert!(old.is_none(), "vacant entry must not hold a value");
+        *self.len += 1;
+        some!(node.value_mut())
+    }
 }
-impl<'a, 'b, K, V> Iterator for CommonPrefixesIter<'a, 'b, K, V>
+
+impl<K: Bytes, V> fmt::Debug for VacantEntry<'_, '_, K, V>
 where
-    K: 'b + ?Sized + BorrowedBytes,
+    K::Borrowed: fmt::Debug,
 {
-    type Item = (&'b K, &'a V);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        for (prefix_len, n) in self.iterator.by_ref() {
-            if let Some(v) = n.value() {
-                return Some((K::from_bytes(&self.key_bytes[..prefix_len]), v));
-            }
-        }
-
-        None
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VacantEntry").field(&self.key).finish()
     }
 }
 
@@ -1082,4 +2523,834 @@ mod tests {
 
         assert_eq!(unique_words.len(), trie.len());
     }
+
+    #[test]
+    fn entry_works() {
+        let mut input = (0..1000).map(|i| i.to_string()).collect::<Vec<_>>();
+        input.shuffle(&mut rand::rng());
+
+        let mut map = RadixMap::new();
+        for k in &input {
+            *map.entry(k).or_insert(0) += 1;
+        }
+        for k in &input {
+            *map.entry(k).or_default() += 1;
+        }
+        assert_eq!(map.len(), input.len());
+        for k in &input {
+            assert_eq!(map.get(k), Some(&2));
+        }
+
+        input.shuffle(&mut rand::rng());
+        for (i, k) in input.iter().enumerate() {
+            match map.entry(k) {
+                Entry::Occupied(e) => assert_eq!(e.remove_entry(), (k.as_bytes().to_vec(), 2)),
+                Entry::Vacant(_) => panic!("missing key {k}"),
+            }
+            assert_eq!(map.len(), input.len() - i - 1);
+            assert!(matches!(map.entry(k), Entry::Vacant(_)));
+        }
+        assert!(map.is_empty());
+        assert_eq!(map.iter().count(), 0);
+    }
+
+    #[test]
+    fn entry_compacts_on_remove() {
+        let mut map = RadixMap::new();
+        map.insert("", 0);
+        map.insert("abc", 1);
+        map.insert("abcdef", 2);
+        map.insert("abcxyz", 3);
+
+        let Entry::Occupied(e) = map.entry("abcdef") else {
+            panic!("abcdef missing");
+        };
+        assert_eq!(e.remove(), 2);
+        let Entry::Occupied(e) = map.entry("abc") else {
+            panic!("abc missing");
+        };
+        assert_eq!(e.remove(), 1);
+
+        let mut expected = RadixMap::new();
+        expected.insert("", 0);
+        expected.insert("abcxyz", 3);
+        assert_eq!(map.tree.root(), expected.tree.root());
+
+        let Entry::Occupied(e) = map.entry("") else {
+            panic!("root missing");
+        };
+        assert_eq!(e.remove(), 0);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get("abcxyz"), Some(&3));
+
+        // vacant entries stopping mid-label or below a long key
+        let long = "x".repeat(600);
+        map.entry(&long).insert(4);
+        map.entry("ab").insert(5);
+        assert_eq!(map.get(&long), Some(&4));
+        assert_eq!(map.get("ab"), Some(&5));
+        assert_eq!(map.get("abcxyz"), Some(&3));
+        assert_eq!(map.len(), 3);
+    }
+
+    #[test]
+    fn range_matches_btreemap() {
+        use rand::{Rng, seq::IndexedRandom};
+        use std::collections::BTreeMap;
+
+        let mut rng = rand::rng();
+        let keys = crate::test::test_keys(if cfg!(miri) { 50 } else { 500 }, 50);
+
+        let mut map = StringRadixMap::new();
+        let mut btree = BTreeMap::new();
+        for (i, k) in keys.iter().enumerate() {
+            map.insert(k, i);
+            btree.insert(k.clone(), i);
+        }
+
+        let mut probes = keys.clone();
+        probes.extend((0..100).map(|i| format!("{}", i * 3)));
+        probes.extend(["", "0", "9", "99999", "a", "b", "x"].map(String::from));
+        probes.push("a".repeat(301));
+        probes.push("x".repeat(256));
+        let bound = |b: u8, k: &String| match b % 3 {
+            0 => Bound::Included(k.clone()),
+            1 => Bound::Excluded(k.clone()),
+            _ => Bound::Unbounded,
+        };
+        for _ in 0..if cfg!(miri) { 20 } else { 2000 } {
+            let (mut a, mut b) = (
+                probes.choose(&mut rng).unwrap(),
+                probes.choose(&mut rng).unwrap(),
+            );
+            if a > b {
+                std::mem::swap(&mut a, &mut b);
+            }
+            let (start, end) = (bound(rng.random(), a), bound(rng.random(), b));
+            if a == b && matches!((&start, &end), (Bound::Excluded(_), Bound::Excluded(_))) {
+                continue;
+            }
+            let expected = btree
+                .range::<String, _>((start.clone(), end.clone()))
+                .map(|(k, v)| (k.clone(), *v))
+                .collect::<Vec<_>>();
+            let actual = map
+                .range::<String, _>((start.clone(), end.clone()))
+                .map(|(k, v)| (k, *v))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{start:?}..{end:?}");
+            let actual = map
+                .range_mut::<String, _>((start.clone(), end.clone()))
+                .map(|(k, v)| (k, *v))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{start:?}..{end:?}");
+
+            let range = map.range::<String, _>((start.clone(), end.clone()));
+            assert_double_ended(range.map(|(k, v)| (k, *v)), &expected);
+            let range = map.range_mut::<String, _>((start.clone(), end.clone()));
+            assert_double_ended(range.map(|(k, v)| (k, *v)), &expected);
+        }
+    }
+
+    /// pulls from both ends of `iter` at random, checking it against `expected`
+    fn assert_double_ended<T, I>(mut iter: I, expected: &[T])
+    where
+        T: PartialEq + std::fmt::Debug,
+        I: DoubleEndedIterator<Item = T>,
+    {
+        use rand::Rng;
+
+        let mut rng = rand::rng();
+        let (mut front, mut back) = (0, expected.len());
+        while front < back {
+            if rng.random() {
+                assert_eq!(iter.next().as_ref(), Some(&expected[front]));
+                front += 1;
+            } else {
+                back -= 1;
+                assert_eq!(iter.next_back().as_ref(), Some(&expected[back]));
+            }
+        }
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn double_ended_iteration() {
+        use std::collections::BTreeMap;
+
+        let keys = crate::test::test_keys(if cfg!(miri) { 30 } else { 300 }, 10);
+
+        let mut map = StringRadixMap::new();
+        let mut btree = BTreeMap::new();
+        for (i, k) in keys.iter().enumerate() {
+            map.insert(k, i);
+            btree.insert(k.clone(), i);
+        }
+        // leave some valueless nodes behind
+        for k in keys.iter().step_by(3) {
+            map.remove(k);
+            btree.remove(k);
+        }
+
+        let expected = btree
+            .iter()
+            .map(|(k, v)| (k.clone(), *v))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            map.iter().rev().map(|(k, v)| (k, *v)).collect::<Vec<_>>(),
+            expected.iter().rev().cloned().collect::<Vec<_>>()
+        );
+        for _ in 0..if cfg!(miri) { 2 } else { 20 } {
+            assert_double_ended(map.iter().map(|(k, v)| (k, *v)), &expected);
+            assert_double_ended(map.iter_mut().map(|(k, v)| (k, *v)), &expected);
+            assert_double_ended(map.keys(), &btree.keys().cloned().collect::<Vec<_>>());
+            assert_double_ended(
+                map.values().copied(),
+                &btree.values().copied().collect::<Vec<_>>(),
+            );
+            assert_double_ended(
+                map.values_mut().map(|v| *v),
+                &btree.values().copied().collect::<Vec<_>>(),
+            );
+        }
+
+        for prefix in ["", "1", "14", "a", "aaa", &"x".repeat(256), "zzz"] {
+            let expected = btree
+                .iter()
+                .filter(|(k, _)| k.starts_with(prefix))
+                .map(|(k, v)| (k.clone(), *v))
+                .collect::<Vec<_>>();
+            assert_double_ended(map.iter_prefix(prefix).map(|(k, v)| (k, *v)), &expected);
+            assert_double_ended(map.iter_prefix_mut(prefix).map(|(k, v)| (k, *v)), &expected);
+        }
+
+        // mutating from both ends hands out every value exactly once
+        let mut iter = map.values_mut();
+        while let (Some(a), b) = (iter.next(), iter.next_back()) {
+            *a += 1;
+            if let Some(b) = b {
+                *b += 1;
+            }
+        }
+        assert!(map.iter().all(|(k, v)| btree[&k] + 1 == *v));
+    }
+
+    #[test]
+    fn raw_iter_matches_iter() {
+        use rand::Rng;
+
+        let mut rng = rand::rng();
+        let mut map = StringRadixMap::new();
+        for i in 0..if cfg!(miri) { 30 } else { 500 } {
+            map.insert(format!("{}", i * 13), i);
+        }
+        map.insert("a".repeat(300), 1);
+        map.insert("a".repeat(600), 2);
+        map.insert("", 3);
+        let expected = map.iter().map(|(k, v)| (k, *v)).collect::<Vec<_>>();
+
+        let mut seen = Vec::new();
+        map.for_each_ref(|k, v| seen.push((k.to_owned(), *v)));
+        assert_eq!(seen, expected);
+
+        let (mut front, mut back) = (0, expected.len());
+        let mut iter = map.raw_iter();
+        while front < back {
+            if rng.random() {
+                let (k, v) = iter.next().unwrap();
+                assert_eq!((k, *v), (expected[front].0.as_str(), expected[front].1));
+                front += 1;
+            } else {
+                back -= 1;
+                let (k, v) = iter.next_back().unwrap();
+                assert_eq!((k, *v), (expected[back].0.as_str(), expected[back].1));
+            }
+        }
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn append_and_merge_with_match_btreemap() {
+        use rand::Rng;
+        use std::collections::BTreeMap;
+
+        let mut rng = rand::rng();
+        let alphabet = [b'a', b'b', b'c'];
+        for _ in 0..if cfg!(miri) { 3 } else { 100 } {
+            let mut maps = [RadixMap::new(), RadixMap::new()];
+            let mut expected = [BTreeMap::new(), BTreeMap::new()];
+            for (map, expected) in maps.iter_mut().zip(&mut expected) {
+                for i in 0..rng.random_range(0..if cfg!(miri) { 10 } else { 50 }) {
+                    let len = rng.random_range(0..8);
+                    let mut key = (0..len)
+                        .map(|_| alphabet[rng.random_range(0..alphabet.len())])
+                        .collect::<Vec<_>>();
+                    if rng.random_ratio(1, 10) {
+                        key.extend_from_slice(&[b'a'; 300]);
+                    }
+                    map.insert(&key, i);
+                    expected.insert(key, i);
+                }
+            }
+            let [mut a, b] = maps;
+            let [mut expected_a, expected_b] = expected;
+
+            let mut appended = a.clone();
+            appended.append(&mut b.clone());
+            let mut expected_appended = expected_a.clone();
+            expected_appended.extend(expected_b.clone());
+            assert_eq!(appended.len(), expected_appended.len());
+            assert!(appended.into_iter().eq(expected_appended));
+
+            a.merge_with(b, |key, x, y| {
+                assert!(expected_a.contains_key(key));
+                x * 100 + y
+            });
+            for (key, y) in expected_b {
+                expected_a
+                    .entry(key)
+                    .and_modify(|x| *x = *x * 100 + y)
+                    .or_insert(y);
+            }
+            assert_eq!(a.len(), expected_a.len());
+            assert!(a.iter().map(|(k, v)| (k, *v)).eq(expected_a));
+            assert_compressed(a.as_node());
+        }
+    }
+
+    #[test]
+    fn append_is_inverse_of_split_by_prefix() {
+        let mut map = StringRadixMap::new();
+        for key in ["rust", "ruby", "python", "erlang", "", "r", "rus"] {
+            map.insert(key, key.len());
+        }
+        let expected = map.clone();
+        for prefix in ["", "r", "ru", "rus", "rust", "p", "x"] {
+            let mut map = map.clone();
+            let mut split = map.split_by_prefix(prefix);
+            map.append(&mut split);
+            assert!(split.is_empty());
+            assert_eq!(map.len(), expected.len());
+            assert!(map.iter().eq(expected.iter()));
+        }
+    }
+
+    #[test]
+    fn split_by_prefix_longer_than_a_label() {
+        let long = "x".repeat(400);
+        let mut map = StringRadixMap::new();
+        map.insert(format!("{long}a"), 1);
+        map.insert(format!("{long}b"), 2);
+        map.insert("y", 3);
+        for prefix in [&long[..300], &long[..], &long[..255]] {
+            let mut map = map.clone();
+            let split = map.split_by_prefix(prefix);
+            assert_eq!(map.len(), 1);
+            assert!(split.keys().eq([format!("{long}a"), format!("{long}b")]));
+        }
+    }
+
+    #[test]
+    fn split_by_prefix_with_empty_prefix() {
+        let map: StringRadixMap<_> = [("", 0), ("rust", 1), ("ruby", 2)].into_iter().collect();
+
+        // every key starts with the empty prefix, so all of them are split off, where none
+        // used to be
+        let mut left = map.clone();
+        let split = left.split_by_prefix("");
+        assert!(left.is_empty());
+        assert_eq!(split.len(), 3);
+        assert!(split.iter().eq(map.iter()));
+
+        // other prefixes split off the same entries as before
+        let mut left = map.clone();
+        let split = left.split_by_prefix("r");
+        assert!(left.keys().eq([""]));
+        assert!(split.keys().eq(["ruby", "rust"]));
+
+        let mut empty = StringRadixMap::<()>::new();
+        assert!(empty.split_by_prefix("").is_empty());
+    }
+
+    #[test]
+    fn insert_subtree_and_rename_prefix_match_btreemap() {
+        use rand::Rng;
+        use std::collections::BTreeMap;
+
+        fn random_key(rng: &mut impl Rng) -> Vec<u8> {
+            let mut key = (0..rng.random_range(0..6))
+                .map(|_| b"abc"[rng.random_range(0..3)])
+                .collect::<Vec<_>>();
+            if rng.random_ratio(1, 8) {
+                key.extend_from_slice(&[b'a'; 300]);
+            }
+            key
+        }
+
+        let mut rng = rand::rng();
+        for _ in 0..if cfg!(miri) { 3 } else { 200 } {
+            let mut map = RadixMap::new();
+            let mut expected = BTreeMap::new();
+            for i in 0..rng.random_range(0..if cfg!(miri) { 10 } else { 40 }) {
+                let key = random_key(&mut rng);
+                map.insert(&key, i);
+                expected.insert(key, i);
+            }
+
+            let prefix = random_key(&mut rng);
+            let mut other = RadixMap::new();
+            for i in 0..rng.random_range(0..10) {
+                let key = random_key(&mut rng);
+                other.insert(&key, 100 + i);
+                expected.insert([&prefix[..], &key].concat(), 100 + i);
+            }
+            map.insert_subtree(&prefix, other);
+            assert_eq!(map.len(), expected.len());
+            assert!(map.iter().map(|(k, v)| (k, *v)).eq(expected.clone()));
+
+            let (from, to) = (random_key(&mut rng), random_key(&mut rng));
+            let moved = expected
+                .keys()
+                .filter(|k| k.starts_with(&from))
+                .cloned()
+                .collect::<Vec<_>>();
+            let moved = moved
+                .into_iter()
+                .map(|k| {
+                    let v = expected.remove(&k).unwrap();
+                    ([&to[..], &k[from.len()..]].concat(), v)
+                })
+                .collect::<Vec<_>>();
+            let count = moved.len();
+            expected.extend(moved);
+            assert_eq!(map.rename_prefix(&from, &to), count);
+            assert_eq!(map.len(), expected.len());
+            assert!(map.iter().map(|(k, v)| (k, *v)).eq(expected));
+            assert_compressed(map.as_node());
+        }
+    }
+
+    /// checks that the root label is empty and no other node is left empty
+    fn assert_compressed<V>(root: &Node<V>) {
+        assert!(root.label().is_empty());
+        for (_, node) in root.iter().skip(1) {
+            assert!(!node.label().is_empty());
+            assert!(node.value().is_some() || !node.children().is_empty());
+        }
+    }
+
+    #[test]
+    fn remove_and_retain_prefix_match_btreemap() {
+        use rand::Rng;
+        use std::collections::BTreeMap;
+
+        fn random_key(rng: &mut impl Rng) -> Vec<u8> {
+            let mut key = (0..rng.random_range(0..6))
+                .map(|_| b"abc"[rng.random_range(0..3)])
+                .collect::<Vec<_>>();
+            if rng.random_ratio(1, 8) {
+                key.extend_from_slice(&[b'a'; 300]);
+            }
+            key
+        }
+
+        let mut rng = rand::rng();
+        for _ in 0..if cfg!(miri) { 3 } else { 200 } {
+            let mut map = RadixMap::new();
+            let mut expected = BTreeMap::new();
+            for i in 0..rng.random_range(0..if cfg!(miri) { 10 } else { 40 }) {
+                let key = random_key(&mut rng);
+                map.insert(&key, i);
+                expected.insert(key, i);
+            }
+
+            let prefix = random_key(&mut rng);
+            let mut retained = map.clone();
+            retained.retain_prefix(&prefix);
+            let expected_retained = expected
+                .iter()
+                .filter(|(k, _)| k.starts_with(&prefix))
+                .map(|(k, v)| (k.clone(), *v))
+                .collect::<BTreeMap<_, _>>();
+            assert_eq!(retained.len(), expected_retained.len());
+            assert!(retained.iter().map(|(k, v)| (k, *v)).eq(expected_retained));
+            assert_compressed(retained.as_node());
+
+            let before = expected.len();
+            expected.retain(|k, _| !k.starts_with(&prefix));
+            assert_eq!(map.remove_prefix(&prefix), before - expected.len());
+            assert_eq!(map.len(), expected.len());
+            assert!(map.iter().map(|(k, v)| (k, *v)).eq(expected));
+            assert_compressed(map.as_node());
+        }
+    }
+
+    #[test]
+    fn retain_and_extract_if_match_btreemap() {
+        use rand::Rng;
+        use std::collections::BTreeMap;
+
+        let mut rng = rand::rng();
+        for _ in 0..if cfg!(miri) { 3 } else { 100 } {
+            let mut map = RadixMap::new();
+            let mut expected = BTreeMap::new();
+            for i in 0..rng.random_range(0..if cfg!(miri) { 15 } else { 60 }) {
+                let mut key = (0..rng.random_range(0..6))
+                    .map(|_| b"abc"[rng.random_range(0..3)])
+                    .collect::<Vec<_>>();
+                if rng.random_ratio(1, 8) {
+                    key.extend_from_slice(&[b'a'; 300]);
+                }
+                map.insert(&key, i);
+                expected.insert(key, i);
+            }
+            let modulus = rng.random_range(1..4);
+
+            let mut retained = map.clone();
+            let mut visited = Vec::new();
+            retained.retain(|key, value| {
+                visited.push(key.to_vec());
+                *value += 1;
+                *value % modulus == 0
+            });
+            assert!(visited.iter().eq(expected.keys()));
+            let expected_retained = expected
+                .iter()
+                .map(|(k, v)| (k.clone(), v + 1))
+                .filter(|(_, v)| v % modulus == 0)
+                .collect::<BTreeMap<_, _>>();
+            assert_eq!(retained.len(), expected_retained.len());
+            assert!(retained.iter().map(|(k, v)| (k, *v)).eq(expected_retained));
+            assert_compressed(retained.as_node());
+
+            let extracted = map.extract_if(|_, v| *v % modulus == 0).collect::<Vec<_>>();
+            let expected_extracted = expected
+                .iter()
+                .filter(|(_, v)| *v % modulus == 0)
+                .map(|(k, v)| (k.clone(), *v))
+                .collect::<Vec<_>>();
+            expected.retain(|_, v| *v % modulus != 0);
+            assert_eq!(extracted, expected_extracted);
+            assert_eq!(map.len(), expected.len());
+            assert!(map.iter().map(|(k, v)| (k, *v)).eq(expected));
+            assert_compressed(map.as_node());
+
+            let len = map.len();
+            assert_eq!(map.drain().count(), len);
+            assert!(map.is_empty());
+        }
+    }
+
+    #[test]
+    fn from_sorted_iter_matches_collect() {
+        use rand::Rng;
+        use std::collections::BTreeMap;
+
+        let mut rng = rand::rng();
+        for _ in 0..if cfg!(miri) { 3 } else { 200 } {
+            let mut entries = Vec::new();
+            for i in 0..rng.random_range(0..if cfg!(miri) { 15 } else { 80 }) {
+                let mut key = (0..rng.random_range(0..6))
+                    .map(|_| b"abc"[rng.random_range(0..3)])
+                    .collect::<Vec<_>>();
+                if rng.random_ratio(1, 8) {
+                    key.extend((0..rng.random_range(250..600)).map(|j| b"ab"[j % 7 / 6]));
+                }
+                entries.push((key, i));
+            }
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+            let expected = entries.iter().cloned().collect::<BTreeMap<_, _>>();
+            let map = RadixMap::from_sorted_iter_dedup(entries.iter().cloned()).unwrap();
+            assert_eq!(map.len(), expected.len());
+            assert!(map.iter().map(|(k, v)| (k, *v)).eq(expected.clone()));
+            assert!(
+                map.iter()
+                    .eq(entries.iter().cloned().collect::<RadixMap<_>>().iter())
+            );
+            assert_compressed(map.as_node());
+            for (k, v) in &expected {
+                assert_eq!(map.get(k), Some(v));
+            }
+
+            let duplicate = entries.windows(2).position(|w| w[0].0 == w[1].0);
+            match RadixMap::from_sorted_iter(entries.iter().cloned()) {
+                Ok(strict) => {
+                    assert_eq!(duplicate, None);
+                    assert!(strict.iter().eq(map.iter()));
+                }
+                Err(err) => assert_eq!(
+                    Some(err),
+                    duplicate.map(|i| FromSortedIterError::Duplicate(i + 1))
+                ),
+            }
+
+            if let Some(i) = entries.windows(2).position(|w| w[0].0 != w[1].0) {
+                entries.swap(i, i + 1);
+                assert_eq!(
+                    RadixMap::from_sorted_iter_dedup(entries).unwrap_err(),
+                    FromSortedIterError::Unsorted(i + 1)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn from_sorted_iter_edge_cases() {
+        let empty = RadixMap::<u8>::from_sorted_iter(Vec::<(&[u8], u8)>::new()).unwrap();
+        assert!(empty.is_empty());
+        assert_compressed(empty.as_node());
+
+        let map = RadixMap::from_sorted_iter([(&b""[..], 0), (b"a", 1), (b"ab", 2)]).unwrap();
+        assert_eq!(map.get(b""), Some(&0));
+        assert_eq!(map.get(b"ab"), Some(&2));
+        assert_eq!(map.len(), 3);
+
+        let set = RadixSet::from_sorted_iter_dedup([b"x", b"x", b"y"]).unwrap();
+        assert_eq!(set.len(), 2);
+        assert_eq!(
+            RadixSet::from_sorted_iter([b"x", b"x"]).unwrap_err(),
+            FromSortedIterError::Duplicate(1)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "range start is greater than range end")]
+    fn range_panics_on_reversed_bounds() {
+        let map = RadixMap::<()>::new();
+        let _ = map.range("b".."a");
+    }
+
+    #[test]
+    fn navigation_matches_btreemap() {
+        use rand::{Rng, seq::IndexedRandom};
+        use std::collections::BTreeMap;
+
+        let mut rng = rand::rng();
+        let keys = crate::test::test_keys(if cfg!(miri) { 30 } else { 300 }, 30);
+
+        let mut map = StringRadixMap::new();
+        let mut btree = BTreeMap::new();
+        for (i, k) in keys.iter().enumerate() {
+            map.insert(k, i);
+            btree.insert(k.clone(), i);
+        }
+
+        let mut probes = keys.clone();
+        probes.extend((0..100).map(|i| format!("{}", i * 3)));
+        probes.extend(["0", "9", "99999", "a", "b", "x"].map(String::from));
+        probes.push("a".repeat(301));
+        probes.push("x".repeat(256));
+        for _ in 0..if cfg!(miri) { 20 } else { 1000 } {
+            let probe = probes.choose(&mut rng).unwrap();
+            let owned = |kv: Option<(&String, &usize)>| kv.map(|(k, v)| (k.clone(), *v));
+            let range = |start, end| owned(btree.range::<String, _>((start, end)).next());
+            let range_back = |start, end| owned(btree.range::<String, _>((start, end)).next_back());
+            let key = probe.clone();
+            assert_eq!(
+                map.floor(probe).map(|(k, v)| (k, *v)),
+                range_back(Bound::Unbounded, Bound::Included(key.clone())),
+                "{probe}"
+            );
+            assert_eq!(
+                map.predecessor(probe).map(|(k, v)| (k, *v)),
+                range_back(Bound::Unbounded, Bound::Excluded(key.clone())),
+                "{probe}"
+            );
+            assert_eq!(
+                map.ceiling(probe).map(|(k, v)| (k, *v)),
+                range(Bound::Included(key.clone()), Bound::Unbounded),
+                "{probe}"
+            );
+            assert_eq!(
+                map.successor(probe).map(|(k, v)| (k, *v)),
+                range(Bound::Excluded(key), Bound::Unbounded),
+                "{probe}"
+            );
+        }
+
+        while !btree.is_empty() {
+            assert_eq!(
+                map.first_key_value(),
+                btree.first_key_value().map(|(k, v)| (k.clone(), v))
+            );
+            assert_eq!(
+                map.last_key_value(),
+                btree.last_key_value().map(|(k, v)| (k.clone(), v))
+            );
+            if rng.random() {
+                assert_eq!(map.pop_first(), btree.pop_first());
+            } else {
+                assert_eq!(map.pop_last(), btree.pop_last());
+            }
+            assert_eq!(map.len(), btree.len());
+        }
+        assert_eq!(map.pop_first(), None);
+        assert_eq!(map.first_key_value(), None);
+    }
+
+    /// Levenshtein distance, or optimal string alignment distance with `transpositions`.
+    fn edit_distance<T: PartialEq>(a: &[T], b: &[T], transpositions: bool) -> usize {
+        let mut rows = vec![(0..=b.len()).collect::<Vec<_>>()];
+        for i in 1..=a.len() {
+            let mut row = vec![i; b.len() + 1];
+            for j in 1..=b.len() {
+                row[j] = (rows[i - 1][j] + 1)
+                    .min(row[j - 1] + 1)
+                    .min(rows[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]));
+                if transpositions && i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1]
+                {
+                    row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+                }
+            }
+            rows.push(row);
+        }
+        rows[a.len()][b.len()]
+    }
+
+    #[test]
+    fn fuzzy_search_matches_brute_force() {
+        use rand::Rng;
+
+        let mut rng = rand::rng();
+        let alphabet = ['a', 'b', 'é', '€', '𝄞'];
+        let word = |rng: &mut rand::rngs::ThreadRng| {
+            (0..rng.random_range(0..7))
+                .map(|_| alphabet[rng.random_range(0..alphabet.len())])
+                .collect::<String>()
+        };
+        for _ in 0..if cfg!(miri) { 2 } else { 50 } {
+            let map: StringRadixMap<usize> = (0..rng.random_range(0..100))
+                .map(|i| (word(&mut rng), i))
+                .collect();
+            let bytes: RadixMap<usize> = map.iter().map(|(k, v)| (k.into_bytes(), *v)).collect();
+            for _ in 0..10 {
+                let query = word(&mut rng);
+                let query_chars = query.chars().collect::<Vec<_>>();
+                for max in 0..4 {
+                    for transpositions in [false, true] {
+                        let expected = map
+                            .iter()
+                            .filter_map(|(k, v)| {
+                                let chars = k.chars().collect::<Vec<_>>();
+                                let d = edit_distance(&chars, &query_chars, transpositions);
+                                (d <= max).then_some((k, v, d))
+                            })
+                            .collect::<Vec<_>>();
+                        let found = if transpositions {
+                            map.fuzzy_search_damerau(&query, max).collect::<Vec<_>>()
+                        } else {
+                            map.fuzzy_search(&query, max).collect()
+                        };
+                        assert_eq!(found, expected, "{query:?} {max} {transpositions}");
+
+                        let expected = bytes
+                            .iter()
+                            .filter_map(|(k, v)| {
+                                let d = edit_distance(&k, query.as_bytes(), transpositions);
+                                (d <= max).then_some((k, v, d))
+                            })
+                            .collect::<Vec<_>>();
+                        let found = if transpositions {
+                            bytes.fuzzy_search_damerau(&query, max).collect::<Vec<_>>()
+                        } else {
+                            bytes.fuzzy_search(&query, max).collect()
+                        };
+                        assert_eq!(found, expected, "{query:?} {max} {transpositions}");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Matches `key` against a pattern of `*`, `?`, `[...]` and `[!...]` without escapes.
+    fn glob_matches(pattern: &[char], key: &[char]) -> bool {
+        match pattern {
+            [] => key.is_empty(),
+            ['*', rest @ ..] => (0..=key.len()).any(|i| glob_matches(rest, &key[i..])),
+            ['?', rest @ ..] => !key.is_empty() && glob_matches(rest, &key[1..]),
+            ['[', rest @ ..] => {
+                let close = rest.iter().skip(1).position(|&c| c == ']').unwrap() + 1;
+                let (negated, class) = match &rest[..close] {
+                    ['!', class @ ..] => (true, class),
+                    class => (false, class),
+                };
+                let in_class = |c: char| {
+                    let mut i = 0;
+                    while i < class.len() {
+                        if i + 2 < class.len() && class[i + 1] == '-' {
+                            if (class[i]..=class[i + 2]).contains(&c) {
+                                return true;
+                            }
+                            i += 3;
+                        } else {
+                            if class[i] == c {
+                                return true;
+                            }
+                            i += 1;
+                        }
+                    }
+                    false
+                };
+                matches!(key.first(), Some(&c) if in_class(c) != negated)
+                    && glob_matches(&rest[close + 1..], &key[1..])
+            }
+            [c, rest @ ..] => key.first() == Some(c) && glob_matches(rest, &key[1..]),
+        }
+    }
+
+    #[test]
+    fn match_pattern_matches_brute_force() {
+        use rand::Rng;
+
+        let mut rng = rand::rng();
+        let alphabet = ['a', 'b', 'é', '€', '𝄞'];
+        for _ in 0..if cfg!(miri) { 2 } else { 50 } {
+            let map: StringRadixMap<usize> = (0..rng.random_range(0..100))
+                .map(|i| {
+                    let key = (0..rng.random_range(0..7))
+                        .map(|_| alphabet[rng.random_range(0..alphabet.len())])
+                        .collect::<String>();
+                    (key, i)
+                })
+                .collect();
+            for _ in 0..40 {
+                let mut pattern = String::new();
+                for _ in 0..rng.random_range(0..6) {
+                    match rng.random_range(0..8) {
+                        0 => pattern.push('*'),
+                        1 => pattern.push('?'),
+                        2 => {
+                            pattern.push('[');
+                            if rng.random() {
+                                pattern.push('!');
+                            }
+                            for _ in 0..rng.random_range(1..3) {
+                                let lo = rng.random_range(0..alphabet.len());
+                                pattern.push(alphabet[lo]);
+                                if rng.random() {
+                                    pattern.push('-');
+                                    pattern.push(alphabet[rng.random_range(lo..alphabet.len())]);
+                                }
+                            }
+                            pattern.push(']');
+                        }
+                        _ => pattern.push(alphabet[rng.random_range(0..alphabet.len())]),
+                    }
+                }
+                let chars = pattern.chars().collect::<Vec<_>>();
+                let expected = map
+                    .iter()
+                    .filter(|(k, _)| glob_matches(&chars, &k.chars().collect::<Vec<_>>()))
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    map.match_pattern(&pattern).collect::<Vec<_>>(),
+                    expected,
+                    "{pattern}"
+                );
+            }
+        }
+    }
 }
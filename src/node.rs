@@ -21,6 +21,9 @@ pub struct Node<V> {
     //   - label: [u8; label_len]
     //   - children: [NonNull<Node<V>>; children_len]
     //   - value: Option<V>
+    //   - first_bytes: [u8; children_len] -- only with 8 or more children and the `first-bytes`
+    //     feature, unless the node has an index
+    //   - index: [u8; 256] -- only with 16 or more children and the `art` feature
     pub(crate) ptr: ptr::NonNull<NodeHeader>,
     pub(crate) _marker: PhantomData<V>,
 }
@@ -74,6 +77,44 @@ impl<V> Node<V> {
         }
     }
 
+    /// Makes a new node from a runtime sized list of children, allocating it exactly once.
+    /// SAFETY: - label len must not exceed 255
+    ///         - children len must not exceed 255
+    pub(crate) fn with_children(
+        label: &[u8],
+        mut children: alloc::vec::Vec<Node<V>>,
+        value: Option<V>,
+    ) -> Self {
+        assert!(
+            children.len() <= u8::MAX as usize,
+            "nodes can have max 255 children"
+        );
+        assert!(
+            label.len() <= crate::node_common::MAX_LABEL_LEN,
+            "nodes must have a label len <= 255"
+        );
+
+        let header = NodeHeader {
+            label_len: label.len() as u8,
+            children_len: children.len() as u8,
+        };
+        let mut ptr = header.ptr_data().allocate();
+        unsafe {
+            ptr.write_header(header);
+            ptr.write_label(label);
+            if let Some(children_ptr) = ptr.children_ptr() {
+                children_ptr.copy_from_nonoverlapping(
+                    NonNull::new_unchecked(children.as_mut_ptr()),
+                    children.len(),
+                );
+                // ownership of the children moved into the node
+                children.set_len(0);
+            }
+            ptr.write_value(value);
+            ptr.assume_init()
+        }
+    }
+
     /// Returns the reference to the value of this node.
     pub fn value(&self) -> Option<&V> {
         unsafe { (self.ptr_data().value_ptr(self.ptr)).as_ref() }.as_ref()
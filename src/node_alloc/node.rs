@@ -22,6 +22,9 @@ pub struct Node<V> {
     //   - label: [u8; label_len]
     //   - children: [NonNull<Node<V>>; children_len] -- optionally allocated
     //   - value: V -- optionally allocated
+    //   - first_bytes: [u8; children_len] -- only with 8 or more children and the `first-bytes`
+    //     feature, unless the node has an index
+    //   - index: [u8; 256] -- only with 16 or more children and the `art` feature
     pub(crate) ptr: ptr::NonNull<NodeHeader>,
     pub(crate) _marker: PhantomData<V>,
 }
@@ -84,6 +87,51 @@ impl<V> Node<V> {
         }
     }
 
+    /// Makes a new node from a runtime sized list of children, allocating it exactly once.
+    /// SAFETY: - label len must not exceed 255
+    ///         - children len must not exceed 255
+    pub(crate) fn with_children(
+        label: &[u8],
+        mut children: alloc::vec::Vec<Node<V>>,
+        value: Option<V>,
+    ) -> Self {
+        assert!(
+            children.len() <= u8::MAX as usize,
+            "nodes can have max 255 children"
+        );
+        assert!(
+            label.len() <= crate::node_common::MAX_LABEL_LEN,
+            "nodes must have a label len <= 255"
+        );
+        let mut flags = Flags::empty();
+        if value.is_some() {
+            flags.insert(Flags::VALUE_ALLOCATED | Flags::VALUE_INITIALIZED);
+        }
+
+        let header = NodeHeader {
+            flags,
+            label_len: label.len() as u8,
+            children_len: children.len() as u8,
+        };
+        let mut ptr = header.ptr_data().allocate();
+        unsafe {
+            ptr.write_header(header);
+            ptr.write_label(label);
+            if let Some(children_ptr) = ptr.children_ptr() {
+                children_ptr.copy_from_nonoverlapping(
+                    NonNull::new_unchecked(children.as_mut_ptr()),
+                    children.len(),
+                );
+                // ownership of the children moved into the node
+                children.set_len(0);
+            }
+            if let Some(val) = value {
+                ptr.write_value(val);
+            }
+            ptr.assume_init()
+        }
+    }
+
     /// Returns the reference to the value of this node.
     pub fn value(&self) -> Option<&V> {
         if let Some(val) = unsafe { self.ptr_data().value_ptr_init(self.ptr) } {
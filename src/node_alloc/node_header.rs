@@ -81,6 +81,9 @@ pub(crate) struct PtrData<V> {
     pub(crate) layout: Layout,
     pub(crate) children_offset: Option<usize>,
     pub(crate) value_offset: Option<usize>,
+    pub(crate) first_bytes_offset: Option<usize>,
+    #[cfg(feature = "art")]
+    pub(crate) index_offset: Option<usize>,
     pub(crate) _marker: PhantomData<V>,
 }
 
@@ -146,6 +149,8 @@ impl<V> NodePtrAndData<V> {
 
     #[inline]
     pub(crate) unsafe fn assume_init(self) -> Node<V> {
+        // every node is finished through here, once its header and children are written
+        unsafe { self.ptr_data.write_lookup(self.ptr) };
         Node {
             ptr: self.ptr,
             _marker: PhantomData,
@@ -189,10 +194,34 @@ impl NodeHeader {
             None
         };
 
+        // branchy nodes copy the first bytes of their children after the value so they can be
+        // searched without touching each child. Like the index below they go last, so neither
+        // moves the children or value when a node crosses a threshold
+        let (layout, first_bytes_offset) = if crate::node_common::has_first_bytes(self.children_len)
+        {
+            let (new_layout, offset) =
+                extend!(layout.extend(extend!(Layout::array::<u8>(self.children_len as usize))));
+            (new_layout, Some(offset))
+        } else {
+            (layout, None)
+        };
+
+        // wide nodes keep a first byte -> child index table last
+        #[cfg(feature = "art")]
+        let (layout, index_offset) = if self.children_len >= crate::node_common::WIDE_FANOUT {
+            let (new_layout, offset) = extend!(layout.extend(Layout::new::<[u8; 256]>()));
+            (new_layout, Some(offset))
+        } else {
+            (layout, None)
+        };
+
         PtrData {
             layout: layout.pad_to_align(),
             children_offset,
             value_offset,
+            first_bytes_offset,
+            #[cfg(feature = "art")]
+            index_offset,
             _marker: PhantomData,
         }
     }
@@ -310,6 +339,15 @@ impl<V> PtrData<V> {
             }
         }
     }
+    /// bytes of the layout taken by the value slot, if one was allocated
+    #[inline]
+    pub(crate) fn value_size(&self) -> usize {
+        if self.value_offset.is_some() {
+            core::mem::size_of::<V>()
+        } else {
+            0
+        }
+    }
     #[inline]
     pub unsafe fn value_ptr_alloc(&self, header_ptr: NonNull<NodeHeader>) -> Option<NonNull<V>> {
         unsafe { self.value_ptr(header_ptr, Flags::VALUE_ALLOCATED) }
@@ -1,7 +1,7 @@
 //! node common methods
 use crate::{BorrowedBytes, Bytes, Node, NodeHeader, NodePtrAndData, PtrData};
 use alloc::{collections::VecDeque, string::String, vec::Vec};
-use core::{cmp::Ordering, fmt, marker::PhantomData, mem};
+use core::{cmp::Ordering, fmt, marker::PhantomData, mem, ops::Bound, ptr::NonNull};
 
 macro_rules! some {
     ($expr:expr) => {
@@ -25,6 +25,77 @@ pub(crate) use {extend, some};
 
 pub const MAX_LABEL_LEN: usize = u8::MAX as usize;
 
+/// with the `first-bytes` feature, nodes with at least this many children store a copy of their
+/// children's first bytes, which is searched with `memchr` instead of reading the label of each
+/// child
+pub(crate) const FIRST_BYTES_MIN: u8 = 8;
+
+/// nodes with at least this many children get a 256 entry table from first byte to child index
+/// instead of scanning their children
+#[cfg(feature = "art")]
+pub(crate) const WIDE_FANOUT: u8 = 16;
+
+/// whether a node with `children_len` children stores their first bytes, which wide nodes
+/// leave out since their index already maps each byte to a child
+#[inline]
+pub(crate) fn has_first_bytes(children_len: u8) -> bool {
+    if !cfg!(feature = "first-bytes") {
+        return false;
+    }
+    #[cfg(feature = "art")]
+    if children_len >= WIDE_FANOUT {
+        return false;
+    }
+    children_len >= FIRST_BYTES_MIN
+}
+
+impl<V> PtrData<V> {
+    /// the first bytes of the children of a branchy node
+    #[inline]
+    pub(crate) unsafe fn first_bytes<'a>(
+        &self,
+        header_ptr: NonNull<NodeHeader>,
+    ) -> Option<&'a [u8]> {
+        self.first_bytes_offset.map(|offset| unsafe {
+            let len = header_ptr.as_ref().children_len as usize;
+            core::slice::from_raw_parts(header_ptr.byte_add(offset).cast().as_ptr(), len)
+        })
+    }
+
+    /// the first byte -> child index + 1 table of a wide node, `0` meaning no child
+    #[cfg(feature = "art")]
+    #[inline]
+    pub(crate) unsafe fn index<'a>(
+        &self,
+        header_ptr: NonNull<NodeHeader>,
+    ) -> Option<&'a [u8; 256]> {
+        self.index_offset
+            .map(|offset| unsafe { header_ptr.byte_add(offset).cast().as_ref() })
+    }
+
+    /// fill in the first bytes and index of a node from its children
+    pub(crate) unsafe fn write_lookup(&self, header_ptr: NonNull<NodeHeader>) {
+        unsafe {
+            let children = self.children(header_ptr);
+            if let Some(offset) = self.first_bytes_offset {
+                let first_bytes = header_ptr.byte_add(offset).cast::<u8>();
+                for (i, child) in children.iter().enumerate() {
+                    first_bytes.add(i).write(child.label()[0]);
+                }
+            }
+            #[cfg(feature = "art")]
+            if let Some(offset) = self.index_offset {
+                let mut index = header_ptr.byte_add(offset).cast::<[u8; 256]>();
+                let index = index.as_mut();
+                index.fill(0);
+                for (i, child) in children.iter().enumerate() {
+                    index[child.label()[0] as usize] = i as u8 + 1;
+                }
+            }
+        }
+    }
+}
+
 impl<V> Node<V> {
     /// Makes a new node which represents an empty tree.
     pub fn root() -> Self {
@@ -120,14 +191,18 @@ impl<V> Node<V> {
     /// Gets an iterator which traverses the nodes in this tree, in depth first order.
     pub fn iter(&self) -> Iter<'_, V> {
         Iter {
-            stack: vec![(0, self)],
+            // SAFETY: self is borrowed for the lifetime of the iterator
+            traversal: unsafe { Traversal::new(NonNull::from(self)) },
+            _marker: PhantomData,
         }
     }
 
     /// Gets a mutable iterator which traverses the nodes in this tree, in depth first order.
     pub fn iter_mut(&mut self) -> IterMut<'_, V> {
         IterMut {
-            stack: vec![(0, self)],
+            // SAFETY: self is mutably borrowed for the lifetime of the iterator
+            traversal: unsafe { Traversal::new(NonNull::from(self)) },
+            _marker: PhantomData,
         }
     }
 
@@ -178,6 +253,13 @@ impl<V> Node<V> {
         Some(unsafe { self.children_mut().get_unchecked_mut(i) })
     }
     pub(crate) fn child_index_with_first(&self, byte: u8) -> Option<usize> {
+        #[cfg(feature = "art")]
+        if let Some(index) = unsafe { self.ptr_data().index(self.ptr) } {
+            return index[byte as usize].checked_sub(1).map(usize::from);
+        }
+        if let Some(first_bytes) = unsafe { self.ptr_data().first_bytes(self.ptr) } {
+            return memchr::memchr(byte, first_bytes);
+        }
         self.children_first_bytes()
             .enumerate()
             .find(|(_, b)| *b == byte)
@@ -283,10 +365,80 @@ impl<V> Node<V> {
         }
     }
 
+    /// descend the node with the key a single time, returning either the slot of the node
+    /// holding the key's value or the deepest node from which the key can be inserted
+    pub(crate) fn entry_mut(&mut self, key: &[u8]) -> NodeEntry<'_, V> {
+        let root: *mut Node<V> = self;
+        let mut parent: Option<(*mut Node<V>, usize)> = None;
+        let mut cur: *mut Node<V> = root;
+        let mut offset = 0;
+        loop {
+            // SAFETY: `cur` always points into the tree borrowed by `self`, and `parent` is only
+            // turned back into a reference once we are done with `cur`
+            let node = unsafe { &mut *cur };
+            let Some(suffix) = crate::strip_prefix(&key[offset..], node.label()) else {
+                return NodeEntry::Vacant { node, offset };
+            };
+            let Some(first) = suffix.first() else {
+                if node.value().is_none() {
+                    return NodeEntry::Vacant { node, offset };
+                }
+                let slot = match parent {
+                    None => NodeSlot::Root(node),
+                    Some((parent, index)) => NodeSlot::Child {
+                        parent_is_root: parent == root,
+                        parent: unsafe { &mut *parent },
+                        index,
+                    },
+                };
+                return NodeEntry::Occupied(slot);
+            };
+            let Some(i) = node.child_index_with_first(*first) else {
+                return NodeEntry::Vacant { node, offset };
+            };
+            offset += node.label_len();
+            parent = Some((cur, i));
+            cur = unsafe { node.children_mut().get_unchecked_mut(i) };
+        }
+    }
+
     /// split the node by the prefix into two distinct nodes
     pub fn split_by_prefix<K: ?Sized + BorrowedBytes>(&mut self, key: &K) -> Option<Self> {
-        let mut cur = self;
         let key = key.as_bytes();
+        let (detached, consumed) = self.detach_prefix(key)?;
+        Some(detached.with_prefix(&key[..consumed]))
+    }
+
+    /// detach the subtree of keys starting with `key`, returning it along with how many bytes of
+    /// `key` lie above it (the detached node's label holds the rest of its key)
+    pub(crate) fn detach_prefix(&mut self, key: &[u8]) -> Option<(Self, usize)> {
+        let detached = self.take_prefix(key)?;
+        self.compact_path(key);
+        Some(detached)
+    }
+
+    /// remove now empty nodes along the path to `key` and merge the ones left with a single child
+    fn compact_path(&mut self, key: &[u8]) {
+        let Some(i) = key
+            .first()
+            .and_then(|first| self.child_index_with_first(*first))
+        else {
+            return;
+        };
+        let child = &mut self.children_mut()[i];
+        if let Some(rest) = crate::strip_prefix(key, child.label()) {
+            child.compact_path(rest);
+        }
+        if child.value().is_none() && child.children_len() == 0 {
+            // SAFETY: `i` is the index of `child`
+            unsafe { self.remove_child(i) };
+        } else {
+            child.try_merge_child();
+        }
+    }
+
+    fn take_prefix(&mut self, key: &[u8]) -> Option<(Self, usize)> {
+        let mut cur = self;
         let mut suffix = key;
         let mut parent: *mut Node<V> = &raw mut *cur;
         // descend as we would for `get_prefix_node` but keep the parent ptr to lag behind `cur`
@@ -325,28 +477,24 @@ impl<V> Node<V> {
                 // split node so we can set label properly
                 child.split_at(suffix.len(), None);
                 // remove node we created
-                let mut detached = child.remove_child(0);
+                let detached = child.remove_child(0);
                 if parent.children_len() != 0 {
                     // remove split node that may have been left over
                     parent.remove_child(child_index);
                 }
-                // set label
-                detached.prefix_label(key);
-                Some(detached)
+                Some((detached, key.len()))
             }
         } else if suffix == child.label() {
             // we are at a leaf
             unsafe {
                 let detached = parent.remove_child(child_index);
-                parent.try_merge_child();
-                Some(detached)
+                Some((detached, key.len() - suffix.len()))
             }
         } else if suffix.is_empty() {
             // full match on node
-            let mut detached = unsafe { parent.remove_child(child_index) };
-            detached.replace_label(key);
-            parent.try_merge_child();
-            Some(detached)
+            let detached = unsafe { parent.remove_child(child_index) };
+            let consumed = key.len() - detached.label_len();
+            Some((detached, consumed))
         } else {
             // if suffix > child.label_len then we didn't descend far enough?
             None
@@ -444,6 +592,136 @@ impl<V> Node<V> {
         }
     }
 
+    /// find the smallest key in this subtree holding a value, appending its bytes (starting with
+    /// this node's label) to `key`.
+    ///
+    /// `key` is left untouched if there's no value in the subtree.
+    pub(crate) fn first_value(&self, key: &mut Vec<u8>) -> Option<&V> {
+        let key_len = key.len();
+        key.extend_from_slice(self.label());
+        if let Some(value) = self.value() {
+            return Some(value);
+        }
+        for child in self.children() {
+            if let Some(value) = child.first_value(key) {
+                return Some(value);
+            }
+        }
+        key.truncate(key_len);
+        None
+    }
+
+    /// find the largest key in this subtree holding a value, see [`first_value`](Self::first_value)
+    pub(crate) fn last_value(&self, key: &mut Vec<u8>) -> Option<&V> {
+        let key_len = key.len();
+        key.extend_from_slice(self.label());
+        for child in self.children().iter().rev() {
+            if let Some(value) = child.last_value(key) {
+                return Some(value);
+            }
+        }
+        if let Some(value) = self.value() {
+            return Some(value);
+        }
+        key.truncate(key_len);
+        None
+    }
+
+    /// find the largest key in this subtree that is less than (or equal to, if `inclusive`)
+    /// `target`.
+    ///
+    /// `key` must hold the bytes of the key leading up to this node, the found key is appended to
+    /// it.
+    pub(crate) fn floor_value(
+        &self,
+        target: &[u8],
+        inclusive: bool,
+        key: &mut Vec<u8>,
+    ) -> Option<&V> {
+        let key_len = key.len();
+        let rest = &target[key_len..];
+        let label = self.label();
+        let Some(rest) = crate::strip_prefix(rest, label) else {
+            let (common, _) = crate::longest_common_prefix(label, rest);
+            // the whole subtree is either above or below `target`
+            return if common < rest.len() && label[common] < rest[common] {
+                self.last_value(key)
+            } else {
+                None
+            };
+        };
+        key.extend_from_slice(label);
+        if let Some(&first) = rest.first() {
+            let children = self.children();
+            let i = children.partition_point(|c| c.label()[0] < first);
+            let equal = children.get(i).filter(|c| c.label()[0] == first);
+            if let Some(value) = equal.and_then(|c| c.floor_value(target, inclusive, key)) {
+                return Some(value);
+            }
+            for child in children[..i].iter().rev() {
+                if let Some(value) = child.last_value(key) {
+                    return Some(value);
+                }
+            }
+            if let Some(value) = self.value() {
+                return Some(value);
+            }
+        } else if inclusive && self.value().is_some() {
+            return self.value();
+        }
+        key.truncate(key_len);
+        None
+    }
+
+    /// find the smallest key in this subtree that is greater than (or equal to, if `inclusive`)
+    /// `target`, see [`floor_value`](Self::floor_value)
+    pub(crate) fn ceiling_value(
+        &self,
+        target: &[u8],
+        inclusive: bool,
+        key: &mut Vec<u8>,
+    ) -> Option<&V> {
+        let key_len = key.len();
+        let rest = &target[key_len..];
+        let label = self.label();
+        let Some(rest) = crate::strip_prefix(rest, label) else {
+            let (common, _) = crate::longest_common_prefix(label, rest);
+            return if common == rest.len() || label[common] > rest[common] {
+                self.first_value(key)
+            } else {
+                None
+            };
+        };
+        key.extend_from_slice(label);
+        let children = self.children();
+        if let Some(&first) = rest.first() {
+            let i = children.partition_point(|c| c.label()[0] < first);
+            let mut greater = &children[i..];
+            if let Some(child) = greater.first().filter(|c| c.label()[0] == first) {
+                if let Some(value) = child.ceiling_value(target, inclusive, key) {
+                    return Some(value);
+                }
+                greater = &greater[1..];
+            }
+            for child in greater {
+                if let Some(value) = child.first_value(key) {
+                    return Some(value);
+                }
+            }
+        } else {
+            if inclusive && self.value().is_some() {
+                return self.value();
+            }
+            for child in children {
+                if let Some(value) = child.first_value(key) {
+                    return Some(value);
+                }
+            }
+        }
+        key.truncate(key_len);
+        None
+    }
+
     /// remove the value at `key` and return it, merging the tree with its children
     /// if necessary.
     pub fn remove<K: ?Sized + BorrowedBytes>(&mut self, key: &K) -> Option<V> {
@@ -474,7 +752,14 @@ impl<V> Node<V> {
             value
         } else {
             let value = child.remove(suffix);
-            child.try_merge_child();
+            if child.value().is_none() && child.children().is_empty() {
+                // a chained label lost the key it led to
+                unsafe {
+                    self.remove_child(i);
+                }
+            } else {
+                child.try_merge_child();
+            }
             value
         }
     }
@@ -484,6 +769,10 @@ impl<V> Node<V> {
         if self.value().is_some() || self.children_len() != 1 {
             return;
         }
+        // labels longer than the max stay chained
+        if self.label_len() + self.children()[0].label_len() > MAX_LABEL_LEN {
+            return;
+        }
         let old_parent = crate::NodePtrAndData {
             ptr: self.ptr,
             ptr_data: self.ptr_data(),
@@ -502,12 +791,151 @@ impl<V> Node<V> {
         self.ptr = child.into_ptr_forget();
     }
 
+    /// Moves every value of `other` into `self`, where both nodes sit at the same key. Values
+    /// present on both sides are combined with `f`. Returns the number of keys that were in both.
+    ///
+    /// Children of `other` whose first byte is free in `self` are grafted as whole subtrees,
+    /// labels are only split where the two tries overlap.
+    pub(crate) fn merge<F>(&mut self, other: Node<V>, key: &mut Vec<u8>, f: &mut F) -> usize
+    where
+        F: FnMut(&[u8], V, V) -> V,
+    {
+        let n = crate::longest_common_prefix(self.label(), other.label()).0;
+        if n < self.label_len() {
+            // SAFETY: `n` is within the label
+            unsafe { self.split_at(n, None) };
+        }
+        let key_len = key.len();
+        key.extend_from_slice(self.label());
+        let both = if n == other.label_len() {
+            self.merge_same(other, key, f)
+        } else {
+            self.merge_child(other.strip_label(n), key, f)
+        };
+        key.truncate(key_len);
+        both
+    }
+
+    /// merge `other` into `self` when both labels are the same
+    fn merge_same<F>(&mut self, mut other: Node<V>, key: &mut Vec<u8>, f: &mut F) -> usize
+    where
+        F: FnMut(&[u8], V, V) -> V,
+    {
+        let mut both = 0;
+        if let Some(b) = other.take_value() {
+            match self.take_value() {
+                Some(a) => {
+                    both += 1;
+                    self.set_value(f(key, a, b));
+                }
+                None => self.set_value(b),
+            }
+        }
+        for child in other.take_children().into_iter().flatten() {
+            both += self.merge_child(child, key, f);
+        }
+        both
+    }
+
+    /// merge `child` in below `self`, grafting it if no child shares its first byte
+    fn merge_child<F>(&mut self, mut child: Node<V>, key: &mut Vec<u8>, f: &mut F) -> usize
+    where
+        F: FnMut(&[u8], V, V) -> V,
+    {
+        let first = child.label()[0];
+        match self.child_index_with_first(first) {
+            Some(i) => {
+                let existing = &mut self.children_mut()[i];
+                let both = existing.merge(child, key, f);
+                existing.try_merge_child();
+                both
+            }
+            None => {
+                // `child` may be the end of a label split off by `merge`
+                child.try_merge_child();
+                let i = self
+                    .children_first_bytes()
+                    .position(|b| b > first)
+                    .unwrap_or(self.children_len());
+                // SAFETY: `i` is at most the number of children
+                unsafe { self.add_child(child, i) };
+                0
+            }
+        }
+    }
+
+    /// rebuild the node without the first `n` bytes of its label
+    pub(crate) fn strip_label(mut self, n: usize) -> Self {
+        if n == 0 {
+            return self;
+        }
+        let children = self.take_children().unwrap_or_default();
+        let value = self.take_value();
+        Node::with_children(&self.label()[n..], children, value)
+    }
+
+    /// Takes out every value for which `pred` returns `true` in key order, handing it to `out`.
+    /// Children left empty are removed and valueless ones with a single child merged on the way
+    /// back up, so the whole tree is compacted in one pass. Returns the number of values taken.
+    pub(crate) fn extract_if<P, O>(&mut self, key: &mut Vec<u8>, pred: &mut P, out: &mut O) -> usize
+    where
+        P: FnMut(&[u8], &mut V) -> bool,
+        O: FnMut(&[u8], V),
+    {
+        let key_len = key.len();
+        key.extend_from_slice(self.label());
+        let mut taken = 0;
+        if self.value_mut().is_some_and(|value| pred(key, value)) {
+            out(key, some!(self.take_value()));
+            taken += 1;
+        }
+        let mut i = 0;
+        while i < self.children_len() {
+            let child = &mut self.children_mut()[i];
+            taken += child.extract_if(key, pred, out);
+            if child.value().is_none() && child.children_len() == 0 {
+                // SAFETY: `i` is the index of `child`
+                unsafe { self.remove_child(i) };
+            } else {
+                child.try_merge_child();
+                i += 1;
+            }
+        }
+        key.truncate(key_len);
+        taken
+    }
+
+    /// prepend `prefix` to the label, chaining valueless nodes above `self` for the part of the
+    /// prefix that doesn't fit in one label
+    pub(crate) fn with_prefix(mut self, prefix: &[u8]) -> Self {
+        let split = prefix
+            .len()
+            .saturating_sub(MAX_LABEL_LEN - self.label_len());
+        let (mut rest, tail) = prefix.split_at(split);
+        if !tail.is_empty() {
+            self.prefix_label(tail);
+        }
+        self.try_merge_child();
+        let mut node = self;
+        while !rest.is_empty() {
+            let (head, chunk) = rest.split_at(rest.len().saturating_sub(MAX_LABEL_LEN));
+            node = Node::new(chunk, [node], None);
+            rest = head;
+        }
+        node
+    }
+
     /// insert key and value into node
     /// SAFETY:
     /// caller must not insert an empty label into children. only the root node can have an empty label
     pub fn insert<K: ?Sized + BorrowedBytes>(&mut self, key: &K, value: V) -> Option<V> {
+        self.insert_node(key.as_bytes(), value).1
+    }
+
+    /// insert key and value into node, returning the node that now holds `value` along with the
+    /// value it replaced (if any)
+    pub(crate) fn insert_node(&mut self, mut key: &[u8], value: V) -> (&mut Self, Option<V>) {
         let mut cur = self;
-        let mut key = key.as_bytes();
         loop {
             match crate::longest_common_prefix(cur.label(), key) {
                 (0, Some(ord)) => {
@@ -528,30 +956,41 @@ impl<V> Node<V> {
                     cur.ptr = new_root.ptr;
                     mem::forget(new_root);
 
-                    return None;
+                    let i = (ord != Ordering::Greater) as usize;
+                    return (&mut cur.children_mut()[i], None);
                 }
                 (n, Some(_)) => {
                     // new child from common prefix that needs split at n
                     let (_, new_suffix) = unsafe { key.split_at_unchecked(n) };
+                    if new_suffix.len() > MAX_LABEL_LEN {
+                        // chain the first chunk and continue inserting below it
+                        let new_child = Node::new(&new_suffix[..MAX_LABEL_LEN], [], None);
+                        unsafe {
+                            cur.split_at(n, Some(new_child));
+                        }
+                        cur = some!(cur.child_with_first_mut(new_suffix[0]));
+                        key = new_suffix;
+                        continue;
+                    }
                     let new_child = Node::new(new_suffix, [], Some(value));
                     unsafe {
                         cur.split_at(n, Some(new_child));
                     }
-                    return None;
+                    return (some!(cur.child_with_first_mut(new_suffix[0])), None);
                 }
-                (n, None) => {
+                (_, None) => {
                     // new child needed but next element doesn't exist
                     match key.len().cmp(&cur.label_len()) {
                         Ordering::Less => {
                             unsafe { cur.split_at(key.len(), None) };
                             cur.set_value(value);
-                            return None;
+                            return (cur, None);
                         }
                         Ordering::Equal => {
                             // key and node are equal, replace data
                             let old_val = cur.take_value();
                             cur.set_value(value);
-                            return old_val;
+                            return (cur, old_val);
                         }
                         Ordering::Greater => {
                             // prefix match but key is longer, so we need to insert into a child
@@ -577,10 +1016,9 @@ impl<V> Node<V> {
                                         .map(|(i, _)| i)
                                         .unwrap_or(cur.children_len());
 
-                                    // if key is bigger than max len and there's no common prefix
-                                    // or the previous length was 255, we need to split off
-                                    // the first chunk and chain the labels together
-                                    if (n == 0 || n == MAX_LABEL_LEN) && key.len() > MAX_LABEL_LEN {
+                                    // if the rest of the key is bigger than max len, we need to
+                                    // split off the first chunk and chain the labels together
+                                    if key.len() > MAX_LABEL_LEN {
                                         let child: Node<V> =
                                             Node::new(&key[..MAX_LABEL_LEN], [], None);
                                         unsafe {
@@ -594,8 +1032,11 @@ impl<V> Node<V> {
                                         // SAFETY: insert_index must be <= children len
                                         unsafe {
                                             cur.add_child(child, insert_index);
+                                            return (
+                                                cur.children_mut().get_unchecked_mut(insert_index),
+                                                None,
+                                            );
                                         }
-                                        return None;
                                     }
                                 }
                             }
@@ -606,6 +1047,71 @@ impl<V> Node<V> {
         }
     }
 
+    /// Makes a node from decoded parts, rejecting any shape that would break the invariants of
+    /// the trie.
+    #[cfg(any(feature = "std", feature = "serde"))]
+    pub(crate) fn try_from_parts(
+        label: &[u8],
+        value: Option<V>,
+        children: Vec<Node<V>>,
+    ) -> Result<Self, &'static str> {
+        if label.len() > MAX_LABEL_LEN {
+            return Err("node label is longer than 255 bytes");
+        }
+        if children.len() > u8::MAX as usize {
+            return Err("node has more than 255 children");
+        }
+        if children.iter().any(|child| child.label().is_empty()) {
+            return Err("child node has an empty label");
+        }
+        if children
+            .windows(2)
+            .any(|pair| pair[0].label()[0] >= pair[1].label()[0])
+        {
+            return Err("children are not sorted by a unique first byte");
+        }
+        Ok(Node::with_children(label, children, value))
+    }
+
+    /// Checks that this node can be the root of a tree whose keys are `K`s, returning the
+    /// number of values.
+    #[cfg(any(feature = "std", feature = "serde"))]
+    pub(crate) fn check_root<K: ?Sized + BorrowedBytes>(&self) -> Result<usize, &'static str> {
+        if !self.label().is_empty() {
+            return Err("root node must have an empty label");
+        }
+        let mut len = 0;
+        let mut key = Vec::new();
+        let mut iter = self.iter();
+        while let Some((key_len, node)) = iter.next_keyed() {
+            key.truncate(key_len);
+            key.extend_from_slice(node.label());
+            if node.value().is_some() {
+                if !K::is_valid_bytes(&key) {
+                    return Err("node keys are not valid for the key type");
+                }
+                len += 1;
+            }
+        }
+        Ok(len)
+    }
+
+    /// returns the size of this node's allocation and how many of those bytes are padding
+    pub(crate) fn allocation_size(&self) -> (usize, usize) {
+        let ptr_data = self.ptr_data();
+        let used = mem::size_of::<NodeHeader>()
+            + self.label_len()
+            + self.children_len() * mem::size_of::<Node<V>>()
+            + ptr_data.value_size()
+            + ptr_data
+                .first_bytes_offset
+                .map_or(0, |_| self.children_len());
+        #[cfg(feature = "art")]
+        let used = used + ptr_data.index_offset.map_or(0, |_| 256);
+        let size = ptr_data.layout.size();
+        (size, size - used)
+    }
+
     /// return node label length
     pub fn label_len(&self) -> usize {
         self.header().label_len as usize
@@ -676,25 +1182,305 @@ impl<V> IntoIterator for Node<V> {
     }
 }
 
+/// A node on a traversal stack along with its level and the length of its parent's key.
+#[derive(Debug)]
+pub(crate) struct Frame<V> {
+    pub(crate) level: usize,
+    pub(crate) key_len: usize,
+    pub(crate) node: NonNull<Node<V>>,
+}
+impl<V> Clone for Frame<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<V> Copy for Frame<V> {}
+impl<V> Frame<V> {
+    /// SAFETY: the node must be alive
+    unsafe fn child(self, i: usize) -> Self {
+        let node = unsafe { self.node.as_ref() };
+        let first = unsafe { node.ptr_data().children_ptr(node.ptr) };
+        Frame {
+            level: self.level + 1,
+            key_len: self.key_len + node.label_len(),
+            // SAFETY: pointers are derived from the node allocation and stay valid for writes
+            node: unsafe { some!(first).add(i) },
+        }
+    }
+    /// SAFETY: the node must be alive
+    unsafe fn children(
+        self,
+        range: core::ops::Range<usize>,
+    ) -> impl DoubleEndedIterator<Item = Self> {
+        range.map(move |i| unsafe { self.child(i) })
+    }
+}
+
+/// Double ended depth first traversal over raw node pointers, shared by [`Iter`] and [`IterMut`].
+///
+/// The front yields nodes in pre-order, the back in reverse pre-order (children before their
+/// parent). Each side remembers the last node it yielded so the two ends stop once they meet.
+#[derive(Debug)]
+pub(crate) struct Traversal<V> {
+    front: Vec<Frame<V>>,
+    // the flag marks frames whose children have already been pushed
+    back: Vec<(Frame<V>, bool)>,
+    // the back stack is seeded lazily, so front-only iteration never allocates it
+    back_root: Option<Frame<V>>,
+    last_front: Option<NonNull<Node<V>>>,
+    last_back: Option<NonNull<Node<V>>>,
+    // key of the node last visited from the back, the back yields nodes before their parents
+    // so callers couldn't rebuild it themselves
+    back_key: Vec<u8>,
+}
+impl<V> Traversal<V> {
+    /// SAFETY: `root` must outlive the traversal and must not be modified structurally while
+    /// it's in use
+    unsafe fn new(root: NonNull<Node<V>>) -> Self {
+        let root = Frame {
+            level: 0,
+            key_len: 0,
+            node: root,
+        };
+        Traversal {
+            front: vec![root],
+            back: Vec::new(),
+            back_root: Some(root),
+            last_front: None,
+            last_back: None,
+            back_key: Vec::new(),
+        }
+    }
+
+    /// Traversal over the nodes whose keys lie within `start` and `end`. Subtrees entirely
+    /// outside of the bounds are skipped, but nodes on the path to either bound may still be
+    /// yielded so callers must check bounds themselves.
+    ///
+    /// Also returns the front key buffer, holding the key of the deepest node visited while
+    /// seeking `start`.
+    ///
+    /// SAFETY: same as [`new`](Self::new)
+    unsafe fn range(
+        root: NonNull<Node<V>>,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> (Self, Vec<u8>) {
+        let root = Frame {
+            level: 0,
+            key_len: 0,
+            node: root,
+        };
+        let (front, front_key) = unsafe { Self::seek_front(root, start) };
+        let (back, back_key) = unsafe { Self::seek_back(root, end) };
+        let traversal = Traversal {
+            front,
+            back,
+            back_root: None,
+            last_front: None,
+            last_back: None,
+            back_key,
+        };
+        (traversal, front_key)
+    }
+
+    /// SAFETY: the tree under `root` must be alive
+    unsafe fn seek_front(mut frame: Frame<V>, start: Bound<&[u8]>) -> (Vec<Frame<V>>, Vec<u8>) {
+        let mut stack = Vec::new();
+        let mut key = Vec::new();
+        let (start, inclusive) = match start {
+            Bound::Unbounded => return (vec![frame], key),
+            Bound::Included(start) => (start, true),
+            Bound::Excluded(start) => (start, false),
+        };
+        loop {
+            let node = unsafe { frame.node.as_ref() };
+            let rest = &start[key.len()..];
+            let label = node.label();
+            let Some(rest) = crate::strip_prefix(rest, label) else {
+                // the whole subtree is either above or below `start`
+                let (common, _) = crate::longest_common_prefix(label, rest);
+                if common == rest.len() || label[common] > rest[common] {
+                    stack.push(frame);
+                }
+                break;
+            };
+            key.extend_from_slice(label);
+            let children_len = node.children_len();
+            let Some(&first) = rest.first() else {
+                if inclusive {
+                    stack.push(frame);
+                } else {
+                    stack.extend(unsafe { frame.children(0..children_len) }.rev());
+                }
+                break;
+            };
+            let children = node.children();
+            let i = children.partition_point(|c| c.label()[0] < first);
+            let equal = children.get(i).is_some_and(|c| c.label()[0] == first);
+            let greater = i + equal as usize..children_len;
+            stack.extend(unsafe { frame.children(greater) }.rev());
+            if !equal {
+                break;
+            }
+            frame = unsafe { frame.child(i) };
+        }
+        (stack, key)
+    }
+
+    /// SAFETY: the tree under `root` must be alive
+    unsafe fn seek_back(
+        mut frame: Frame<V>,
+        end: Bound<&[u8]>,
+    ) -> (Vec<(Frame<V>, bool)>, Vec<u8>) {
+        let mut stack = Vec::new();
+        let mut key = Vec::new();
+        let (end, inclusive) = match end {
+            Bound::Unbounded => return (vec![(frame, false)], key),
+            Bound::Included(end) => (end, true),
+            Bound::Excluded(end) => (end, false),
+        };
+        loop {
+            let node = unsafe { frame.node.as_ref() };
+            let rest = &end[key.len()..];
+            let label = node.label();
+            let Some(rest) = crate::strip_prefix(rest, label) else {
+                let (common, _) = crate::longest_common_prefix(label, rest);
+                if common < rest.len() && label[common] < rest[common] {
+                    stack.push((frame, false));
+                }
+                break;
+            };
+            key.extend_from_slice(label);
+            let Some(&first) = rest.first() else {
+                // children sort after `end`
+                if inclusive {
+                    stack.push((frame, true));
+                }
+                break;
+            };
+            // this node sorts before `end`, and is yielded after its children
+            stack.push((frame, true));
+            let children = node.children();
+            let i = children.partition_point(|c| c.label()[0] < first);
+            stack.extend(unsafe { frame.children(0..i) }.map(|c| (c, false)));
+            let equal = children.get(i).is_some_and(|c| c.label()[0] == first);
+            if !equal {
+                break;
+            }
+            frame = unsafe { frame.child(i) };
+        }
+        (stack, key)
+    }
+
+    /// stop the traversal on both ends
+    pub(crate) fn clear(&mut self) {
+        self.front.clear();
+        self.back.clear();
+        self.back_root = None;
+    }
+
+    /// key of the node last returned by [`next_back`](Self::next_back)
+    pub(crate) fn back_key(&self) -> &[u8] {
+        &self.back_key
+    }
+
+    fn next(&mut self) -> Option<Frame<V>> {
+        let frame = self.front.pop()?;
+        if Some(frame.node) == self.last_back {
+            self.clear();
+            return None;
+        }
+        // SAFETY: nodes are alive for as long as the traversal is
+        let children_len = unsafe { frame.node.as_ref() }.children_len();
+        self.front
+            .extend(unsafe { frame.children(0..children_len) }.rev());
+        self.last_front = Some(frame.node);
+        Some(frame)
+    }
+
+    fn next_back(&mut self) -> Option<Frame<V>> {
+        if let Some(root) = self.back_root.take() {
+            self.back.push((root, false));
+        }
+        loop {
+            let (frame, expanded) = self.back.pop()?;
+            self.back_key.truncate(frame.key_len);
+            // SAFETY: nodes are alive for as long as the traversal is
+            let node = unsafe { frame.node.as_ref() };
+            self.back_key.extend_from_slice(node.label());
+            if expanded {
+                if Some(frame.node) == self.last_front {
+                    self.clear();
+                    return None;
+                }
+                self.last_back = Some(frame.node);
+                return Some(frame);
+            }
+            self.back.push((frame, true));
+            let children = unsafe { frame.children(0..node.children_len()) };
+            self.back.extend(children.map(|c| (c, false)));
+        }
+    }
+}
+
 /// An iterator which traverses the nodes in a tree, in depth first order.
 ///
 /// The first element of an item is the level of the traversing node.
 #[derive(Debug)]
 pub struct Iter<'a, V: 'a> {
-    stack: Vec<(usize, &'a Node<V>)>,
+    traversal: Traversal<V>,
+    _marker: PhantomData<&'a Node<V>>,
+}
+// SAFETY: behaves like a collection of `&'a Node<V>`
+unsafe impl<V: Sync> Send for Iter<'_, V> {}
+unsafe impl<V: Sync> Sync for Iter<'_, V> {}
+impl<'a, V: 'a> Iter<'a, V> {
+    /// Iterator over the nodes with keys within the given bounds, see [`Traversal::range`].
+    pub(crate) fn range(
+        root: &'a Node<V>,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> (Self, Vec<u8>) {
+        // SAFETY: root is borrowed for 'a
+        let (traversal, front_key) = unsafe { Traversal::range(NonNull::from(root), start, end) };
+        let iter = Iter {
+            traversal,
+            _marker: PhantomData,
+        };
+        (iter, front_key)
+    }
+    /// stop iterating on both ends
+    pub(crate) fn clear(&mut self) {
+        self.traversal.clear();
+    }
+    /// key of the node last yielded from the back, relative to the root of the iteration
+    pub(crate) fn back_key(&self) -> &[u8] {
+        self.traversal.back_key()
+    }
+    /// like `next`, but yielding the length of the parent key instead of the level
+    pub(crate) fn next_keyed(&mut self) -> Option<(usize, &'a Node<V>)> {
+        let frame = self.traversal.next()?;
+        Some((frame.key_len, unsafe { frame.node.as_ref() }))
+    }
+    /// like `next_back`, but yielding the length of the parent key instead of the level
+    pub(crate) fn next_back_keyed(&mut self) -> Option<(usize, &'a Node<V>)> {
+        let frame = self.traversal.next_back()?;
+        Some((frame.key_len, unsafe { frame.node.as_ref() }))
+    }
 }
 impl<'a, V: 'a> Iterator for Iter<'a, V> {
     type Item = (usize, &'a Node<V>);
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some((level, node)) = self.stack.pop() {
-            let next_level = level + 1;
-            for child in node.children().iter().rev() {
-                self.stack.push((next_level, child))
-            }
-            Some((level, node))
-        } else {
-            None
-        }
+        let frame = self.traversal.next()?;
+        // SAFETY: the tree is borrowed for 'a
+        Some((frame.level, unsafe { frame.node.as_ref() }))
+    }
+}
+impl<'a, V: 'a> DoubleEndedIterator for Iter<'a, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let frame = self.traversal.next_back()?;
+        // SAFETY: the tree is borrowed for 'a
+        Some((frame.level, unsafe { frame.node.as_ref() }))
     }
 }
 
@@ -725,24 +1511,65 @@ impl<'a, V: 'a> Iterator for IterBfs<'a, V> {
 /// The first element of an item is the level of the traversing node.
 #[derive(Debug)]
 pub struct IterMut<'a, V: 'a> {
-    stack: Vec<(usize, &'a mut Node<V>)>,
+    traversal: Traversal<V>,
+    _marker: PhantomData<&'a mut Node<V>>,
+}
+// SAFETY: behaves like a collection of `&'a mut V`
+unsafe impl<V: Send> Send for IterMut<'_, V> {}
+unsafe impl<V: Sync> Sync for IterMut<'_, V> {}
+impl<'a, V: 'a> IterMut<'a, V> {
+    /// Mutable iterator over the nodes with keys within the given bounds, see
+    /// [`Traversal::range`].
+    pub(crate) fn range(
+        root: &'a mut Node<V>,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> (Self, Vec<u8>) {
+        // SAFETY: root is mutably borrowed for 'a
+        let (traversal, front_key) = unsafe { Traversal::range(NonNull::from(root), start, end) };
+        let iter = IterMut {
+            traversal,
+            _marker: PhantomData,
+        };
+        (iter, front_key)
+    }
+    /// stop iterating on both ends
+    pub(crate) fn clear(&mut self) {
+        self.traversal.clear();
+    }
+    /// key of the node last yielded from the back, relative to the root of the iteration
+    pub(crate) fn back_key(&self) -> &[u8] {
+        self.traversal.back_key()
+    }
+    /// like `next`, but yielding the length of the parent key instead of the level
+    pub(crate) fn next_keyed(&mut self) -> Option<(usize, NodeMut<'a, V>)> {
+        let frame = self.traversal.next()?;
+        Some((frame.key_len, unsafe { Self::node_mut(frame) }))
+    }
+    /// like `next_back`, but yielding the length of the parent key instead of the level
+    pub(crate) fn next_back_keyed(&mut self) -> Option<(usize, NodeMut<'a, V>)> {
+        let frame = self.traversal.next_back()?;
+        Some((frame.key_len, unsafe { Self::node_mut(frame) }))
+    }
+    /// SAFETY: each frame must only be converted once, the traversal never yields a node twice.
+    /// The children are left out, they are still reachable through the traversal.
+    unsafe fn node_mut(mut frame: Frame<V>) -> NodeMut<'a, V> {
+        let mut node = unsafe { frame.node.as_mut() }.as_mut();
+        node.children = None;
+        node
+    }
 }
-
 impl<'a, V: 'a> Iterator for IterMut<'a, V> {
     type Item = (usize, NodeMut<'a, V>);
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some((level, node)) = self.stack.pop() {
-            let mut node = node.as_mut();
-            let next_level = level + 1;
-            if let Some(children) = node.children.take() {
-                for child in children.iter_mut().rev() {
-                    self.stack.push((next_level, child))
-                }
-            }
-            Some((level, node))
-        } else {
-            None
-        }
+        let frame = self.traversal.next()?;
+        Some((frame.level, unsafe { Self::node_mut(frame) }))
+    }
+}
+impl<'a, V: 'a> DoubleEndedIterator for IterMut<'a, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let frame = self.traversal.next_back()?;
+        Some((frame.level, unsafe { Self::node_mut(frame) }))
     }
 }
 
@@ -888,6 +1715,81 @@ where
     }
 }
 
+/// Result of descending a tree with a key via `Node::entry_mut`.
+pub(crate) enum NodeEntry<'a, V> {
+    /// the key is stored in the tree
+    Occupied(NodeSlot<'a, V>),
+    /// the key is not stored in the tree, it can be inserted from `node` with `key[offset..]`
+    Vacant {
+        node: &'a mut Node<V>,
+        offset: usize,
+    },
+}
+
+/// A mutable reference to a node along with enough of its surroundings to unlink it
+/// from the tree without descending again.
+pub(crate) enum NodeSlot<'a, V> {
+    /// the node is the root of the tree
+    Root(&'a mut Node<V>),
+    /// the node is the child at `index` of `parent`
+    Child {
+        parent: &'a mut Node<V>,
+        index: usize,
+        parent_is_root: bool,
+    },
+}
+
+impl<'a, V> NodeSlot<'a, V> {
+    pub(crate) fn node(&self) -> &Node<V> {
+        match self {
+            NodeSlot::Root(node) => node,
+            NodeSlot::Child { parent, index, .. } => &parent.children()[*index],
+        }
+    }
+
+    pub(crate) fn node_mut(&mut self) -> &mut Node<V> {
+        match self {
+            NodeSlot::Root(node) => node,
+            NodeSlot::Child { parent, index, .. } => &mut parent.children_mut()[*index],
+        }
+    }
+
+    pub(crate) fn into_node_mut(self) -> &'a mut Node<V> {
+        match self {
+            NodeSlot::Root(node) => node,
+            NodeSlot::Child { parent, index, .. } => &mut parent.children_mut()[index],
+        }
+    }
+
+    /// take the value out of the node, removing or merging nodes that are no longer needed
+    /// the same way `Node::remove` does
+    pub(crate) fn remove_value(self) -> Option<V> {
+        match self {
+            NodeSlot::Root(node) => node.take_value(),
+            NodeSlot::Child {
+                parent,
+                index,
+                parent_is_root,
+            } => {
+                let child = &mut parent.children_mut()[index];
+                let value = child.take_value();
+                if child.children().is_empty() {
+                    unsafe {
+                        parent.remove_child(index);
+                    }
+                    // the root keeps its (empty) label, so it is never merged with its child
+                    if !parent_is_root {
+                        parent.try_merge_child();
+                    }
+                } else {
+                    child.try_merge_child();
+                }
+                value
+            }
+        }
+    }
+}
+
 /// A reference to an immediate node (without child or sibling) with its
 /// label and a mutable reference to its value, if present.
 pub struct NodeMut<'a, V: 'a> {
@@ -1251,6 +2153,63 @@ mod tests {
         assert_eq!(node.get("2"), Some(&2));
     }
 
+    #[test]
+    fn test_insert_long_label_below_short_label() {
+        let mut node = Node::root();
+        node.insert("ab", 1);
+        node.insert("ax", 2);
+
+        // long suffix below a label that is neither the root nor a full chunk
+        let below = format!("ab{}", "c".repeat(300));
+        node.insert(below.as_str(), 3);
+        // long suffix split off from the middle of a label
+        let split = format!("a{}", "d".repeat(600));
+        node.insert(split.as_str(), 4);
+
+        assert_eq!(node.get("ab"), Some(&1));
+        assert_eq!(node.get("ax"), Some(&2));
+        assert_eq!(node.get(below.as_str()), Some(&3));
+        assert_eq!(node.get(split.as_str()), Some(&4));
+        assert!(node.iter().all(|(_, n)| n.label_len() <= MAX_LABEL_LEN));
+    }
+
+    #[test]
+    fn test_remove_keeps_long_labels_chained() {
+        let mut node = Node::root();
+        let prefix = "x".repeat(MAX_LABEL_LEN);
+        let a = format!("{prefix}a12");
+        let b = format!("{prefix}b34");
+        node.insert(a.as_str(), 1);
+        node.insert(b.as_str(), 2);
+
+        assert_eq!(node.remove(a.as_str()), Some(1));
+        assert_eq!(node.get(b.as_str()), Some(&2));
+        assert!(node.iter().all(|(_, n)| n.label_len() <= MAX_LABEL_LEN));
+    }
+
+    #[test]
+    fn test_remove_prunes_emptied_chains() {
+        let mut node = Node::root();
+        let long = "a".repeat(600);
+        let short = "a".repeat(300);
+        node.insert(long.as_str(), 1);
+        node.insert(short.as_str(), 2);
+        node.insert("b", 3);
+        let is_compact = |node: &Node<i32>| {
+            node.iter()
+                .skip(1)
+                .all(|(_, n)| n.value().is_some() || !n.children().is_empty())
+        };
+
+        assert_eq!(node.remove(long.as_str()), Some(1));
+        assert!(is_compact(&node));
+        assert_eq!(node.get(short.as_str()), Some(&2));
+        assert_eq!(node.remove(short.as_str()), Some(2));
+        assert!(is_compact(&node));
+        assert_eq!(node.iter().filter(|(_, n)| n.value().is_some()).count(), 1);
+        assert_eq!(node.children_len(), 1);
+    }
+
     #[test]
     #[should_panic]
     fn test_children_push_more_than_255_items() {
@@ -1579,6 +2538,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn iter_rev_works() {
+        let mut set = Node::root();
+        set.insert("foo", ());
+        set.insert("bar", ());
+        set.insert("baz", ());
+
+        let expected = [
+            (1, "foo".as_ref()),
+            (2, "z".as_ref()),
+            (2, "r".as_ref()),
+            (1, "ba".as_ref()),
+            (0, "".as_ref()),
+        ];
+        let nodes = set
+            .iter()
+            .rev()
+            .map(|(level, node)| (level, node.label()))
+            .collect::<Vec<_>>();
+        assert_eq!(nodes, expected);
+        let nodes = set
+            .iter_mut()
+            .rev()
+            .map(|(level, node)| (level, node.label()))
+            .collect::<Vec<_>>();
+        assert_eq!(nodes, expected);
+
+        // both ends meet in the middle
+        let mut iter = set.iter();
+        assert_eq!(iter.next().map(|(_, n)| n.label()), Some("".as_ref()));
+        assert_eq!(
+            iter.next_back().map(|(_, n)| n.label()),
+            Some("foo".as_ref())
+        );
+        assert_eq!(iter.next_back().map(|(_, n)| n.label()), Some("z".as_ref()));
+        assert_eq!(iter.next().map(|(_, n)| n.label()), Some("ba".as_ref()));
+        assert_eq!(iter.next().map(|(_, n)| n.label()), Some("r".as_ref()));
+        assert!(iter.next().is_none());
+        assert!(iter.next_back().is_none());
+    }
+
     #[test]
     fn node_into_iter_works() {
         let mut set = Node::root();
@@ -1766,6 +2766,49 @@ mod tests {
         assert_eq!(other, None);
     }
 
+    #[test]
+    fn test_split_by_prefix_long_prefix_and_label() {
+        // the prefix above the detached node and its label don't fit in one label together,
+        // which used to overflow the label length and corrupt the node (visible under miri)
+        let prefix = "a".repeat(200);
+        let long = format!("{prefix}{}", "b".repeat(200));
+        let below = format!("{long}c");
+        for split in [&long[..300], &long[..]] {
+            let mut root = Node::root();
+            root.insert(long.as_str(), 1);
+            root.insert(below.as_str(), 2);
+            root.insert(format!("{prefix}d").as_str(), 3);
+
+            let other = root.split_by_prefix(split).unwrap();
+            assert_eq!(other.get(long.as_str()), Some(&1));
+            assert_eq!(other.get(below.as_str()), Some(&2));
+            assert_eq!(root.get(long.as_str()), None);
+            assert_eq!(root.get(format!("{prefix}d").as_str()), Some(&3));
+            assert!(other.iter().all(|(_, n)| n.label_len() <= MAX_LABEL_LEN));
+        }
+    }
+
+    #[test]
+    fn test_merge_compresses_grafted_remainder() {
+        // `other` is chained at different offsets than `root`, so the merge grafts the end of
+        // one of its labels below a leaf of `root`
+        let long = "a".repeat(300);
+        let mut root = Node::root();
+        root.insert(long.as_str(), 1);
+        let mut other = Node::root();
+        other.insert("c", 2);
+        let other = other.with_prefix(format!("{long}aaa").as_bytes());
+
+        assert_eq!(root.merge(other, &mut Vec::new(), &mut |_, _, b| b), 0);
+        assert_eq!(root.get(long.as_str()), Some(&1));
+        assert_eq!(root.get(format!("{long}aaac").as_str()), Some(&2));
+        assert!(root.iter().skip(1).all(|(_, n)| {
+            n.value().is_some()
+                || n.children_len() != 1
+                || n.label_len() + n.children()[0].label_len() > MAX_LABEL_LEN
+        }));
+    }
+
     #[test]
     fn test_take_children() {
         let mut root = create_bigger_test_tree();
@@ -1861,6 +2904,57 @@ mod tests {
         assert_eq!(lcp("123456789"), Some("123456".as_bytes()));
     }
 
+    #[test]
+    fn wide_nodes_match_btreemap() {
+        use rand::Rng;
+        use std::collections::BTreeMap;
+
+        let mut rng = rand::rng();
+        let mut map = crate::RadixMap::new();
+        let mut expected = BTreeMap::new();
+        for i in 0..if cfg!(miri) { 300 } else { 20_000 } {
+            // stay below 256 distinct bytes per node, the most children a node can hold
+            let key = (0..rng.random_range(1..4))
+                .map(|_| rng.random_range(0..250u8))
+                .collect::<Vec<_>>();
+            if rng.random_ratio(1, 3) {
+                assert_eq!(map.remove(&key), expected.remove(&key));
+            } else {
+                assert_eq!(map.insert(&key, i), expected.insert(key, i));
+            }
+        }
+        assert!(map.iter().map(|(k, v)| (k, *v)).eq(expected.clone()));
+        for (_, node) in map.as_node().iter() {
+            for byte in 0..=u8::MAX {
+                let scanned = node.children_first_bytes().position(|b| b == byte);
+                assert_eq!(node.child_index_with_first(byte), scanned);
+            }
+        }
+        let cloned = map.clone();
+        assert!(expected.iter().all(|(k, v)| cloned.get(k) == Some(v)));
+    }
+
+    #[cfg(all(feature = "art", feature = "first-bytes"))]
+    #[test]
+    fn wide_nodes_skip_first_bytes() {
+        let mut node = Node::root();
+        for byte in 0..WIDE_FANOUT {
+            node.insert(&[byte][..], byte);
+        }
+        let ptr_data = node.ptr_data();
+        assert!(ptr_data.index_offset.is_some());
+        assert!(ptr_data.first_bytes_offset.is_none());
+
+        node.remove(&[0][..]);
+        let ptr_data = node.ptr_data();
+        assert!(ptr_data.index_offset.is_none());
+        assert!(ptr_data.first_bytes_offset.is_some());
+        for byte in 0..=u8::MAX {
+            let scanned = node.children_first_bytes().position(|b| b == byte);
+            assert_eq!(node.child_index_with_first(byte), scanned);
+        }
+    }
+
     #[test]
     fn get_longest_common_prefix_mut_works() {
         let mut map = [
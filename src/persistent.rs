@@ -0,0 +1,683 @@
+//! Persistent maps that share structure between versions, enabled by the `persistent` feature.
+//!
+//! Unlike [`GenericRadixMap`], whose nodes own their children, a [`GenericPersistentRadixMap`]
+//! keeps every child behind an [`Arc`]. Cloning a map only bumps the count on its root, and
+//! [`insert`](GenericPersistentRadixMap::insert) and [`remove`](GenericPersistentRadixMap::remove)
+//! return a new version that copies the nodes on the path to the key, sharing every other subtree
+//! with the version they started from. Old versions are never modified, so they can be handed to
+//! readers, on other threads too, while writers keep producing new ones.
+//!
+//! # Examples
+//!
+//! ```
+//! use fast_radix_trie::StringPersistentRadixMap;
+//!
+//! let v1: StringPersistentRadixMap<u32> = [("10.0", 1), ("10.1", 2)].into_iter().collect();
+//! let v2 = v1.insert("10.2", 3).remove("10.0");
+//!
+//! assert_eq!(v1.get("10.0"), Some(&1));
+//! assert_eq!(v1.len(), 2);
+//! assert_eq!(v2.get("10.0"), None);
+//! assert_eq!(v2.keys().collect::<Vec<_>>(), ["10.1", "10.2"]);
+//! ```
+use crate::{BorrowedBytes, Bytes, GenericRadixMap, node_common::some};
+use alloc::{borrow::ToOwned, boxed::Box, string::String, sync::Arc, vec::Vec};
+use core::{fmt, iter::FromIterator, marker::PhantomData, mem};
+
+/// Persistent radix tree based map with [`Vec<u8>`] as key.
+pub type PersistentRadixMap<V> = GenericPersistentRadixMap<Vec<u8>, V>;
+
+/// Persistent radix tree based map with [`String`] as key.
+pub type StringPersistentRadixMap<V> = GenericPersistentRadixMap<String, V>;
+
+#[derive(Clone)]
+struct PersistentNode<V> {
+    label: Box<[u8]>,
+    value: Option<V>,
+    /// sorted by the first byte of their label, which is never empty
+    children: Vec<Arc<PersistentNode<V>>>,
+}
+
+impl<V> PersistentNode<V> {
+    fn root() -> Self {
+        PersistentNode {
+            label: Box::default(),
+            value: None,
+            children: Vec::new(),
+        }
+    }
+
+    fn child_index(&self, byte: u8) -> Result<usize, usize> {
+        self.children
+            .binary_search_by_key(&byte, |child| child.label[0])
+    }
+
+    /// `key` is the part of the key below this node
+    fn get(&self, mut key: &[u8]) -> Option<&V> {
+        let mut node = self;
+        loop {
+            let Some(&first) = key.first() else {
+                return node.value.as_ref();
+            };
+            node = &node.children[node.child_index(first).ok()?];
+            key = crate::strip_prefix(key, &node.label)?;
+        }
+    }
+
+    /// the topmost node whose keys all start with `prefix`, with the key length above it
+    fn find_prefix(&self, prefix: &[u8]) -> Option<(usize, &Self)> {
+        let mut node = self;
+        let mut len = 0;
+        while let Some(&first) = prefix.get(len) {
+            node = &node.children[node.child_index(first).ok()?];
+            let (common, _) = crate::longest_common_prefix(&node.label, &prefix[len..]);
+            if len + common == prefix.len() {
+                return Some((len, node));
+            }
+            if common < node.label.len() {
+                return None;
+            }
+            len += common;
+        }
+        Some((0, node))
+    }
+}
+
+impl<V: Clone> PersistentNode<V> {
+    /// Inserts below this node, copying the shared nodes on the way down.
+    fn insert(&mut self, key: &[u8], value: V) -> Option<V> {
+        let Some(&first) = key.first() else {
+            return self.value.replace(value);
+        };
+        let i = match self.child_index(first) {
+            Ok(i) => i,
+            Err(i) => {
+                let leaf = PersistentNode {
+                    label: key.into(),
+                    value: Some(value),
+                    children: Vec::new(),
+                };
+                self.children.insert(i, Arc::new(leaf));
+                return None;
+            }
+        };
+        let child = Arc::make_mut(&mut self.children[i]);
+        let (common, _) = crate::longest_common_prefix(&child.label, key);
+        if common < child.label.len() {
+            child.split(common);
+        }
+        child.insert(&key[common..], value)
+    }
+
+    /// moves everything past `at` in the label into a new only child
+    fn split(&mut self, at: usize) {
+        let tail = PersistentNode {
+            label: self.label[at..].into(),
+            value: self.value.take(),
+            children: mem::take(&mut self.children),
+        };
+        self.label = self.label[..at].into();
+        self.children.push(Arc::new(tail));
+    }
+
+    /// Removes a key that's known to be below this node, copying the shared nodes on the way
+    /// down and compacting the ones left without a value.
+    fn remove(&mut self, key: &[u8]) -> Option<V> {
+        let Some(&first) = key.first() else {
+            return self.value.take();
+        };
+        let i = self.child_index(first).ok()?;
+        let child = Arc::make_mut(&mut self.children[i]);
+        let old = child.remove(&key[child.label.len()..]);
+        if child.value.is_none() {
+            match child.children.len() {
+                0 => {
+                    self.children.remove(i);
+                }
+                1 => child.merge_child(),
+                _ => {}
+            }
+        }
+        old
+    }
+
+    /// absorbs the only child of a node without a value
+    fn merge_child(&mut self) {
+        let child = Arc::unwrap_or_clone(some!(self.children.pop()));
+        let mut label = mem::take(&mut self.label).into_vec();
+        label.extend_from_slice(&child.label);
+        self.label = label.into();
+        self.value = child.value;
+        self.children = child.children;
+    }
+}
+
+/// Persistent radix tree based map, where every version shares its unchanged subtrees with the
+/// versions it was derived from.
+///
+/// See the [`persistent`](crate::persistent) module.
+pub struct GenericPersistentRadixMap<K, V> {
+    root: Arc<PersistentNode<V>>,
+    len: usize,
+    _key: PhantomData<K>,
+}
+
+impl<K, V> GenericPersistentRadixMap<K, V> {
+    /// Makes a new empty persistent map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::PersistentRadixMap;
+    ///
+    /// let map = PersistentRadixMap::<u32>::new();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        GenericPersistentRadixMap {
+            root: Arc::new(PersistentNode::root()),
+            len: 0,
+            _key: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements in this map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if both maps are the same version, sharing their root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::PersistentRadixMap;
+    ///
+    /// let a = PersistentRadixMap::new().insert("foo", 1);
+    /// let b = a.clone();
+    /// assert!(a.ptr_eq(&b));
+    /// assert!(!a.ptr_eq(&b.insert("bar", 2)));
+    /// ```
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.root, &other.root)
+    }
+
+    /// Gets an iterator over the values of this map, in order by key.
+    pub fn values(&self) -> Values<'_, V> {
+        Values {
+            stack: vec![&*self.root],
+        }
+    }
+}
+
+impl<K: Bytes, V> GenericPersistentRadixMap<K, V> {
+    /// Returns `true` if this map contains a value for the specified key.
+    pub fn contains_key<Q: AsRef<K::Borrowed>>(&self, key: Q) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::PersistentRadixMap;
+    ///
+    /// let map = PersistentRadixMap::new().insert("foo", 1);
+    /// assert_eq!(map.get("foo"), Some(&1));
+    /// assert_eq!(map.get("bar"), None);
+    /// ```
+    pub fn get<Q: AsRef<K::Borrowed>>(&self, key: Q) -> Option<&V> {
+        self.root.get(key.as_ref().as_bytes())
+    }
+
+    /// Gets an iterator over the entries of this map, sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::PersistentRadixMap;
+    ///
+    /// let map: PersistentRadixMap<_> = [("foo", 1), ("bar", 2)].into_iter().collect();
+    /// assert_eq!(map.iter().collect::<Vec<_>>(), [(Vec::from("bar"), &2), ("foo".into(), &1)]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            stack: vec![(0, &*self.root)],
+            key: Vec::new(),
+            _key: PhantomData,
+        }
+    }
+
+    /// Gets an iterator over the keys of this map, in sorted order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys(self.iter())
+    }
+
+    /// Gets an iterator over the entries having the given prefix of this map, sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::PersistentRadixMap;
+    ///
+    /// let map: PersistentRadixMap<_> = [("foo", 1), ("bar", 2), ("baz", 3)].into_iter().collect();
+    /// assert_eq!(
+    ///     map.iter_prefix(b"ba").collect::<Vec<_>>(),
+    ///     [(Vec::from("bar"), &2), ("baz".into(), &3)]
+    /// );
+    /// ```
+    pub fn iter_prefix(&self, prefix: &K::Borrowed) -> Iter<'_, K, V> {
+        let prefix = prefix.as_bytes();
+        let (stack, key) = match self.root.find_prefix(prefix) {
+            Some((len, node)) => (vec![(len, node)], prefix[..len].to_vec()),
+            None => (Vec::new(), Vec::new()),
+        };
+        Iter {
+            stack,
+            key,
+            _key: PhantomData,
+        }
+    }
+
+    /// Finds the longest common prefix of `key` and the keys in this map,
+    /// and returns a reference to the entry whose key matches the prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::PersistentRadixMap;
+    ///
+    /// let map = PersistentRadixMap::new().insert("foo", 1).insert("foobar", 2);
+    /// assert_eq!(map.get_longest_common_prefix("fo"), None);
+    /// assert_eq!(map.get_longest_common_prefix("fooba"), Some(("foo".as_bytes(), &1)));
+    /// assert_eq!(map.get_longest_common_prefix("foobarbaz"), Some(("foobar".as_bytes(), &2)));
+    /// ```
+    pub fn get_longest_common_prefix<'a, Q>(&self, key: &'a Q) -> Option<(&'a K::Borrowed, &V)>
+    where
+        Q: ?Sized + AsRef<K::Borrowed>,
+    {
+        self.common_prefixes(key).last()
+    }
+
+    /// Returns an iterator over the entries whose keys are prefixes of `key`, shortest first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringPersistentRadixMap;
+    ///
+    /// let map: StringPersistentRadixMap<_> =
+    ///     [("a", 1), ("ab", 2), ("abcd", 3), ("x", 4)].into_iter().collect();
+    /// assert_eq!(
+    ///     map.common_prefixes("abcde").collect::<Vec<_>>(),
+    ///     [("a", &1), ("ab", &2), ("abcd", &3)]
+    /// );
+    /// ```
+    pub fn common_prefixes<'a, 'b, Q>(
+        &'a self,
+        key: &'b Q,
+    ) -> CommonPrefixesIter<'a, 'b, K::Borrowed, V>
+    where
+        Q: ?Sized + AsRef<K::Borrowed>,
+    {
+        CommonPrefixesIter {
+            node: Some(&self.root),
+            key_bytes: key.as_ref().as_bytes(),
+            len: 0,
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<K: Bytes, V: Clone> GenericPersistentRadixMap<K, V> {
+    /// Returns a new version of this map with `key` set to `value`.
+    ///
+    /// Only the nodes on the path to `key` are copied, along with their values, everything else
+    /// is shared with `self`, which is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::PersistentRadixMap;
+    ///
+    /// let v1 = PersistentRadixMap::new().insert("foo", 1);
+    /// let v2 = v1.insert("foo", 2);
+    /// assert_eq!(v1.get("foo"), Some(&1));
+    /// assert_eq!(v2.get("foo"), Some(&2));
+    /// ```
+    pub fn insert<Q: AsRef<K::Borrowed>>(&self, key: Q, value: V) -> Self {
+        let mut map = self.clone();
+        map.insert_in_place(key.as_ref().as_bytes(), value);
+        map
+    }
+
+    /// Returns a new version of this map without `key`.
+    ///
+    /// If the map doesn't contain `key`, the new version is just a clone of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::PersistentRadixMap;
+    ///
+    /// let v1: PersistentRadixMap<_> = [("foo", 1), ("bar", 2)].into_iter().collect();
+    /// let v2 = v1.remove("foo");
+    /// assert_eq!(v1.len(), 2);
+    /// assert_eq!(v2.len(), 1);
+    /// assert!(v2.remove("foo").ptr_eq(&v2));
+    /// ```
+    pub fn remove<Q: AsRef<K::Borrowed>>(&self, key: Q) -> Self {
+        let mut map = self.clone();
+        map.remove_in_place(key.as_ref().as_bytes());
+        map
+    }
+
+    /// path copying insert, which copies nothing once this version holds the only reference
+    pub(crate) fn insert_in_place(&mut self, key: &[u8], value: V) -> Option<V> {
+        let old = Arc::make_mut(&mut self.root).insert(key, value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// path copying remove, see [`insert_in_place`](Self::insert_in_place)
+    pub(crate) fn remove_in_place(&mut self, key: &[u8]) -> Option<V> {
+        // checked up front so a missing key doesn't copy the path to it
+        self.root.get(key)?;
+        self.len -= 1;
+        Arc::make_mut(&mut self.root).remove(key)
+    }
+}
+
+impl<K, V> Clone for GenericPersistentRadixMap<K, V> {
+    /// Makes a new handle on the same version in O(1).
+    fn clone(&self) -> Self {
+        GenericPersistentRadixMap {
+            root: Arc::clone(&self.root),
+            len: self.len,
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<K, V> Default for GenericPersistentRadixMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Bytes, V: fmt::Debug> fmt::Debug for GenericPersistentRadixMap<K, V>
+where
+    K::Borrowed: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut map = f.debug_map();
+        let mut iter = self.iter();
+        while let Some((key, value)) = iter.next_raw() {
+            map.entry(&K::Borrowed::from_bytes(key), value);
+        }
+        map.finish()
+    }
+}
+
+impl<K, Q, V> FromIterator<(Q, V)> for GenericPersistentRadixMap<K, V>
+where
+    K: Bytes,
+    Q: AsRef<K::Borrowed>,
+    V: Clone,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (Q, V)>,
+    {
+        let mut map = GenericPersistentRadixMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, Q, V> Extend<(Q, V)> for GenericPersistentRadixMap<K, V>
+where
+    K: Bytes,
+    Q: AsRef<K::Borrowed>,
+    V: Clone,
+{
+    /// Inserts every entry into this version, copying shared nodes only the first time they
+    /// are reached.
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (Q, V)>,
+    {
+        for (key, value) in iter {
+            self.insert_in_place(key.as_ref().as_bytes(), value);
+        }
+    }
+}
+
+impl<K: Bytes, V: Clone> From<GenericRadixMap<K, V>> for GenericPersistentRadixMap<K, V>
+where
+    K: AsRef<K::Borrowed>,
+{
+    fn from(map: GenericRadixMap<K, V>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+/// An iterator over a persistent map's entries, sorted by key.
+pub struct Iter<'a, K, V> {
+    // nodes still to visit, with the key length of their parent
+    stack: Vec<(usize, &'a PersistentNode<V>)>,
+    key: Vec<u8>,
+    _key: PhantomData<K>,
+}
+impl<'a, K, V> Iter<'a, K, V> {
+    fn next_raw(&mut self) -> Option<(&[u8], &'a V)> {
+        while let Some((key_len, node)) = self.stack.pop() {
+            self.key.truncate(key_len);
+            self.key.extend_from_slice(&node.label);
+            let key_len = self.key.len();
+            self.stack
+                .extend(node.children.iter().rev().map(|child| (key_len, &**child)));
+            if let Some(value) = &node.value {
+                return Some((&self.key, value));
+            }
+        }
+        None
+    }
+}
+impl<K, V> fmt::Debug for Iter<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Iter").field("key", &self.key).finish()
+    }
+}
+impl<'a, K: Bytes, V> Iterator for Iter<'a, K, V> {
+    type Item = (K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_raw()
+            .map(|(key, value)| (K::Borrowed::from_bytes(key).to_owned(), value))
+    }
+}
+
+/// An iterator over a persistent map's keys, in sorted order.
+#[derive(Debug)]
+pub struct Keys<'a, K, V>(Iter<'a, K, V>);
+impl<K: Bytes, V> Iterator for Keys<'_, K, V> {
+    type Item = K;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, _)| k)
+    }
+}
+
+/// An iterator over the entries of a persistent map whose keys are prefixes of a given key.
+pub struct CommonPrefixesIter<'a, 'b, K: ?Sized, V> {
+    // the next node on the path to the key, which sits `len` bytes into it
+    node: Option<&'a PersistentNode<V>>,
+    key_bytes: &'b [u8],
+    len: usize,
+    _key: PhantomData<&'b K>,
+}
+impl<K: ?Sized, V> fmt::Debug for CommonPrefixesIter<'_, '_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CommonPrefixesIter")
+            .field("key_bytes", &self.key_bytes)
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+impl<'a, 'b, K, V> Iterator for CommonPrefixesIter<'a, 'b, K, V>
+where
+    K: 'b + ?Sized + BorrowedBytes,
+{
+    type Item = (&'b K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.node.take() {
+            let len = self.len;
+            let rest = &self.key_bytes[len..];
+            if let Some(Ok(i)) = rest.first().map(|&first| node.child_index(first)) {
+                let child = &*node.children[i];
+                if rest.starts_with(&child.label) {
+                    self.node = Some(child);
+                    self.len += child.label.len();
+                }
+            }
+            if let Some(value) = &node.value {
+                return Some((K::from_bytes(&self.key_bytes[..len]), value));
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over a persistent map's values, in order by key.
+pub struct Values<'a, V> {
+    stack: Vec<&'a PersistentNode<V>>,
+}
+impl<V> fmt::Debug for Values<'_, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Values").finish_non_exhaustive()
+    }
+}
+impl<'a, V> Iterator for Values<'a, V> {
+    type Item = &'a V;
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            self.stack
+                .extend(node.children.iter().rev().map(|child| &**child));
+            if let Some(value) = &node.value {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RadixMap;
+    use rand::Rng;
+    use std::collections::BTreeMap;
+
+    fn assert_compressed<V>(node: &PersistentNode<V>, is_root: bool) {
+        if !is_root {
+            assert!(!node.label.is_empty());
+            assert!(node.value.is_some() || node.children.len() > 1);
+        }
+        assert!(node.children.is_sorted_by_key(|child| child.label[0]));
+        for child in &node.children {
+            assert_compressed(child, false);
+        }
+    }
+
+    #[test]
+    fn versions_match_btree_map() {
+        let mut rng = rand::rng();
+        let mut versions = vec![(PersistentRadixMap::new(), BTreeMap::new())];
+        for i in 0..if cfg!(miri) { 100 } else { 3000 } {
+            let (map, expected) = &versions[rng.random_range(0..versions.len())];
+            let mut key = (0..rng.random_range(0..6))
+                .map(|_| b"abc"[rng.random_range(0..3)])
+                .collect::<Vec<_>>();
+            if rng.random_ratio(1, 20) {
+                key.extend(b"x".repeat(300));
+            }
+            let mut expected = expected.clone();
+            let map = if rng.random_ratio(1, 3) {
+                expected.remove(&key);
+                map.remove(&key)
+            } else {
+                expected.insert(key.clone(), i);
+                map.insert(&key, i)
+            };
+            versions.push((map, expected));
+        }
+        for (map, expected) in &versions {
+            assert_eq!(map.len(), expected.len());
+            assert!(map.iter().eq(expected.iter().map(|(k, v)| (k.clone(), v))));
+            assert!(map.values().eq(expected.values()));
+            assert_compressed(&map.root, true);
+        }
+    }
+
+    #[test]
+    fn untouched_subtrees_are_shared() {
+        let v1: PersistentRadixMap<u32> = [("apple", 1), ("apply", 2), ("box", 3), ("boxer", 4)]
+            .into_iter()
+            .collect();
+        let v2 = v1.insert("apricot", 5);
+
+        // the `box` subtree is shared, the `ap` path was copied
+        assert!(Arc::ptr_eq(&v1.root.children[1], &v2.root.children[1]));
+        assert!(!Arc::ptr_eq(&v1.root.children[0], &v2.root.children[0]));
+        assert_eq!(v1.get("apricot"), None);
+        assert_eq!(v2.get("apricot"), Some(&5));
+
+        let v3 = v2.remove("apricot");
+        assert_eq!(v3.len(), 4);
+        assert!(v3.iter().eq(v1.iter()));
+        assert!(Arc::ptr_eq(&v1.root.children[1], &v3.root.children[1]));
+
+        let map: RadixMap<u32> = v3.iter().map(|(k, v)| (k, *v)).collect();
+        assert!(PersistentRadixMap::from(map).iter().eq(v3.iter()));
+    }
+
+    #[test]
+    fn prefix_queries_match_radix_map() {
+        let mut rng = rand::rng();
+        let mut map = PersistentRadixMap::new();
+        let mut expected = RadixMap::new();
+        let random_key = |rng: &mut rand::rngs::ThreadRng| {
+            let mut key = (0..rng.random_range(0..6))
+                .map(|_| b"abc"[rng.random_range(0..3)])
+                .collect::<Vec<_>>();
+            if rng.random_ratio(1, 20) {
+                key.extend(b"x".repeat(300));
+            }
+            key
+        };
+        for i in 0..if cfg!(miri) { 30 } else { 300 } {
+            let key = random_key(&mut rng);
+            map = map.insert(&key, i);
+            expected.insert(key, i);
+        }
+        for _ in 0..if cfg!(miri) { 30 } else { 300 } {
+            let mut key = random_key(&mut rng);
+            key.extend(random_key(&mut rng));
+            let key = &key[..rng.random_range(0..=key.len())];
+            assert!(map.iter_prefix(key).eq(expected.iter_prefix(key)));
+            assert_eq!(
+                map.get_longest_common_prefix(key),
+                expected.get_longest_common_prefix(key)
+            );
+            assert!(map.common_prefixes(key).eq(expected.common_prefixes(key)));
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! [Rayon](https://docs.rs/rayon) support, enabled by the `rayon` feature.
+//!
+//! Parallel iteration splits the tree into independent subtrees, starting from the root's
+//! children and descending a level at a time until there are a few subtrees per thread. Each
+//! subtree is then walked sequentially, so results still come out sorted by key when collected.
+//!
+//! Collecting a parallel iterator into a map or set groups the entries by first byte, builds
+//! the subtree of each group in parallel and stitches the subtrees under a new root.
+//!
+//! # Examples
+//!
+//! ```
+//! use fast_radix_trie::StringRadixMap;
+//! use rayon::prelude::*;
+//!
+//! let mut map: StringRadixMap<u32> =
+//!     (0..1000).into_par_iter().map(|i| (i.to_string(), i)).collect();
+//! map.par_values_mut().for_each(|v| *v *= 2);
+//! assert_eq!(map.par_iter().map(|(_, v)| *v as u64).sum::<u64>(), 999_000);
+//! assert_eq!(map.get("42"), Some(&84));
+//! ```
+use crate::{
+    BorrowedBytes, Bytes, GenericRadixMap, GenericRadixSet, Node,
+    map::{Iter, IterMut},
+    node_common::NodeMut,
+    tree::{Nodes, NodesMut, RadixTrie},
+};
+use ::rayon::iter::{Either, FromParallelIterator, IntoParallelIterator, ParallelIterator};
+use alloc::{borrow::ToOwned, vec::Vec};
+use core::iter;
+
+/// Subtrees to hand out per thread, so a few large ones don't leave the other threads idle.
+const PARTS_PER_THREAD: usize = 4;
+
+/// One unit of parallel work: a single value, or a whole subtree along with its parent's key.
+enum Part<K, N, R> {
+    Value(K, R),
+    Subtree(K, N),
+}
+
+/// Splits the tree under `root` a level at a time, until there are enough parts to keep every
+/// thread busy or only leaves are left. Parts are in key order.
+fn split<V>(root: &Node<V>) -> Vec<Part<Vec<u8>, &Node<V>, &V>> {
+    let target = ::rayon::current_num_threads() * PARTS_PER_THREAD;
+    let mut parts = vec![Part::Subtree(Vec::new(), root)];
+    while parts.len() < target
+        && parts
+            .iter()
+            .any(|part| matches!(part, Part::Subtree(_, node) if node.children_len() > 0))
+    {
+        let mut next = Vec::with_capacity(parts.len());
+        for part in parts {
+            match part {
+                Part::Subtree(mut key, node) => {
+                    key.extend_from_slice(node.label());
+                    if let Some(value) = node.value() {
+                        next.push(Part::Value(key.clone(), value));
+                    }
+                    for child in node.children() {
+                        next.push(Part::Subtree(key.clone(), child));
+                    }
+                }
+                value => next.push(value),
+            }
+        }
+        parts = next;
+    }
+    parts
+}
+
+/// Mutable version of [`split`].
+fn split_mut<V>(root: &mut Node<V>) -> Vec<Part<Vec<u8>, &mut Node<V>, &mut V>> {
+    let target = ::rayon::current_num_threads() * PARTS_PER_THREAD;
+    let mut parts = vec![Part::Subtree(Vec::new(), root)];
+    while parts.len() < target
+        && parts
+            .iter()
+            .any(|part| matches!(part, Part::Subtree(_, node) if node.children_len() > 0))
+    {
+        let mut next = Vec::with_capacity(parts.len());
+        for part in parts {
+            match part {
+                Part::Subtree(mut key, node) => {
+                    let NodeMut {
+                        label,
+                        value,
+                        children,
+                    } = node.as_mut();
+                    key.extend_from_slice(label);
+                    if let Some(value) = value {
+                        next.push(Part::Value(key.clone(), value));
+                    }
+                    for child in children.into_iter().flatten() {
+                        next.push(Part::Subtree(key.clone(), child));
+                    }
+                }
+                value => next.push(value),
+            }
+        }
+        parts = next;
+    }
+    parts
+}
+
+fn par_entries<K, V>(root: &Node<V>) -> impl ParallelIterator<Item = (K, &V)>
+where
+    K: Bytes + Send,
+    V: Sync,
+{
+    split(root)
+        .into_par_iter()
+        .flat_map_iter(|part| match part {
+            Part::Value(key, value) => Either::Left(iter::once((
+                K::Borrowed::from_bytes(&key).to_owned(),
+                value,
+            ))),
+            Part::Subtree(key, node) => Either::Right(Iter::new(Nodes::subtree(node), key)),
+        })
+}
+
+impl<K: Bytes + Send, V: Sync> GenericRadixMap<K, V> {
+    /// Gets a parallel iterator over the entries of this map.
+    ///
+    /// Collecting it yields the entries sorted by key, like [`iter`](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let map: RadixMap<_> =
+    ///     vec![("foo", 1), ("bar", 2), ("baz", 3)].into_iter().collect();
+    /// assert_eq!(vec![(Vec::from("bar"), &2), ("baz".into(), &3), ("foo".into(), &1)],
+    ///            map.par_iter().collect::<Vec<_>>());
+    /// ```
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = (K, &V)> {
+        par_entries(self.as_node())
+    }
+}
+
+impl<K: Bytes + Send, V: Send> GenericRadixMap<K, V> {
+    /// Gets a parallel mutable iterator over the entries of this map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let mut map: StringRadixMap<_> =
+    ///     vec![("foo", 1), ("bar", 2), ("baz", 3)].into_iter().collect();
+    /// map.par_iter_mut().for_each(|(k, v)| *v += k.len());
+    /// assert_eq!(map.get("bar"), Some(&5));
+    /// ```
+    pub fn par_iter_mut(&mut self) -> impl ParallelIterator<Item = (K, &mut V)> {
+        split_mut(self.root_mut())
+            .into_par_iter()
+            .flat_map_iter(|part| match part {
+                Part::Value(key, value) => Either::Left(iter::once((
+                    K::Borrowed::from_bytes(&key).to_owned(),
+                    value,
+                ))),
+                Part::Subtree(key, node) => {
+                    Either::Right(IterMut::new(NodesMut::subtree(node), key))
+                }
+            })
+    }
+
+    /// Gets a parallel mutable iterator over the values of this map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let mut map: RadixMap<_> =
+    ///     vec![("foo", 1), ("bar", 2), ("baz", 3)].into_iter().collect();
+    /// map.par_values_mut().for_each(|v| *v += 10);
+    /// assert_eq!(vec![12, 13, 11],
+    ///            map.values().cloned().collect::<Vec<_>>());
+    /// ```
+    pub fn par_values_mut(&mut self) -> impl ParallelIterator<Item = &mut V> {
+        split_mut(self.root_mut())
+            .into_par_iter()
+            .flat_map_iter(|part| match part {
+                Part::Value(_, value) => Either::Left(iter::once(value)),
+                Part::Subtree(_, node) => Either::Right(
+                    NodesMut::subtree(node).filter_map(|(_, node)| node.into_value_mut()),
+                ),
+            })
+    }
+}
+
+impl<T: Bytes + Send> GenericRadixSet<T> {
+    /// Gets a parallel iterator over the contents of this set.
+    ///
+    /// Collecting it yields the items in sorted order, like [`iter`](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    /// use rayon::prelude::*;
+    ///
+    /// let set: StringRadixSet = ["rust", "ruby", "erlang"].into_iter().collect();
+    /// assert_eq!(set.par_iter().collect::<Vec<_>>(), ["erlang", "ruby", "rust"]);
+    /// ```
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = T> {
+        par_entries(self.as_node()).map(|(item, _)| item)
+    }
+}
+
+/// Entries grouped by the first byte of their key, the last group holds the empty key.
+struct Buckets<Q, V>(Vec<Vec<(Q, V)>>);
+
+impl<Q, V> Buckets<Q, V> {
+    fn new() -> Self {
+        Buckets((0..=u8::MAX as usize + 1).map(|_| Vec::new()).collect())
+    }
+
+    fn push<K: ?Sized + BorrowedBytes>(mut self, key: Q, value: V) -> Self
+    where
+        Q: AsRef<K>,
+    {
+        let i = key
+            .as_ref()
+            .as_bytes()
+            .first()
+            .map_or(u8::MAX as usize + 1, |&b| b as usize);
+        self.0[i].push((key, value));
+        self
+    }
+
+    /// appends `other` after `self`, keeping later entries last
+    fn append(mut self, other: Self) -> Self {
+        for (a, mut b) in self.0.iter_mut().zip(other.0) {
+            a.append(&mut b);
+        }
+        self
+    }
+}
+
+impl<K, Q, V> FromParallelIterator<(Q, V)> for GenericRadixMap<K, V>
+where
+    K: Bytes,
+    Q: AsRef<K::Borrowed> + Send,
+    V: Send,
+{
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = (Q, V)>,
+    {
+        let Buckets(mut buckets) = par_iter
+            .into_par_iter()
+            .fold(Buckets::new, |buckets, (key, value)| {
+                buckets.push::<K::Borrowed>(key, value)
+            })
+            .reduce(Buckets::new, Buckets::append);
+        // like `insert`, the last value given for a key wins
+        let value = buckets
+            .pop()
+            .and_then(|mut empty| empty.pop())
+            .map(|(_, value)| value);
+        let subtrees = buckets
+            .into_par_iter()
+            .filter(|bucket| !bucket.is_empty())
+            .map(|bucket| {
+                let mut tree = RadixTrie::new();
+                for (key, value) in bucket {
+                    tree.insert(key.as_ref(), value);
+                }
+                let len = tree.len();
+                // every key starts with the same byte, so the root has a single child
+                let child = tree.into_root().take_children().and_then(|mut c| c.pop());
+                (child, len)
+            })
+            .collect::<Vec<_>>();
+
+        let mut len = usize::from(value.is_some());
+        let mut children = Vec::with_capacity(subtrees.len());
+        for (child, n) in subtrees {
+            children.extend(child);
+            len += n;
+        }
+        GenericRadixMap::from_root(Node::with_children(b"", children, value), len)
+    }
+}
+
+impl<T, U> FromParallelIterator<U> for GenericRadixSet<T>
+where
+    T: Bytes,
+    U: AsRef<T::Borrowed> + Send,
+{
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = U>,
+    {
+        let map: GenericRadixMap<T, ()> = par_iter.into_par_iter().map(|v| (v, ())).collect();
+        let len = map.len();
+        GenericRadixSet::from_root(map.into_node(), len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RadixMap, RadixSet};
+    use ::rayon::prelude::*;
+    use rand::Rng;
+
+    fn random_entries(n: usize) -> Vec<(Vec<u8>, usize)> {
+        let mut rng = rand::rng();
+        (0..n)
+            .map(|i| {
+                let key = (0..rng.random_range(0..8))
+                    .map(|_| b"abcd"[rng.random_range(0..4)])
+                    .collect::<Vec<_>>();
+                (key, i)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn par_iter_matches_iter() {
+        for n in [0, 1, 5, if cfg!(miri) { 40 } else { 3000 }] {
+            let mut map = random_entries(n).into_iter().collect::<RadixMap<_>>();
+            // a long shared prefix forces splitting below the root
+            map.insert("z".repeat(600), 0);
+            map.insert("z".repeat(601), 1);
+
+            assert!(
+                map.par_iter()
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .eq(map.iter())
+            );
+
+            let expected = map.iter().map(|(k, v)| (k, v + 1)).collect::<Vec<_>>();
+            map.par_iter_mut().for_each(|(_, v)| *v += 1);
+            assert!(map.iter().map(|(k, v)| (k, *v)).eq(expected.clone()));
+            let keys = map.par_iter_mut().map(|(k, _)| k).collect::<Vec<_>>();
+            assert!(keys.into_iter().eq(map.keys()));
+
+            map.par_values_mut().for_each(|v| *v += 1);
+            assert!(map.values().zip(&expected).all(|(v, (_, e))| *v == e + 1));
+        }
+    }
+
+    #[test]
+    fn from_par_iter_matches_collect() {
+        let mut entries = random_entries(if cfg!(miri) { 40 } else { 3000 });
+        entries.push((Vec::new(), 1));
+        entries.push((Vec::new(), 2));
+        entries.extend((0..=200u8).map(|b| (vec![b, b], b as usize)));
+
+        let map = entries.par_iter().cloned().collect::<RadixMap<_>>();
+        let expected = entries.iter().cloned().collect::<RadixMap<_>>();
+        assert_eq!(map.len(), expected.len());
+        assert!(map.iter().eq(expected.iter()));
+        assert_eq!(map.get(b""), Some(&2));
+        assert_eq!(map.as_node(), expected.as_node());
+
+        let set = entries.par_iter().map(|(k, _)| k).collect::<RadixSet>();
+        assert_eq!(set.len(), expected.len());
+        assert!(
+            set.par_iter()
+                .collect::<Vec<_>>()
+                .into_iter()
+                .eq(expected.keys())
+        );
+
+        assert!(
+            Vec::<(Vec<u8>, u8)>::new()
+                .into_par_iter()
+                .collect::<RadixMap<_>>()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! Scanning text for every occurrence of every key of a map, Aho–Corasick style.
+//!
+//! [`GenericRadixMap::compile`](crate::GenericRadixMap::compile) turns each position of the
+//! trie, inside labels as well as at nodes, into a state of an automaton, and links every state
+//! to the state of its longest proper suffix that's also a position of the trie. A [`Scanner`]
+//! then reads text a byte at a time, following those failure links instead of backtracking,
+//! so a scan takes time linear in the length of the text plus the number of matches.
+//!
+//! # Examples
+//!
+//! ```
+//! use fast_radix_trie::StringRadixMap;
+//!
+//! let map: StringRadixMap<_> =
+//!     vec![("he", 1), ("she", 2), ("his", 3), ("hers", 4)].into_iter().collect();
+//! let scanner = map.compile();
+//! assert_eq!(scanner.scan("ushers").collect::<Vec<_>>(),
+//!            [(1, 4, &2), (2, 4, &1), (2, 6, &4)]);
+//! ```
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::node::Node;
+use crate::node_common::some;
+
+const ROOT: usize = 0;
+const NONE: usize = usize::MAX;
+
+#[derive(Debug)]
+struct State<'a, V> {
+    /// the transitions out of this state are `edges[edges_start..edges_end]`, sorted by byte
+    edges_start: usize,
+    edges_end: usize,
+    /// the state of the longest proper suffix of this one's key
+    fail: usize,
+    /// the nearest state with a value along the failure links, or `NONE`
+    output: usize,
+    /// the length of this state's key
+    depth: usize,
+    value: Option<&'a V>,
+}
+
+/// An Aho–Corasick automaton over the keys of a map, for finding them in text.
+///
+/// This `struct` is created by the [`compile`](crate::GenericRadixMap::compile) method on
+/// [`GenericRadixMap`](crate::GenericRadixMap). See the [`scanner`](crate::scanner) module.
+pub struct Scanner<'a, V> {
+    states: Vec<State<'a, V>>,
+    /// the transitions of every state, each pointing at the state reached by its byte
+    edges: Vec<(u8, usize)>,
+    /// the transitions out of the root, for every byte
+    root: [usize; 256],
+}
+
+impl<'a, V> Scanner<'a, V> {
+    pub(crate) fn new(root: &'a Node<V>) -> Self {
+        // states are numbered breadth first, so the targets of a state's edges are known when
+        // they're pushed and every failure link points at a state already linked
+        let mut positions = vec![(root, 0)];
+        let mut states = Vec::new();
+        let mut edges = Vec::new();
+        let mut i = 0;
+        while let Some(&(node, offset)) = positions.get(i) {
+            let edges_start = edges.len();
+            let label = node.label();
+            let mut value = None;
+            if offset < label.len() {
+                edges.push((label[offset], positions.len()));
+                positions.push((node, offset + 1));
+            } else {
+                value = node.value();
+                for child in node.children() {
+                    // only the root may have an empty label
+                    edges.push((child.label()[0], positions.len()));
+                    positions.push((child, 1));
+                }
+            }
+            states.push(State {
+                edges_start,
+                edges_end: edges.len(),
+                fail: ROOT,
+                output: NONE,
+                depth: 0,
+                value,
+            });
+            i += 1;
+        }
+
+        let mut scanner = Scanner {
+            states,
+            edges,
+            root: [ROOT; 256],
+        };
+        let root = &scanner.states[ROOT];
+        for &(byte, next) in &scanner.edges[root.edges_start..root.edges_end] {
+            scanner.root[usize::from(byte)] = next;
+        }
+        for state in 0..scanner.states.len() {
+            let State {
+                edges_start,
+                edges_end,
+                depth,
+                ..
+            } = scanner.states[state];
+            for edge in edges_start..edges_end {
+                let (byte, next) = scanner.edges[edge];
+                let fail = if state == ROOT {
+                    ROOT
+                } else {
+                    scanner.next_state(scanner.states[state].fail, byte)
+                };
+                let output = if scanner.states[fail].value.is_some() {
+                    fail
+                } else {
+                    scanner.states[fail].output
+                };
+                let next = &mut scanner.states[next];
+                next.fail = fail;
+                next.output = output;
+                next.depth = depth + 1;
+            }
+        }
+        scanner
+    }
+
+    /// Returns the state reached from `state` by `byte`, following failure links as needed.
+    fn next_state(&self, mut state: usize, byte: u8) -> usize {
+        loop {
+            if state == ROOT {
+                return self.root[usize::from(byte)];
+            }
+            let State {
+                edges_start,
+                edges_end,
+                fail,
+                ..
+            } = self.states[state];
+            let edges = &self.edges[edges_start..edges_end];
+            if let Ok(i) = edges.binary_search_by_key(&byte, |&(byte, _)| byte) {
+                return edges[i].1;
+            }
+            state = fail;
+        }
+    }
+
+    /// Returns the number of states, one per distinct prefix of the keys.
+    pub fn num_states(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Gets an iterator over every occurrence of every key in `text`, overlapping ones
+    /// included, as `(start, end, value)` with `text[start..end]` being the key.
+    ///
+    /// Occurrences are sorted by end, and the longest come first among those ending at the
+    /// same offset. An empty key occurs at every offset, `text.len()` included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    ///
+    /// let map: RadixMap<_> = vec![("ab", 'x'), ("b", 'y'), ("bab", 'z')].into_iter().collect();
+    /// let scanner = map.compile();
+    /// assert_eq!(scanner.scan("abab").collect::<Vec<_>>(),
+    ///            [(0, 2, &'x'), (1, 2, &'y'), (1, 4, &'z'), (2, 4, &'x'), (3, 4, &'y')]);
+    /// ```
+    pub fn scan<'s, T>(&'s self, text: &'s T) -> Scan<'s, 'a, V>
+    where
+        T: ?Sized + AsRef<[u8]>,
+    {
+        Scan {
+            scanner: self,
+            text: text.as_ref(),
+            offset: 0,
+            state: ROOT,
+            pending: if self.states[ROOT].value.is_some() {
+                ROOT
+            } else {
+                NONE
+            },
+        }
+    }
+
+    /// Returns `true` if any key occurs in `text`.
+    pub fn is_match<T: ?Sized + AsRef<[u8]>>(&self, text: &T) -> bool {
+        self.scan(text).next().is_some()
+    }
+}
+
+impl<V> fmt::Debug for Scanner<'_, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Scanner")
+            .field("num_states", &self.states.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// An iterator over the occurrences of keys in a text.
+///
+/// This `struct` is created by the [`scan`](Scanner::scan) method on [`Scanner`].
+pub struct Scan<'s, 'a, V> {
+    scanner: &'s Scanner<'a, V>,
+    text: &'s [u8],
+    /// the number of bytes of the text read
+    offset: usize,
+    state: usize,
+    /// the next state with a value to report at `offset`, or `NONE`
+    pending: usize,
+}
+
+impl<'a, V> Iterator for Scan<'_, 'a, V> {
+    type Item = (usize, usize, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let states = &self.scanner.states;
+        while self.pending == NONE {
+            let &byte = self.text.get(self.offset)?;
+            self.offset += 1;
+            self.state = self.scanner.next_state(self.state, byte);
+            self.pending = if states[self.state].value.is_some() {
+                self.state
+            } else {
+                states[self.state].output
+            };
+        }
+        let state = &states[self.pending];
+        self.pending = state.output;
+        Some((self.offset - state.depth, self.offset, some!(state.value)))
+    }
+}
+
+impl<V> fmt::Debug for Scan<'_, '_, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Scan")
+            .field("offset", &self.offset)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RadixMap, StringRadixMap};
+
+    /// Runs `common_prefixes` at every offset.
+    fn quadratic<'a>(map: &'a RadixMap<usize>, text: &[u8]) -> Vec<(usize, usize, &'a usize)> {
+        let mut found = (0..=text.len())
+            .flat_map(|start| {
+                map.common_prefixes(&text[start..])
+                    .map(move |(key, value)| (start, start + key.len(), value))
+            })
+            .collect::<Vec<_>>();
+        found.sort_by_key(|&(start, end, _)| (end, start));
+        found
+    }
+
+    #[test]
+    fn scan_matches_common_prefixes_at_every_offset() {
+        use rand::Rng;
+
+        let mut rng = rand::rng();
+        for _ in 0..if cfg!(miri) { 3 } else { 200 } {
+            let alphabet = &b"abcd"[..rng.random_range(1..=4)];
+            let mut word = |max| {
+                (0..rng.random_range(0..max))
+                    .map(|_| alphabet[rng.random_range(0..alphabet.len())])
+                    .collect::<Vec<_>>()
+            };
+            let map: RadixMap<usize> = (0..word(20).len()).map(|i| (word(6), i)).collect();
+            let scanner = map.compile();
+            assert_eq!(scanner.num_states(), {
+                let mut prefixes = map
+                    .keys()
+                    .flat_map(|k| (0..=k.len()).map(move |i| k[..i].to_vec()))
+                    .collect::<Vec<_>>();
+                prefixes.sort();
+                prefixes.dedup();
+                prefixes.len().max(1)
+            });
+            for _ in 0..10 {
+                let text = word(40);
+                let found = scanner.scan(&text).collect::<Vec<_>>();
+                assert_eq!(found, quadratic(&map, &text), "{text:?}");
+                assert_eq!(scanner.is_match(&text), !found.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn scan_reports_byte_offsets_in_str() {
+        let map: StringRadixMap<_> = [("é", 1), ("€", 2), ("é€", 3)].into_iter().collect();
+        let text = "aé€é";
+        let found = map
+            .compile()
+            .scan(text)
+            .map(|(start, end, &value)| (&text[start..end], value))
+            .collect::<Vec<_>>();
+        assert_eq!(found, [("é", 1), ("é€", 3), ("€", 2), ("é", 1)]);
+    }
+
+    #[test]
+    fn scan_is_linear_on_repetitive_text() {
+        let map: RadixMap<_> = [("a".repeat(100), 0), ("a".repeat(99) + "b", 1)]
+            .into_iter()
+            .collect();
+        let text = "a".repeat(if cfg!(miri) { 300 } else { 100_000 });
+        let scanner = map.compile();
+        assert_eq!(scanner.num_states(), 102);
+        assert_eq!(scanner.scan(&text).count(), text.len() - 99);
+        assert!(!scanner.is_match(&"a".repeat(99)));
+    }
+
+    #[test]
+    fn empty_maps_and_keys() {
+        let map = RadixMap::<()>::new();
+        assert_eq!(map.compile().scan("abc").count(), 0);
+
+        let map: RadixMap<_> = [("", 0), ("b", 1)].into_iter().collect();
+        assert_eq!(
+            map.compile().scan("ab").collect::<Vec<_>>(),
+            [(0, 0, &0), (1, 1, &0), (1, 2, &1), (2, 2, &0)]
+        );
+    }
+}
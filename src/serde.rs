@@ -0,0 +1,534 @@
+//! [Serde](https://serde.rs) support, enabled by the `serde` feature.
+//!
+//! Maps serialize as ordinary maps and sets as ordinary sequences, both sorted by key, so they
+//! are interchangeable with `BTreeMap`/`BTreeSet` in formats like JSON or TOML.
+//!
+//! [`Node`] serializes structurally, as a struct with a `label`, a `value` and its `children`.
+//! Human readable formats leave out the `value` field of a node without a value.
+//! The [`structural`] module applies that encoding to whole maps and sets, which preserves the
+//! compressed shape of the tree and rebuilds it without re-inserting every key.
+//!
+//! # Examples
+//!
+//! ```
+//! use fast_radix_trie::StringRadixMap;
+//!
+//! let map: StringRadixMap<u32> = [("foo", 1), ("bar", 2)].into_iter().collect();
+//! let json = serde_json::to_string(&map).unwrap();
+//! assert_eq!(json, r#"{"bar":2,"foo":1}"#);
+//!
+//! let back: StringRadixMap<u32> = serde_json::from_str(&json).unwrap();
+//! assert_eq!(back.get("foo"), Some(&1));
+//! ```
+use crate::{Bytes, GenericRadixMap, GenericRadixSet, Node};
+use ::serde::{
+    de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    ser::{Serialize, SerializeMap, SerializeSeq, SerializeStruct, Serializer},
+};
+use alloc::vec::Vec;
+use core::{fmt, marker::PhantomData};
+
+impl<K, V> Serialize for GenericRadixMap<K, V>
+where
+    K: Bytes,
+    K::Borrowed: Serialize,
+    V: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        let mut iter = self.raw_iter();
+        while let Some((key, value)) = iter.next() {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for GenericRadixMap<K, V>
+where
+    K: Bytes + AsRef<K::Borrowed> + Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MapVisitor<K, V>(PhantomData<(K, V)>);
+        impl<'de, K, V> Visitor<'de> for MapVisitor<K, V>
+        where
+            K: Bytes + AsRef<K::Borrowed> + Deserialize<'de>,
+            V: Deserialize<'de>,
+        {
+            type Value = GenericRadixMap<K, V>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut map = GenericRadixMap::new();
+                while let Some((key, value)) = access.next_entry::<K, V>()? {
+                    map.insert(key, value);
+                }
+                Ok(map)
+            }
+        }
+        deserializer.deserialize_map(MapVisitor(PhantomData))
+    }
+}
+
+impl<T> Serialize for GenericRadixSet<T>
+where
+    T: Bytes,
+    T::Borrowed: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        let mut iter = self.raw_iter();
+        while let Some(item) = iter.next() {
+            seq.serialize_element(item)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for GenericRadixSet<T>
+where
+    T: Bytes + AsRef<T::Borrowed> + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SetVisitor<T>(PhantomData<T>);
+        impl<'de, T> Visitor<'de> for SetVisitor<T>
+        where
+            T: Bytes + AsRef<T::Borrowed> + Deserialize<'de>,
+        {
+            type Value = GenericRadixSet<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a sequence")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut set = GenericRadixSet::new();
+                while let Some(item) = access.next_element::<T>()? {
+                    set.insert(item);
+                }
+                Ok(set)
+            }
+        }
+        deserializer.deserialize_seq(SetVisitor(PhantomData))
+    }
+}
+
+const NODE_FIELDS: &[&str] = &["label", "value", "children"];
+
+// Human readable formats usually collapse `Some(())` and `None` into the same `null`, so there
+// an absent value is written by leaving the field out. Other formats write an `Option<V>`.
+impl<V: Serialize> Serialize for Node<V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let human_readable = serializer.is_human_readable();
+        let skip_value = human_readable && self.value().is_none();
+        let len = NODE_FIELDS.len() - skip_value as usize;
+        let mut state = serializer.serialize_struct("Node", len)?;
+        state.serialize_field("label", &Label(self.label()))?;
+        match self.value() {
+            Some(value) if human_readable => state.serialize_field("value", value)?,
+            None if human_readable => state.skip_field("value")?,
+            value => state.serialize_field("value", &value)?,
+        }
+        state.serialize_field("children", self.children())?;
+        state.end()
+    }
+}
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for Node<V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let visitor = NodeVisitor {
+            human_readable: deserializer.is_human_readable(),
+            _value: PhantomData,
+        };
+        deserializer.deserialize_struct("Node", NODE_FIELDS, visitor)
+    }
+}
+
+/// Node labels are written as bytes, which binary formats store compactly.
+struct Label<'a>(&'a [u8]);
+impl Serialize for Label<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// Accepts a label as bytes, a sequence of bytes or a string.
+struct LabelBuf(Vec<u8>);
+impl<'de> Deserialize<'de> for LabelBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct LabelVisitor;
+        impl<'de> Visitor<'de> for LabelVisitor {
+            type Value = LabelBuf;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a node label")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(LabelBuf(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(LabelBuf(v))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(LabelBuf(v.as_bytes().to_vec()))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut label = Vec::with_capacity(access.size_hint().unwrap_or(0));
+                while let Some(byte) = access.next_element()? {
+                    label.push(byte);
+                }
+                Ok(LabelBuf(label))
+            }
+        }
+        deserializer.deserialize_bytes(LabelVisitor)
+    }
+}
+
+enum NodeField {
+    Label,
+    Value,
+    Children,
+    Ignore,
+}
+impl<'de> Deserialize<'de> for NodeField {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+        impl Visitor<'_> for FieldVisitor {
+            type Value = NodeField;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a node field")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(match v {
+                    0 => NodeField::Label,
+                    1 => NodeField::Value,
+                    2 => NodeField::Children,
+                    _ => NodeField::Ignore,
+                })
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                self.visit_bytes(v.as_bytes())
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(match v {
+                    b"label" => NodeField::Label,
+                    b"value" => NodeField::Value,
+                    b"children" => NodeField::Children,
+                    _ => NodeField::Ignore,
+                })
+            }
+        }
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+struct NodeVisitor<V> {
+    human_readable: bool,
+    _value: PhantomData<V>,
+}
+impl<'de, V: Deserialize<'de>> Visitor<'de> for NodeVisitor<V> {
+    type Value = Node<V>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a radix trie node")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let label: LabelBuf = access
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let value = access
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let children = access
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        Node::try_from_parts(&label.0, value, children).map_err(de::Error::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut label: Option<LabelBuf> = None;
+        let mut value: Option<Option<V>> = None;
+        let mut children: Option<Vec<Node<V>>> = None;
+        while let Some(field) = access.next_key()? {
+            match field {
+                NodeField::Label if label.is_some() => {
+                    return Err(de::Error::duplicate_field("label"));
+                }
+                NodeField::Label => label = Some(access.next_value()?),
+                NodeField::Value if value.is_some() => {
+                    return Err(de::Error::duplicate_field("value"));
+                }
+                NodeField::Value if self.human_readable => {
+                    value = Some(Some(access.next_value()?));
+                }
+                NodeField::Value => value = Some(access.next_value()?),
+                NodeField::Children if children.is_some() => {
+                    return Err(de::Error::duplicate_field("children"));
+                }
+                NodeField::Children => children = Some(access.next_value()?),
+                NodeField::Ignore => {
+                    access.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let label = label.ok_or_else(|| de::Error::missing_field("label"))?;
+        Node::try_from_parts(
+            &label.0,
+            value.unwrap_or_default(),
+            children.unwrap_or_default(),
+        )
+        .map_err(de::Error::custom)
+    }
+}
+
+/// Structural encoding for maps and sets, for use with `#[serde(with = "...")]`.
+///
+/// The tree is written as its root [`Node`], with labels, values and children, instead of
+/// a flat list of keys. Deserializing restores the exact shape without re-inserting keys; the
+/// structure is validated and rejected if it does not form a well-formed trie.
+///
+/// # Examples
+///
+/// ```
+/// use fast_radix_trie::{StringRadixMap, serde::structural};
+///
+/// let map: StringRadixMap<u32> = [("foo", 1), ("foobar", 2)].into_iter().collect();
+///
+/// let mut json = Vec::new();
+/// structural::serialize(&map, &mut serde_json::Serializer::new(&mut json)).unwrap();
+/// let back: StringRadixMap<u32> =
+///     structural::deserialize(&mut serde_json::Deserializer::from_slice(&json)).unwrap();
+/// assert_eq!(back.len(), 2);
+/// assert_eq!(back.get("foobar"), Some(&2));
+/// ```
+///
+/// Or as a field attribute:
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     #[serde(with = "fast_radix_trie::serde::structural")]
+///     routes: StringRadixMap<u32>,
+/// }
+/// ```
+pub mod structural {
+    use super::*;
+
+    /// A map or set that can be encoded as its root [`Node`].
+    pub trait Structural: Sized + sealed::Sealed {
+        /// The value type stored in the nodes.
+        type Value;
+
+        /// Returns the root node.
+        fn root(&self) -> &Node<Self::Value>;
+
+        /// Builds the collection from a root node, validating its keys.
+        fn try_from_root(root: Node<Self::Value>) -> Result<Self, &'static str>;
+    }
+
+    mod sealed {
+        pub trait Sealed {}
+        impl<K, V> Sealed for crate::GenericRadixMap<K, V> {}
+        impl<T> Sealed for crate::GenericRadixSet<T> {}
+    }
+
+    impl<K: Bytes, V> Structural for GenericRadixMap<K, V> {
+        type Value = V;
+
+        fn root(&self) -> &Node<V> {
+            self.as_node()
+        }
+
+        fn try_from_root(root: Node<V>) -> Result<Self, &'static str> {
+            let len = root.check_root::<K::Borrowed>()?;
+            Ok(GenericRadixMap::from_root(root, len))
+        }
+    }
+
+    impl<T: Bytes> Structural for GenericRadixSet<T> {
+        type Value = ();
+
+        fn root(&self) -> &Node<()> {
+            self.as_node()
+        }
+
+        fn try_from_root(root: Node<()>) -> Result<Self, &'static str> {
+            let len = root.check_root::<T::Borrowed>()?;
+            Ok(GenericRadixSet::from_root(root, len))
+        }
+    }
+
+    /// Serializes a map or set as its root [`Node`].
+    pub fn serialize<T, S>(collection: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Structural,
+        T::Value: Serialize,
+        S: Serializer,
+    {
+        collection.root().serialize(serializer)
+    }
+
+    /// Deserializes a map or set from its root [`Node`].
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Structural,
+        T::Value: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let root = Node::deserialize(deserializer)?;
+        T::try_from_root(root).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::structural;
+    use crate::{Node, RadixMap, RadixSet, StringRadixMap, StringRadixSet};
+    use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+    fn keys() -> Vec<String> {
+        crate::test::test_keys(if cfg!(miri) { 20 } else { 500 }, 5)
+    }
+
+    fn to_structural<T: structural::Structural>(collection: &T) -> String
+    where
+        T::Value: serde::Serialize,
+    {
+        let mut out = Vec::new();
+        structural::serialize(collection, &mut serde_json::Serializer::new(&mut out)).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn from_structural<T: structural::Structural>(json: &str) -> Result<T, serde_json::Error>
+    where
+        T::Value: serde::de::DeserializeOwned,
+    {
+        structural::deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn map_roundtrips_like_btreemap() {
+        let keys = keys();
+        let map: StringRadixMap<usize> = keys.iter().enumerate().map(|(i, k)| (k, i)).collect();
+        let btree: BTreeMap<String, usize> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, k)| (k.clone(), i))
+            .collect();
+
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(json, serde_json::to_string(&btree).unwrap());
+        let back: StringRadixMap<usize> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.len(), map.len());
+        assert!(back.iter().eq(map.iter()));
+    }
+
+    #[test]
+    fn set_roundtrips() {
+        let set: StringRadixSet = keys().into_iter().collect();
+        let json = serde_json::to_string(&set).unwrap();
+        let back: StringRadixSet = serde_json::from_str(&json).unwrap();
+        assert!(back.iter().eq(set.iter()));
+
+        let set: RadixSet = [&b"\xff\x00"[..], b"ab"].into_iter().collect();
+        let back: RadixSet = serde_json::from_str(&serde_json::to_string(&set).unwrap()).unwrap();
+        assert!(back.iter().eq(set.iter()));
+    }
+
+    #[test]
+    fn structural_roundtrip_keeps_shape() {
+        let keys = keys();
+        let mut map: StringRadixMap<usize> = keys.iter().enumerate().map(|(i, k)| (k, i)).collect();
+        // leave valueless nodes behind
+        for k in keys.iter().step_by(3) {
+            map.remove(k);
+        }
+
+        let json = to_structural(&map);
+        let back: StringRadixMap<usize> = from_structural(&json).unwrap();
+        assert_eq!(back.len(), map.len());
+        assert!(back.iter().eq(map.iter()));
+        assert_eq!(
+            format!("{:?}", back.as_node()),
+            format!("{:?}", map.as_node())
+        );
+        assert_eq!(to_structural(&back), json);
+
+        let set: RadixSet = keys.iter().collect();
+        let back: RadixSet = from_structural(&to_structural(&set)).unwrap();
+        assert!(back.iter().eq(set.iter()));
+    }
+
+    #[test]
+    fn node_roundtrips() {
+        let node = Node::new(b"ab", [Node::new(b"c", [], Some(2))], Some(1));
+        let json = serde_json::to_string(&node).unwrap();
+        assert_eq!(
+            json,
+            r#"{"label":[97,98],"value":1,"children":[{"label":[99],"value":2,"children":[]}]}"#
+        );
+        let back: Node<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{back:?}"), format!("{node:?}"));
+        // labels may be given as strings and missing fields default to empty
+        let node = Node::new(b"", [Node::new(b"ab", [], Some(()))], None);
+        let json = serde_json::to_string(&node).unwrap();
+        assert_eq!(
+            json,
+            r#"{"label":[],"children":[{"label":[97,98],"value":null,"children":[]}]}"#
+        );
+        let back: Node<()> = serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{back:?}"), format!("{node:?}"));
+
+        let node = Node::new(b"ab", [Node::new(b"c", [], Some(2))], Some(1));
+        let back: Node<u32> = serde_json::from_str(
+            r#"{"label":"ab","value":1,"children":[{"label":"c","value":2}]}"#,
+        )
+        .unwrap();
+        assert_eq!(format!("{back:?}"), format!("{node:?}"));
+    }
+
+    #[test]
+    fn structural_rejects_malformed_trees() {
+        let invalid = [
+            (
+                r#"{"label":"","children":[{"label":"b"},{"label":"a"}]}"#,
+                "sorted",
+            ),
+            (
+                r#"{"label":"","children":[{"label":"ab"},{"label":"a"}]}"#,
+                "sorted",
+            ),
+            (r#"{"label":"","children":[{"label":""}]}"#, "empty label"),
+            (r#"{"label":"a","value":1}"#, "root node"),
+        ];
+        for (json, reason) in invalid {
+            let err = from_structural::<RadixMap<u32>>(json).unwrap_err();
+            assert!(err.to_string().contains(reason), "{err}");
+        }
+
+        let long = format!(
+            r#"{{"label":"","children":[{{"label":"{}"}}]}}"#,
+            "a".repeat(256)
+        );
+        let err = from_structural::<RadixMap<u32>>(&long).unwrap_err();
+        assert!(err.to_string().contains("255 bytes"), "{err}");
+
+        let not_utf8 = r#"{"label":"","children":[{"label":[255],"value":1}]}"#;
+        assert!(from_structural::<RadixMap<u32>>(not_utf8).is_ok());
+        let err = from_structural::<StringRadixMap<u32>>(not_utf8).unwrap_err();
+        assert!(err.to_string().contains("not valid"), "{err}");
+    }
+}
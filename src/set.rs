@@ -1,9 +1,13 @@
 //! A set based on a patricia tree.
-use crate::Bytes;
+use crate::automaton::Automaton;
 use crate::map::{self, GenericRadixMap};
+use crate::{BorrowedBytes, Bytes, Node};
+use alloc::borrow::ToOwned;
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::iter::FromIterator;
+use core::marker::PhantomData;
+use core::ops::{BitAnd, BitOr, BitXor, RangeBounds, Sub};
 
 /// Patricia tree based set with [`Vec<u8>`] as key.
 pub type RadixSet = GenericRadixSet<Vec<u8>>;
@@ -92,6 +96,14 @@ impl<T> GenericRadixSet<T> {
         }
     }
 
+    /// create set from a root node holding `len` items
+    #[cfg(any(feature = "std", feature = "serde"))]
+    pub(crate) fn from_root(root: crate::Node<()>, len: usize) -> Self {
+        Self {
+            map: GenericRadixMap::from_root(root, len),
+        }
+    }
+
     /// get ref to root node  (NOTE: calling methods on node directly seriously mess up your tree)
     pub fn as_node(&self) -> &crate::Node<()> {
         self.map.as_node()
@@ -230,6 +242,163 @@ impl<T: Bytes> GenericRadixSet<T> {
         }
     }
 
+    /// Removes every item starting with `prefix`, returning the number of items removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let mut set: StringRadixSet = ["rust", "ruby", "python"].into_iter().collect();
+    /// assert_eq!(set.remove_prefix("ru"), 2);
+    /// assert_eq!(set.iter().collect::<Vec<_>>(), ["python"]);
+    /// ```
+    pub fn remove_prefix<U: AsRef<T::Borrowed>>(&mut self, prefix: U) -> usize {
+        self.map.remove_prefix(prefix)
+    }
+
+    /// Retains only the items starting with `prefix`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let mut set: StringRadixSet = ["rust", "ruby", "python"].into_iter().collect();
+    /// set.retain_prefix("ru");
+    /// assert_eq!(set.iter().collect::<Vec<_>>(), ["ruby", "rust"]);
+    /// ```
+    pub fn retain_prefix<U: AsRef<T::Borrowed>>(&mut self, prefix: U) {
+        self.map.retain_prefix(prefix);
+    }
+
+    /// Retains only the items for which `f` returns `true`, visiting them in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let mut set: StringRadixSet = ["rust", "ruby", "python"].into_iter().collect();
+    /// set.retain(|item| item.starts_with('p'));
+    /// assert_eq!(set.iter().collect::<Vec<_>>(), ["python"]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T::Borrowed) -> bool,
+    {
+        self.map.retain(|item, _| f(item));
+    }
+
+    /// Moves all items of `other` into `self` with `prefix` prepended to them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let mut set = StringRadixSet::new();
+    /// set.insert("a/x");
+    ///
+    /// let other: StringRadixSet = ["x", "y"].into_iter().collect();
+    /// set.insert_subtree("b/", other);
+    /// assert_eq!(set.iter().collect::<Vec<_>>(), ["a/x", "b/x", "b/y"]);
+    /// ```
+    pub fn insert_subtree<U: AsRef<T::Borrowed>>(&mut self, prefix: U, other: Self) {
+        self.map.insert_subtree(prefix, other.map);
+    }
+
+    /// Moves every item starting with `from` so that it starts with `to` instead, returning the
+    /// number of items moved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let mut set: StringRadixSet = ["a/x", "a/y", "b/z"].into_iter().collect();
+    /// assert_eq!(set.rename_prefix("a/", "c/"), 2);
+    /// assert_eq!(set.iter().collect::<Vec<_>>(), ["b/z", "c/x", "c/y"]);
+    /// ```
+    pub fn rename_prefix<U, W>(&mut self, from: U, to: W) -> usize
+    where
+        U: AsRef<T::Borrowed>,
+        W: AsRef<T::Borrowed>,
+    {
+        self.map.rename_prefix(from, to)
+    }
+
+    /// Moves all items from `other` into `self`, leaving `other` empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let mut a = StringRadixSet::new();
+    /// a.insert("rust");
+    ///
+    /// let mut b = a.clone();
+    /// b.insert("ruby");
+    /// b.insert("erlang");
+    ///
+    /// a.append(&mut b);
+    /// assert!(b.is_empty());
+    /// assert_eq!(a.iter().collect::<Vec<_>>(), ["erlang", "ruby", "rust"]);
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        self.map.append(&mut other.map);
+    }
+
+    /// Builds a set from values in strictly ascending order, allocating each node once.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a value is smaller than or equal to the value before it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{StringRadixSet, map::FromSortedIterError};
+    ///
+    /// let set = StringRadixSet::from_sorted_iter(["erlang", "ruby", "rust"]).unwrap();
+    /// assert!(set.contains("ruby"));
+    ///
+    /// let err = StringRadixSet::from_sorted_iter(["rust", "ruby"]).unwrap_err();
+    /// assert_eq!(err, FromSortedIterError::Unsorted(1));
+    /// ```
+    pub fn from_sorted_iter<I, U>(iter: I) -> Result<Self, map::FromSortedIterError>
+    where
+        I: IntoIterator<Item = U>,
+        U: AsRef<T::Borrowed>,
+    {
+        let map = GenericRadixMap::from_sorted_iter(iter.into_iter().map(|v| (v, ())))?;
+        Ok(GenericRadixSet { map })
+    }
+
+    /// Builds a set from values in ascending order, skipping repeated values.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a value is smaller than the value before it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let set = StringRadixSet::from_sorted_iter_dedup(["ruby", "ruby", "rust"]).unwrap();
+    /// assert_eq!(set.len(), 2);
+    /// ```
+    pub fn from_sorted_iter_dedup<I, U>(iter: I) -> Result<Self, map::FromSortedIterError>
+    where
+        I: IntoIterator<Item = U>,
+        U: AsRef<T::Borrowed>,
+    {
+        let map = GenericRadixMap::from_sorted_iter_dedup(iter.into_iter().map(|v| (v, ())))?;
+        Ok(GenericRadixSet { map })
+    }
+
     /// Gets an iterator over the contents of this set, in sorted order.
     ///
     /// # Examples
@@ -247,6 +416,46 @@ impl<T: Bytes> GenericRadixSet<T> {
     pub fn iter(&self) -> Iter<'_, T> {
         Iter(self.map.keys())
     }
+
+    /// Gets a lending iterator over the contents of this set, in sorted order.
+    ///
+    /// Each item is borrowed from a buffer owned by the iterator, so nothing is allocated per
+    /// item. See [`GenericRadixMap::raw_iter`](crate::GenericRadixMap::raw_iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let set: StringRadixSet = vec!["foo", "bar", "baz"].into_iter().collect();
+    /// let mut iter = set.raw_iter();
+    /// assert_eq!(iter.next(), Some("bar"));
+    /// assert_eq!(iter.next_back(), Some("foo"));
+    /// assert_eq!(iter.next(), Some("baz"));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn raw_iter(&self) -> RawIter<'_, T> {
+        RawIter(self.map.raw_iter())
+    }
+
+    /// Calls `f` on each item of this set, in sorted order, lending it as `&T::Borrowed`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let set: StringRadixSet = vec!["foo", "bar", "baz"].into_iter().collect();
+    /// let mut count = 0;
+    /// set.for_each_ref(|item: &str| count += item.starts_with('b') as usize);
+    /// assert_eq!(count, 2);
+    /// ```
+    pub fn for_each_ref<F>(&self, mut f: F)
+    where
+        F: FnMut(&T::Borrowed),
+    {
+        self.map.for_each_ref(|item, _| f(item));
+    }
 }
 
 impl<T: Bytes> GenericRadixSet<T> {
@@ -264,12 +473,411 @@ impl<T: Bytes> GenericRadixSet<T> {
     ///
     /// assert_eq!(set.iter_prefix(b"ba").collect::<Vec<_>>(), [Vec::from("bar"), "baz".into()]);
     /// ```
-    pub fn iter_prefix<'a, 'b>(&'a self, prefix: &'b T::Borrowed) -> impl 'a + Iterator<Item = T>
+    pub fn iter_prefix<'a, 'b>(
+        &'a self,
+        prefix: &'b T::Borrowed,
+    ) -> impl 'a + DoubleEndedIterator<Item = T>
     where
         'b: 'a,
     {
         self.map.iter_prefix(prefix).map(|(k, _)| k)
     }
+
+    /// Gets an iterator over a sub-range of the contents of this set, in sorted order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`, or if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let mut set = StringRadixSet::new();
+    /// set.insert("foo");
+    /// set.insert("bar");
+    /// set.insert("baz");
+    ///
+    /// assert_eq!(set.range("b".."baz").collect::<Vec<_>>(), ["bar"]);
+    /// assert_eq!(set.range("baz"..).collect::<Vec<_>>(), ["baz", "foo"]);
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, T>
+    where
+        Q: ?Sized + AsRef<T::Borrowed>,
+        R: RangeBounds<Q>,
+    {
+        Range(self.map.range(range))
+    }
+
+    /// Gets an iterator over the items within `max_distance` edits of `value`, in sorted order,
+    /// each along with its Levenshtein distance to `value`.
+    ///
+    /// See [`GenericRadixMap::fuzzy_search`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let set: StringRadixSet = ["bar", "baz", "foo"].into_iter().collect();
+    /// assert_eq!(set.fuzzy_search("bat", 1).collect::<Vec<_>>(),
+    ///            [("bar".to_owned(), 1), ("baz".to_owned(), 1)]);
+    /// ```
+    pub fn fuzzy_search<U: AsRef<T::Borrowed>>(
+        &self,
+        value: U,
+        max_distance: usize,
+    ) -> FuzzySearch<'_, T> {
+        FuzzySearch(self.map.fuzzy_search(value, max_distance))
+    }
+
+    /// Like [`fuzzy_search`](Self::fuzzy_search), but also counts swapping two adjacent
+    /// symbols as a single edit.
+    ///
+    /// See [`GenericRadixMap::fuzzy_search_damerau`].
+    pub fn fuzzy_search_damerau<U: AsRef<T::Borrowed>>(
+        &self,
+        value: U,
+        max_distance: usize,
+    ) -> FuzzySearch<'_, T> {
+        FuzzySearch(self.map.fuzzy_search_damerau(value, max_distance))
+    }
+
+    /// Gets an iterator over the items matching a glob `pattern`, in sorted order.
+    ///
+    /// See [`GenericRadixMap::match_pattern`] for the pattern syntax.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let set: StringRadixSet = ["bar", "baz", "foo"].into_iter().collect();
+    /// assert_eq!(set.match_pattern("ba[!r]").collect::<Vec<_>>(), ["baz"]);
+    /// assert_eq!(set.match_pattern("*o*").collect::<Vec<_>>(), ["foo"]);
+    /// ```
+    pub fn match_pattern<U: AsRef<T::Borrowed>>(&self, pattern: U) -> MatchPattern<'_, T> {
+        MatchPattern(self.map.match_pattern(pattern))
+    }
+
+    /// Gets a lending iterator over the items `automaton` matches, in sorted order.
+    ///
+    /// See [`GenericRadixMap::search`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    /// use fast_radix_trie::automaton::{Automaton, Str};
+    ///
+    /// let set: StringRadixSet = ["bar", "baz", "foo"].into_iter().collect();
+    /// let mut search = set.search(Str::new("foo").complement());
+    /// assert_eq!(search.next(), Some("bar"));
+    /// assert_eq!(search.next(), Some("baz"));
+    /// assert_eq!(search.next(), None);
+    /// ```
+    pub fn search<A: Automaton>(&self, automaton: A) -> Search<'_, T, A> {
+        Search(self.map.search(automaton))
+    }
+
+    /// Returns the first (minimum) value in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let mut set = StringRadixSet::new();
+    /// assert_eq!(set.first(), None);
+    /// set.insert("foo");
+    /// set.insert("bar");
+    /// assert_eq!(set.first(), Some("bar".into()));
+    /// ```
+    pub fn first(&self) -> Option<T> {
+        self.map.first_key_value().map(|(k, _)| k)
+    }
+
+    /// Returns the last (maximum) value in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let mut set = StringRadixSet::new();
+    /// assert_eq!(set.last(), None);
+    /// set.insert("foo");
+    /// set.insert("bar");
+    /// assert_eq!(set.last(), Some("foo".into()));
+    /// ```
+    pub fn last(&self) -> Option<T> {
+        self.map.last_key_value().map(|(k, _)| k)
+    }
+
+    /// Removes the first value from the set and returns it, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let mut set: StringRadixSet = ["foo", "bar"].into_iter().collect();
+    /// assert_eq!(set.pop_first(), Some("bar".into()));
+    /// assert_eq!(set.pop_first(), Some("foo".into()));
+    /// assert_eq!(set.pop_first(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.map.pop_first().map(|(k, _)| k)
+    }
+
+    /// Removes the last value from the set and returns it, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let mut set: StringRadixSet = ["foo", "bar"].into_iter().collect();
+    /// assert_eq!(set.pop_last(), Some("foo".into()));
+    /// assert_eq!(set.pop_last(), Some("bar".into()));
+    /// assert_eq!(set.pop_last(), None);
+    /// ```
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.map.pop_last().map(|(k, _)| k)
+    }
+
+    /// Returns the greatest value in the set less than or equal to `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let set: StringRadixSet = ["foo", "foobar", "qux"].into_iter().collect();
+    /// assert_eq!(set.floor("foobaz"), Some("foobar".into()));
+    /// assert_eq!(set.floor("bar"), None);
+    /// ```
+    pub fn floor<U: AsRef<T::Borrowed>>(&self, value: U) -> Option<T> {
+        self.map.floor(value).map(|(k, _)| k)
+    }
+
+    /// Returns the smallest value in the set greater than or equal to `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let set: StringRadixSet = ["foo", "foobar", "qux"].into_iter().collect();
+    /// assert_eq!(set.ceiling("foob"), Some("foobar".into()));
+    /// assert_eq!(set.ceiling("quxx"), None);
+    /// ```
+    pub fn ceiling<U: AsRef<T::Borrowed>>(&self, value: U) -> Option<T> {
+        self.map.ceiling(value).map(|(k, _)| k)
+    }
+
+    /// Returns the greatest value in the set strictly less than `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let set: StringRadixSet = ["foo", "foobar", "qux"].into_iter().collect();
+    /// assert_eq!(set.predecessor("qux"), Some("foobar".into()));
+    /// assert_eq!(set.predecessor("foo"), None);
+    /// ```
+    pub fn predecessor<U: AsRef<T::Borrowed>>(&self, value: U) -> Option<T> {
+        self.map.predecessor(value).map(|(k, _)| k)
+    }
+
+    /// Returns the smallest value in the set strictly greater than `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let set: StringRadixSet = ["foo", "foobar", "qux"].into_iter().collect();
+    /// assert_eq!(set.successor("foo"), Some("foobar".into()));
+    /// assert_eq!(set.successor("qux"), None);
+    /// ```
+    pub fn successor<U: AsRef<T::Borrowed>>(&self, value: U) -> Option<T> {
+        self.map.successor(value).map(|(k, _)| k)
+    }
+
+    /// Visits the items in `self` or `other`, without duplicates, in sorted order.
+    ///
+    /// Both trees are walked together, so shared prefixes are compared once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let a: StringRadixSet = ["foo", "bar"].into_iter().collect();
+    /// let b: StringRadixSet = ["foo", "baz"].into_iter().collect();
+    /// assert_eq!(a.union(&b).collect::<Vec<_>>(), ["bar", "baz", "foo"]);
+    /// assert_eq!((&a | &b).len(), 3);
+    /// ```
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, T> {
+        Union(SetWalk::new(SetOp::Union, self, other), PhantomData)
+    }
+
+    /// Visits the items in both `self` and `other`, in sorted order.
+    ///
+    /// Subtrees present in only one of the sets are skipped without being visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let a: StringRadixSet = ["foo", "bar"].into_iter().collect();
+    /// let b: StringRadixSet = ["foo", "baz"].into_iter().collect();
+    /// assert_eq!(a.intersection(&b).collect::<Vec<_>>(), ["foo"]);
+    /// assert_eq!((&a & &b).len(), 1);
+    /// ```
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a, T> {
+        Intersection(SetWalk::new(SetOp::Intersection, self, other), PhantomData)
+    }
+
+    /// Visits the items in `self` but not in `other`, in sorted order.
+    ///
+    /// Subtrees present only in `other` are skipped without being visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let a: StringRadixSet = ["foo", "bar"].into_iter().collect();
+    /// let b: StringRadixSet = ["foo", "baz"].into_iter().collect();
+    /// assert_eq!(a.difference(&b).collect::<Vec<_>>(), ["bar"]);
+    /// assert_eq!((&a - &b).len(), 1);
+    /// ```
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, T> {
+        Difference(SetWalk::new(SetOp::Difference, self, other), PhantomData)
+    }
+
+    /// Visits the items in `self` or `other` but not in both, in sorted order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let a: StringRadixSet = ["foo", "bar"].into_iter().collect();
+    /// let b: StringRadixSet = ["foo", "baz"].into_iter().collect();
+    /// assert_eq!(a.symmetric_difference(&b).collect::<Vec<_>>(), ["bar", "baz"]);
+    /// assert_eq!((&a ^ &b).len(), 2);
+    /// ```
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<'a, T> {
+        SymmetricDifference(
+            SetWalk::new(SetOp::SymmetricDifference, self, other),
+            PhantomData,
+        )
+    }
+
+    /// Returns `true` if every item of `self` is also in `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let a: StringRadixSet = ["foo"].into_iter().collect();
+    /// let b: StringRadixSet = ["foo", "foobar"].into_iter().collect();
+    /// assert!(a.is_subset(&b));
+    /// assert!(!b.is_subset(&a));
+    /// ```
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len()
+            && SetWalk::new(SetOp::Difference, self, other)
+                .next()
+                .is_none()
+    }
+
+    /// Returns `true` if every item of `other` is also in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let a: StringRadixSet = ["foo"].into_iter().collect();
+    /// let b: StringRadixSet = ["foo", "foobar"].into_iter().collect();
+    /// assert!(b.is_superset(&a));
+    /// assert!(!a.is_superset(&b));
+    /// ```
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns `true` if `self` and `other` have no items in common.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let a: StringRadixSet = ["foo", "bar"].into_iter().collect();
+    /// let b: StringRadixSet = ["foobar", "ba"].into_iter().collect();
+    /// assert!(a.is_disjoint(&b));
+    /// ```
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        SetWalk::new(SetOp::Intersection, self, other)
+            .next()
+            .is_none()
+    }
+
+    /// Builds the result of `op` node by node, cloning the subtrees found in only one of the
+    /// sets whole instead of re-inserting their items.
+    fn build(op: SetOp, a: &Self, b: &Self) -> Self {
+        let root = op
+            .build_both((a.as_node(), 0), (b.as_node(), 0))
+            .unwrap_or_else(Node::root);
+        GenericRadixSet {
+            map: GenericRadixMap::from_node(root),
+        }
+    }
+}
+
+impl<T: Bytes> BitOr<&GenericRadixSet<T>> for &GenericRadixSet<T> {
+    type Output = GenericRadixSet<T>;
+
+    /// Returns the union of `self` and `rhs` as a new set.
+    fn bitor(self, rhs: &GenericRadixSet<T>) -> GenericRadixSet<T> {
+        GenericRadixSet::build(SetOp::Union, self, rhs)
+    }
+}
+
+impl<T: Bytes> BitAnd<&GenericRadixSet<T>> for &GenericRadixSet<T> {
+    type Output = GenericRadixSet<T>;
+
+    /// Returns the intersection of `self` and `rhs` as a new set.
+    fn bitand(self, rhs: &GenericRadixSet<T>) -> GenericRadixSet<T> {
+        GenericRadixSet::build(SetOp::Intersection, self, rhs)
+    }
+}
+
+impl<T: Bytes> Sub<&GenericRadixSet<T>> for &GenericRadixSet<T> {
+    type Output = GenericRadixSet<T>;
+
+    /// Returns the difference of `self` and `rhs` as a new set.
+    fn sub(self, rhs: &GenericRadixSet<T>) -> GenericRadixSet<T> {
+        GenericRadixSet::build(SetOp::Difference, self, rhs)
+    }
+}
+
+impl<T: Bytes> BitXor<&GenericRadixSet<T>> for &GenericRadixSet<T> {
+    type Output = GenericRadixSet<T>;
+
+    /// Returns the symmetric difference of `self` and `rhs` as a new set.
+    fn bitxor(self, rhs: &GenericRadixSet<T>) -> GenericRadixSet<T> {
+        GenericRadixSet::build(SetOp::SymmetricDifference, self, rhs)
+    }
 }
 
 // impl<T: Bytes + fmt::Debug> fmt::Debug for GenericRadixSet<T> {
@@ -334,6 +942,380 @@ impl<T: Bytes> Iterator for Iter<'_, T> {
         self.0.next()
     }
 }
+impl<T: Bytes> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+/// A lending iterator over a `RadixSet`'s items that borrows each item from an internal buffer.
+///
+/// This `struct` is created by the [`raw_iter`](GenericRadixSet::raw_iter) method on [`GenericRadixSet`].
+#[derive(Debug)]
+pub struct RawIter<'a, T>(map::RawIter<'a, T, ()>);
+impl<T: Bytes> RawIter<'_, T> {
+    /// Advances the iterator and returns the next item, in sorted order.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&T::Borrowed> {
+        self.0.next().map(|(item, _)| item)
+    }
+
+    /// Returns the next item from the back of the iterator, in reverse sorted order.
+    pub fn next_back(&mut self) -> Option<&T::Borrowed> {
+        self.0.next_back().map(|(item, _)| item)
+    }
+}
+
+/// An iterator over a sub-range of a `RadixSet`'s items.
+#[derive(Debug)]
+pub struct Range<'a, T>(map::Range<'a, T, ()>);
+impl<T: Bytes> Iterator for Range<'_, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, _)| k)
+    }
+}
+impl<T: Bytes> DoubleEndedIterator for Range<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(k, _)| k)
+    }
+}
+
+/// An iterator over the items of a `RadixSet` within an edit distance of a value, along with
+/// their distances.
+///
+/// This `struct` is created by the [`fuzzy_search`](GenericRadixSet::fuzzy_search) and
+/// [`fuzzy_search_damerau`](GenericRadixSet::fuzzy_search_damerau) methods on
+/// [`GenericRadixSet`].
+#[derive(Debug)]
+pub struct FuzzySearch<'a, T>(map::FuzzySearch<'a, T, ()>);
+impl<T: Bytes> Iterator for FuzzySearch<'_, T> {
+    type Item = (T, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, _, distance)| (k, distance))
+    }
+}
+
+/// An iterator over the items of a `RadixSet` matching a glob pattern.
+///
+/// This `struct` is created by the [`match_pattern`](GenericRadixSet::match_pattern) method on
+/// [`GenericRadixSet`].
+#[derive(Debug)]
+pub struct MatchPattern<'a, T>(map::MatchPattern<'a, T, ()>);
+impl<T: Bytes> Iterator for MatchPattern<'_, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, _)| k)
+    }
+}
+
+/// A lending iterator over the items of a `RadixSet` that an [`Automaton`] matches.
+///
+/// This `struct` is created by the [`search`](GenericRadixSet::search) method on
+/// [`GenericRadixSet`].
+#[derive(Debug)]
+pub struct Search<'a, T, A: Automaton>(map::Search<'a, T, (), A>);
+impl<T: Bytes, A: Automaton> Search<'_, T, A> {
+    /// Advances the search and returns the next match, in sorted order.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&T::Borrowed> {
+        self.0.next().map(|(k, _)| k)
+    }
+}
+
+macro_rules! set_op_iter {
+    ($name:ident, $method:literal, $what:literal) => {
+        #[doc = concat!("A lazy iterator over the ", $what, " of two `RadixSet`s, in sorted order.")]
+        ///
+        #[doc = concat!("This `struct` is created by the [`", $method, "`](GenericRadixSet::", $method, ") method on [`GenericRadixSet`].")]
+        #[derive(Debug)]
+        pub struct $name<'a, T>(SetWalk<'a>, PhantomData<T>);
+        impl<T: Bytes> Iterator for $name<'_, T> {
+            type Item = T;
+            fn next(&mut self) -> Option<Self::Item> {
+                self.0.next().map(|item| T::Borrowed::from_bytes(item).to_owned())
+            }
+        }
+    };
+}
+set_op_iter!(Union, "union", "union");
+set_op_iter!(Intersection, "intersection", "intersection");
+set_op_iter!(Difference, "difference", "difference");
+set_op_iter!(
+    SymmetricDifference,
+    "symmetric_difference",
+    "symmetric difference"
+);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SetOp {
+    Union,
+    Intersection,
+    Difference,
+    SymmetricDifference,
+}
+impl SetOp {
+    /// Whether a key found in `a`, `b` or both belongs to the result.
+    fn keeps(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            SetOp::Union => in_a || in_b,
+            SetOp::Intersection => in_a && in_b,
+            SetOp::Difference => in_a && !in_b,
+            SetOp::SymmetricDifference => in_a != in_b,
+        }
+    }
+
+    /// Builds the part of the result below a point both sets reach, labeled from that point
+    /// on, or `None` if it's empty.
+    fn build_both(
+        self,
+        (a, a_offset): (&Node<()>, usize),
+        (b, b_offset): (&Node<()>, usize),
+    ) -> Option<Node<()>> {
+        let (rest_a, rest_b) = (&a.label()[a_offset..], &b.label()[b_offset..]);
+        let (n, _) = crate::longest_common_prefix(rest_a, rest_b);
+        let (a_offset, b_offset) = (a_offset + n, b_offset + n);
+        let (a_done, b_done) = (n == rest_a.len(), n == rest_b.len());
+        let mut children = Vec::new();
+        let mut value = None;
+        if !a_done && !b_done {
+            // the labels diverge, so the rest of each subtree is in one set only
+            let mut only = [
+                self.build_only((a, a_offset), true),
+                self.build_only((b, b_offset), false),
+            ];
+            if rest_a[n] > rest_b[n] {
+                only.swap(0, 1);
+            }
+            for child in only {
+                push_child(&mut children, child);
+            }
+        } else {
+            self.build_branches(
+                Branches::new(a, a_offset),
+                Branches::new(b, b_offset),
+                &mut children,
+            );
+            let in_a = a_done && a.value().is_some();
+            let in_b = b_done && b.value().is_some();
+            value = self.keeps(in_a, in_b).then_some(());
+        }
+        if value.is_none() && children.is_empty() {
+            return None;
+        }
+        Some(Node::with_children(&rest_a[..n], children, value))
+    }
+
+    /// Builds the part of the result below a point only one of the sets reaches, which is
+    /// either all of that set's subtree or nothing.
+    fn build_only(self, (node, offset): (&Node<()>, usize), in_a: bool) -> Option<Node<()>> {
+        self.keeps(in_a, !in_a).then(|| {
+            Node::with_children(
+                &node.label()[offset..],
+                node.children().to_vec(),
+                node.value().copied(),
+            )
+        })
+    }
+
+    /// Builds the branches below a point both sets reach, in order of their first byte.
+    fn build_branches(self, a: Branches<'_>, b: Branches<'_>, children: &mut Vec<Node<()>>) {
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            let next_a = (i < a.len()).then(|| a.get(i));
+            let next_b = (j < b.len()).then(|| b.get(j));
+            let child = match (next_a, next_b) {
+                (Some((x, a)), Some((y, b))) if x == y => {
+                    i += 1;
+                    j += 1;
+                    self.build_both(a, b)
+                }
+                (Some((x, a)), Some((y, _))) if x < y => {
+                    i += 1;
+                    self.build_only(a, true)
+                }
+                (Some((_, a)), None) => {
+                    i += 1;
+                    self.build_only(a, true)
+                }
+                (_, Some((_, b))) => {
+                    j += 1;
+                    self.build_only(b, false)
+                }
+                (None, None) => unreachable!(),
+            };
+            push_child(children, child);
+        }
+    }
+}
+
+/// Adds a built child, merging it with its own child if it's left holding nothing else.
+fn push_child(children: &mut Vec<Node<()>>, child: Option<Node<()>>) {
+    if let Some(mut child) = child {
+        child.try_merge_child();
+        children.push(child);
+    }
+}
+
+/// A pending piece of the simultaneous walk. Offsets count the label bytes of a node that are
+/// already part of the key, `key_len` is the key length before the step.
+#[derive(Debug)]
+enum Step<'a> {
+    /// Both sets reach the same key.
+    Both {
+        a: (&'a Node<()>, usize),
+        b: (&'a Node<()>, usize),
+        key_len: usize,
+    },
+    /// A subtree that exists in only one of the sets.
+    Only {
+        node: (&'a Node<()>, usize),
+        key_len: usize,
+    },
+}
+
+/// The branches below a point of the walk: the children of a node whose label is fully
+/// matched, or the rest of a label still being matched.
+#[derive(Clone, Copy)]
+enum Branches<'a> {
+    Children(&'a [Node<()>]),
+    Label(&'a Node<()>, usize),
+}
+impl<'a> Branches<'a> {
+    fn new(node: &'a Node<()>, offset: usize) -> Self {
+        if offset < node.label_len() {
+            Branches::Label(node, offset)
+        } else {
+            Branches::Children(node.children())
+        }
+    }
+
+    fn len(self) -> usize {
+        match self {
+            Branches::Children(children) => children.len(),
+            Branches::Label(..) => 1,
+        }
+    }
+
+    fn get(self, i: usize) -> (u8, (&'a Node<()>, usize)) {
+        match self {
+            Branches::Children(children) => (children[i].label()[0], (&children[i], 0)),
+            Branches::Label(node, offset) => (node.label()[offset], (node, offset)),
+        }
+    }
+}
+
+/// Walks two tries side by side, in key order.
+#[derive(Debug)]
+struct SetWalk<'a> {
+    op: SetOp,
+    stack: Vec<Step<'a>>,
+    key: Vec<u8>,
+}
+impl<'a> SetWalk<'a> {
+    fn new<T>(op: SetOp, a: &'a GenericRadixSet<T>, b: &'a GenericRadixSet<T>) -> Self {
+        SetWalk {
+            op,
+            stack: vec![Step::Both {
+                a: (a.as_node(), 0),
+                b: (b.as_node(), 0),
+                key_len: 0,
+            }],
+            key: Vec::new(),
+        }
+    }
+
+    fn push_only(&mut self, node: (&'a Node<()>, usize), in_a: bool, key_len: usize) {
+        // a subtree of only one set can be skipped entirely if its keys are never kept
+        if self.op.keeps(in_a, !in_a) {
+            self.stack.push(Step::Only { node, key_len });
+        }
+    }
+
+    /// Pushes the branches below the current key, largest first byte first.
+    fn push_branches(&mut self, a: Branches<'a>, b: Branches<'a>) {
+        let key_len = self.key.len();
+        let (mut i, mut j) = (a.len(), b.len());
+        while i > 0 || j > 0 {
+            let next_a = (i > 0).then(|| a.get(i - 1));
+            let next_b = (j > 0).then(|| b.get(j - 1));
+            match (next_a, next_b) {
+                (Some((x, a)), Some((y, b))) if x == y => {
+                    self.stack.push(Step::Both { a, b, key_len });
+                    i -= 1;
+                    j -= 1;
+                }
+                (Some((x, a)), Some((y, _))) if x > y => {
+                    self.push_only(a, true, key_len);
+                    i -= 1;
+                }
+                (Some((_, a)), None) => {
+                    self.push_only(a, true, key_len);
+                    i -= 1;
+                }
+                (_, Some((_, b))) => {
+                    self.push_only(b, false, key_len);
+                    j -= 1;
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+
+    fn next(&mut self) -> Option<&[u8]> {
+        while let Some(step) = self.stack.pop() {
+            let keep = match step {
+                Step::Only {
+                    node: (node, offset),
+                    key_len,
+                } => {
+                    self.key.truncate(key_len);
+                    self.key.extend_from_slice(&node.label()[offset..]);
+                    let key_len = self.key.len();
+                    for child in node.children().iter().rev() {
+                        self.stack.push(Step::Only {
+                            node: (child, 0),
+                            key_len,
+                        });
+                    }
+                    node.value().is_some()
+                }
+                Step::Both {
+                    a: (a, a_offset),
+                    b: (b, b_offset),
+                    key_len,
+                } => {
+                    self.key.truncate(key_len);
+                    let (rest_a, rest_b) = (&a.label()[a_offset..], &b.label()[b_offset..]);
+                    let (n, _) = crate::longest_common_prefix(rest_a, rest_b);
+                    self.key.extend_from_slice(&rest_a[..n]);
+                    let (a_offset, b_offset) = (a_offset + n, b_offset + n);
+                    let (a_done, b_done) = (n == rest_a.len(), n == rest_b.len());
+                    if !a_done && !b_done {
+                        // the labels diverge, so the rest of each subtree is in one set only
+                        let key_len = self.key.len();
+                        if rest_a[n] < rest_b[n] {
+                            self.push_only((b, b_offset), false, key_len);
+                            self.push_only((a, a_offset), true, key_len);
+                        } else {
+                            self.push_only((a, a_offset), true, key_len);
+                            self.push_only((b, b_offset), false, key_len);
+                        }
+                        continue;
+                    }
+                    self.push_branches(Branches::new(a, a_offset), Branches::new(b, b_offset));
+                    let in_a = a_done && a.value().is_some();
+                    let in_b = b_done && b.value().is_some();
+                    self.op.keeps(in_a, in_b)
+                }
+            };
+            if keep {
+                return Some(&self.key);
+            }
+        }
+        None
+    }
+}
 
 /// An owning iterator over a `RadixSet`'s items.
 #[derive(Debug)]
@@ -480,4 +1462,100 @@ mod tests {
             assert_iter_prefix(&set, prefix);
         }
     }
+
+    #[test]
+    fn range_works() {
+        let set: RadixSet = ["", "a", "ab", "abc", "b", "ba"].into_iter().collect();
+        assert_eq!(
+            set.range::<&str, _>(..).collect::<Vec<_>>(),
+            set.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            set.range("a".."b").collect::<Vec<_>>(),
+            [Vec::from("a"), "ab".into(), "abc".into()]
+        );
+        assert_eq!(
+            set.range("aa"..="ba").collect::<Vec<_>>(),
+            [Vec::from("ab"), "abc".into(), "b".into(), "ba".into()]
+        );
+        assert_eq!(set.range("abcd".."b").count(), 0);
+    }
+
+    #[test]
+    fn iter_rev_works() {
+        let set: RadixSet = ["", "a", "ab", "abc", "b", "ba"].into_iter().collect();
+        let mut expected = set.iter().collect::<Vec<_>>();
+        expected.reverse();
+        assert_eq!(set.iter().rev().collect::<Vec<_>>(), expected);
+        assert_eq!(
+            set.iter_prefix(b"a").rev().collect::<Vec<_>>(),
+            [Vec::from("abc"), "ab".into(), "a".into()]
+        );
+        assert_eq!(
+            set.range("a".."b").rev().collect::<Vec<_>>(),
+            [Vec::from("abc"), "ab".into(), "a".into()]
+        );
+    }
+
+    #[test]
+    fn set_algebra_matches_btreeset() {
+        use rand::Rng;
+        use std::collections::BTreeSet;
+
+        let mut rng = rand::rng();
+        let pick = |rng: &mut rand::rngs::ThreadRng| -> String {
+            let len = rng.random_range(0..6);
+            let mut key: String = (0..len).map(|_| rng.random_range('a'..='c')).collect();
+            if rng.random_range(0..10) == 0 {
+                key.insert_str(0, &"x".repeat(300));
+            }
+            key
+        };
+        for _ in 0..if cfg!(miri) { 3 } else { 100 } {
+            let size = if cfg!(miri) { 20 } else { 200 };
+            let a_keys: BTreeSet<String> = (0..rng.random_range(0..size))
+                .map(|_| pick(&mut rng))
+                .collect();
+            let b_keys: BTreeSet<String> = (0..rng.random_range(0..size))
+                .map(|_| pick(&mut rng))
+                .collect();
+            let a: StringRadixSet = a_keys.iter().collect();
+            let b: StringRadixSet = b_keys.iter().collect();
+
+            let expected = a_keys.union(&b_keys).cloned().collect::<Vec<_>>();
+            assert_eq!(a.union(&b).collect::<Vec<_>>(), expected);
+            assert_eq!((&a | &b).iter().collect::<Vec<_>>(), expected);
+            let expected = a_keys.intersection(&b_keys).cloned().collect::<Vec<_>>();
+            assert_eq!(a.intersection(&b).collect::<Vec<_>>(), expected);
+            assert_eq!((&a & &b).iter().collect::<Vec<_>>(), expected);
+            let expected = a_keys.difference(&b_keys).cloned().collect::<Vec<_>>();
+            assert_eq!(a.difference(&b).collect::<Vec<_>>(), expected);
+            assert_eq!((&a - &b).iter().collect::<Vec<_>>(), expected);
+            let expected = a_keys
+                .symmetric_difference(&b_keys)
+                .cloned()
+                .collect::<Vec<_>>();
+            assert_eq!(a.symmetric_difference(&b).collect::<Vec<_>>(), expected);
+            assert_eq!((&a ^ &b).iter().collect::<Vec<_>>(), expected);
+
+            assert_eq!(a.is_subset(&b), a_keys.is_subset(&b_keys));
+            assert_eq!(a.is_superset(&b), a_keys.is_superset(&b_keys));
+            assert_eq!(a.is_disjoint(&b), a_keys.is_disjoint(&b_keys));
+            // owned results are built from the nodes of both sets, and shaped as if their items
+            // were inserted one by one
+            for (result, expected) in [
+                (&a | &b, a_keys.union(&b_keys).collect::<StringRadixSet>()),
+                (&a & &b, a_keys.intersection(&b_keys).collect()),
+                (&a - &b, a_keys.difference(&b_keys).collect()),
+                (&a ^ &b, a_keys.symmetric_difference(&b_keys).collect()),
+            ] {
+                assert_eq!(result.len(), expected.len());
+                assert_eq!(result.as_node(), expected.as_node());
+            }
+
+            let both = &a & &b;
+            assert!(both.is_subset(&a) && both.is_subset(&b));
+            assert!((&a | &b).is_superset(&a));
+        }
+    }
 }
@@ -0,0 +1,546 @@
+//! Versioned, checksummed binary snapshots of maps and sets, enabled by the `std` feature.
+//!
+//! A snapshot stores the nodes of the tree depth first, in the same shape they have in memory,
+//! so loading one allocates each node once and never compares keys. Values are encoded by a
+//! [`ValueCodec`].
+//!
+//! The format is, with all integers little endian:
+//!
+//! - the magic bytes `FRTS` and a format version byte
+//! - the number of entries as a `u64`
+//! - every node depth first: `label_len: u8`, the label bytes, `children_len: u8`, then either
+//!   a `0` byte for no value or a `1` byte followed by a `u32` length and the encoded value
+//! - a CRC-32 of all of the above as a `u32`
+//!
+//! Reads and writes are issued in small pieces, so wrap files in a
+//! [`BufReader`](std::io::BufReader)/[`BufWriter`](std::io::BufWriter).
+//!
+//! # Examples
+//!
+//! ```
+//! use fast_radix_trie::{StringRadixMap, snapshot::LeBytesCodec};
+//!
+//! let map: StringRadixMap<u32> = [("foo", 1), ("foobar", 2)].into_iter().collect();
+//!
+//! let mut buf = Vec::new();
+//! map.write_to(&mut buf, &LeBytesCodec).unwrap();
+//! let back = StringRadixMap::<u32>::read_from(buf.as_slice(), &LeBytesCodec).unwrap();
+//! assert_eq!(back.get("foobar"), Some(&2));
+//! ```
+use crate::{Bytes, GenericRadixMap, GenericRadixSet, Node, node_common::some};
+use alloc::vec::Vec;
+use core::fmt;
+use std::io::{self, Read, Write};
+
+const MAGIC: &[u8; 4] = b"FRTS";
+const VERSION: u8 = 1;
+
+/// Encodes and decodes the values stored in a snapshot.
+///
+/// # Examples
+///
+/// ```
+/// use fast_radix_trie::snapshot::ValueCodec;
+///
+/// struct Utf8;
+/// impl ValueCodec<String> for Utf8 {
+///     fn encode(&self, value: &String, buf: &mut Vec<u8>) {
+///         buf.extend_from_slice(value.as_bytes());
+///     }
+///     fn decode(&self, bytes: &[u8]) -> Option<String> {
+///         String::from_utf8(bytes.to_vec()).ok()
+///     }
+/// }
+/// ```
+pub trait ValueCodec<V> {
+    /// Appends the encoding of `value` to `buf`.
+    fn encode(&self, value: &V, buf: &mut Vec<u8>);
+
+    /// Decodes a value from exactly the bytes written by [`encode`](Self::encode), returning
+    /// `None` if they are invalid.
+    fn decode(&self, bytes: &[u8]) -> Option<V>;
+}
+
+/// Codec for `()`, used by sets.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnitCodec;
+impl ValueCodec<()> for UnitCodec {
+    fn encode(&self, _value: &(), _buf: &mut Vec<u8>) {}
+
+    fn decode(&self, bytes: &[u8]) -> Option<()> {
+        bytes.is_empty().then_some(())
+    }
+}
+
+/// Codec for primitive integers and floats, using their little endian bytes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LeBytesCodec;
+
+macro_rules! le_bytes_codec {
+    ($($ty:ty),*) => {
+        $(
+            impl ValueCodec<$ty> for LeBytesCodec {
+                fn encode(&self, value: &$ty, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(&value.to_le_bytes());
+                }
+
+                fn decode(&self, bytes: &[u8]) -> Option<$ty> {
+                    Some(<$ty>::from_le_bytes(bytes.try_into().ok()?))
+                }
+            }
+        )*
+    };
+}
+le_bytes_codec!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
+);
+
+/// Errors raised while reading or writing a snapshot.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The underlying reader or writer failed, including on a truncated snapshot.
+    Io(io::Error),
+    /// The input does not start with the snapshot magic bytes.
+    InvalidMagic,
+    /// The snapshot was written by an unsupported version of the format.
+    UnsupportedVersion(u8),
+    /// The checksum does not match the contents.
+    ChecksumMismatch,
+    /// The contents do not describe a valid tree for this key type.
+    Corrupt(&'static str),
+    /// The value codec rejected an encoded value.
+    InvalidValue,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Io(e) => write!(f, "snapshot i/o error: {e}"),
+            SnapshotError::InvalidMagic => f.write_str("not a radix trie snapshot"),
+            SnapshotError::UnsupportedVersion(v) => {
+                write!(f, "unsupported snapshot version {v}")
+            }
+            SnapshotError::ChecksumMismatch => f.write_str("snapshot checksum mismatch"),
+            SnapshotError::Corrupt(reason) => write!(f, "corrupt snapshot: {reason}"),
+            SnapshotError::InvalidValue => f.write_str("snapshot value could not be decoded"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(e: io::Error) -> Self {
+        SnapshotError::Io(e)
+    }
+}
+
+impl<K: Bytes, V> GenericRadixMap<K, V> {
+    /// Writes a binary snapshot of this map, see the [`snapshot`](crate::snapshot) module.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{RadixMap, snapshot::LeBytesCodec};
+    ///
+    /// let map: RadixMap<u64> = [("foo", 1), ("bar", 2)].into_iter().collect();
+    /// let mut buf = Vec::new();
+    /// map.write_to(&mut buf, &LeBytesCodec).unwrap();
+    /// assert_eq!(&buf[..4], b"FRTS");
+    /// ```
+    pub fn write_to<W, C>(&self, writer: W, codec: &C) -> Result<(), SnapshotError>
+    where
+        W: Write,
+        C: ValueCodec<V>,
+    {
+        write_snapshot(self.as_node(), self.len(), writer, codec)
+    }
+
+    /// Reads a map from a binary snapshot written by [`write_to`](Self::write_to).
+    ///
+    /// The snapshot is validated: it must carry a correct checksum and describe a well formed
+    /// tree whose keys are valid for `K`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::{RadixMap, snapshot::{LeBytesCodec, SnapshotError}};
+    ///
+    /// let map: RadixMap<u64> = [("foo", 1), ("bar", 2)].into_iter().collect();
+    /// let mut buf = Vec::new();
+    /// map.write_to(&mut buf, &LeBytesCodec).unwrap();
+    ///
+    /// let back = RadixMap::<u64>::read_from(buf.as_slice(), &LeBytesCodec).unwrap();
+    /// assert_eq!(back.get("bar"), Some(&2));
+    ///
+    /// buf[18] ^= 1;
+    /// assert!(matches!(
+    ///     RadixMap::<u64>::read_from(buf.as_slice(), &LeBytesCodec),
+    ///     Err(SnapshotError::ChecksumMismatch)
+    /// ));
+    /// ```
+    pub fn read_from<R, C>(reader: R, codec: &C) -> Result<Self, SnapshotError>
+    where
+        R: Read,
+        C: ValueCodec<V>,
+    {
+        let (root, len) = read_snapshot(reader, codec)?;
+        if root
+            .check_root::<K::Borrowed>()
+            .map_err(SnapshotError::Corrupt)?
+            != len
+        {
+            return Err(SnapshotError::Corrupt(
+                "entry count does not match the nodes",
+            ));
+        }
+        Ok(GenericRadixMap::from_root(root, len))
+    }
+}
+
+impl<T: Bytes> GenericRadixSet<T> {
+    /// Writes a binary snapshot of this set, see the [`snapshot`](crate::snapshot) module.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let set: StringRadixSet = ["foo", "bar"].into_iter().collect();
+    /// let mut buf = Vec::new();
+    /// set.write_to(&mut buf).unwrap();
+    ///
+    /// let back = StringRadixSet::read_from(buf.as_slice()).unwrap();
+    /// assert!(back.contains("foo"));
+    /// ```
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<(), SnapshotError> {
+        write_snapshot(self.as_node(), self.len(), writer, &UnitCodec)
+    }
+
+    /// Reads a set from a binary snapshot written by [`write_to`](Self::write_to).
+    pub fn read_from<R: Read>(reader: R) -> Result<Self, SnapshotError> {
+        let (root, len) = read_snapshot(reader, &UnitCodec)?;
+        if root
+            .check_root::<T::Borrowed>()
+            .map_err(SnapshotError::Corrupt)?
+            != len
+        {
+            return Err(SnapshotError::Corrupt(
+                "entry count does not match the nodes",
+            ));
+        }
+        Ok(GenericRadixSet::from_root(root, len))
+    }
+}
+
+fn write_snapshot<V, W, C>(
+    root: &Node<V>,
+    len: usize,
+    writer: W,
+    codec: &C,
+) -> Result<(), SnapshotError>
+where
+    W: Write,
+    C: ValueCodec<V>,
+{
+    let mut writer = CrcWriter::new(writer);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[VERSION])?;
+    writer.write_all(&(len as u64).to_le_bytes())?;
+
+    let mut buf = Vec::new();
+    for (_, node) in root.iter() {
+        writer.write_all(&[node.label_len() as u8])?;
+        writer.write_all(node.label())?;
+        writer.write_all(&[node.children_len() as u8])?;
+        match node.value() {
+            None => writer.write_all(&[0])?,
+            Some(value) => {
+                buf.clear();
+                codec.encode(value, &mut buf);
+                let value_len = u32::try_from(buf.len())
+                    .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "value too large"))?;
+                writer.write_all(&[1])?;
+                writer.write_all(&value_len.to_le_bytes())?;
+                writer.write_all(&buf)?;
+            }
+        }
+    }
+
+    let crc = writer.crc.finish();
+    writer.inner.write_all(&crc.to_le_bytes())?;
+    writer.inner.flush()?;
+    Ok(())
+}
+
+/// A node whose children are still being read.
+struct Pending<V> {
+    label: Vec<u8>,
+    value: Option<V>,
+    children: Vec<Node<V>>,
+    remaining: usize,
+}
+
+fn read_snapshot<V, R, C>(reader: R, codec: &C) -> Result<(Node<V>, usize), SnapshotError>
+where
+    R: Read,
+    C: ValueCodec<V>,
+{
+    let mut reader = CrcReader::new(reader);
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(SnapshotError::InvalidMagic);
+    }
+    let version = reader.read_u8()?;
+    if version != VERSION {
+        return Err(SnapshotError::UnsupportedVersion(version));
+    }
+    let len = u64::from_le_bytes(reader.read_array()?);
+    let len = usize::try_from(len).map_err(|_| SnapshotError::Corrupt("entry count overflow"))?;
+
+    let mut stack: Vec<Pending<V>> = Vec::new();
+    let mut buf = Vec::new();
+    let root = 'nodes: loop {
+        let mut label = vec![0; reader.read_u8()? as usize];
+        reader.read_exact(&mut label)?;
+        let children_len = reader.read_u8()? as usize;
+        let value = match reader.read_u8()? {
+            0 => None,
+            1 => {
+                let value_len = u32::from_le_bytes(reader.read_array()?) as u64;
+                buf.clear();
+                // read through `take` so a corrupt length can't force a huge allocation
+                if (&mut reader).take(value_len).read_to_end(&mut buf)? as u64 != value_len {
+                    return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+                }
+                Some(codec.decode(&buf).ok_or(SnapshotError::InvalidValue)?)
+            }
+            _ => return Err(SnapshotError::Corrupt("invalid value tag")),
+        };
+
+        if children_len > 0 {
+            stack.push(Pending {
+                label,
+                value,
+                children: Vec::with_capacity(children_len),
+                remaining: children_len,
+            });
+            continue;
+        }
+
+        // a leaf completes its parent, which may complete its own parent and so on
+        let mut node =
+            Node::try_from_parts(&label, value, Vec::new()).map_err(SnapshotError::Corrupt)?;
+        loop {
+            let Some(parent) = stack.last_mut() else {
+                break 'nodes node;
+            };
+            parent.children.push(node);
+            parent.remaining -= 1;
+            if parent.remaining > 0 {
+                break;
+            }
+            let parent = some!(stack.pop());
+            node = Node::try_from_parts(&parent.label, parent.value, parent.children)
+                .map_err(SnapshotError::Corrupt)?;
+        }
+    };
+
+    let crc = reader.crc.finish();
+    let mut expected = [0; 4];
+    reader.inner.read_exact(&mut expected)?;
+    if u32::from_le_bytes(expected) != crc {
+        return Err(SnapshotError::ChecksumMismatch);
+    }
+    Ok((root, len))
+}
+
+/// CRC-32 (IEEE), as used by zlib and PNG.
+struct Crc32(u32);
+impl Crc32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 == 1 {
+                    0xedb8_8320 ^ (crc >> 1)
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    fn new() -> Self {
+        Crc32(!0)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = Self::TABLE[((self.0 ^ b as u32) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+struct CrcWriter<W> {
+    inner: W,
+    crc: Crc32,
+}
+impl<W: Write> CrcWriter<W> {
+    fn new(inner: W) -> Self {
+        CrcWriter {
+            inner,
+            crc: Crc32::new(),
+        }
+    }
+}
+impl<W: Write> Write for CrcWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.crc.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+struct CrcReader<R> {
+    inner: R,
+    crc: Crc32,
+}
+impl<R: Read> CrcReader<R> {
+    fn new(inner: R) -> Self {
+        CrcReader {
+            inner,
+            crc: Crc32::new(),
+        }
+    }
+
+    fn read_u8(&mut self) -> io::Result<u8> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    fn read_array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut bytes = [0; N];
+        self.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+impl<R: Read> Read for CrcReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.crc.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RadixMap, RadixSet, StringRadixMap};
+    use alloc::vec::Vec;
+
+    fn map() -> StringRadixMap<u64> {
+        let keys = crate::test::test_keys(if cfg!(miri) { 20 } else { 1000 }, 5);
+        let mut map: StringRadixMap<u64> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, k)| (k, i as u64))
+            .collect();
+        // leave valueless nodes behind
+        for k in keys.iter().step_by(3) {
+            map.remove(k);
+        }
+        map
+    }
+
+    #[test]
+    fn roundtrip_keeps_shape() {
+        let map = map();
+        let mut buf = Vec::new();
+        map.write_to(&mut buf, &LeBytesCodec).unwrap();
+        let back = StringRadixMap::<u64>::read_from(buf.as_slice(), &LeBytesCodec).unwrap();
+        assert_eq!(back.len(), map.len());
+        assert!(back.iter().eq(map.iter()));
+        assert_eq!(
+            format!("{:?}", back.as_node()),
+            format!("{:?}", map.as_node())
+        );
+
+        let empty = RadixMap::<u64>::new();
+        buf.clear();
+        empty.write_to(&mut buf, &LeBytesCodec).unwrap();
+        let back = RadixMap::<u64>::read_from(buf.as_slice(), &LeBytesCodec).unwrap();
+        assert!(back.is_empty());
+
+        let set: RadixSet = map.keys().collect();
+        buf.clear();
+        set.write_to(&mut buf).unwrap();
+        let back = RadixSet::read_from(buf.as_slice()).unwrap();
+        assert!(back.iter().eq(set.iter()));
+    }
+
+    #[test]
+    fn rejects_damaged_snapshots() {
+        let map = map();
+        let mut buf = Vec::new();
+        map.write_to(&mut buf, &LeBytesCodec).unwrap();
+        let read = |bytes: &[u8]| StringRadixMap::<u64>::read_from(bytes, &LeBytesCodec);
+
+        let mut bad = buf.clone();
+        bad[0] = b'X';
+        assert!(matches!(read(&bad), Err(SnapshotError::InvalidMagic)));
+
+        let mut bad = buf.clone();
+        bad[4] = 2;
+        assert!(matches!(
+            read(&bad),
+            Err(SnapshotError::UnsupportedVersion(2))
+        ));
+
+        let mut bad = buf.clone();
+        let last = bad.len() - 5;
+        bad[last] ^= 0x80;
+        assert!(matches!(read(&bad), Err(SnapshotError::ChecksumMismatch)));
+
+        for len in [3, 13, buf.len() / 2, buf.len() - 1] {
+            match read(&buf[..len]) {
+                Err(SnapshotError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+                other => panic!("{other:?}"),
+            }
+        }
+
+        // a well formed snapshot whose keys are not valid UTF-8
+        let bytes: RadixMap<u64> = [(&b"\xff"[..], 1)].into_iter().collect();
+        buf.clear();
+        bytes.write_to(&mut buf, &LeBytesCodec).unwrap();
+        assert!(matches!(read(&buf), Err(SnapshotError::Corrupt(_))));
+
+        // values that the codec rejects
+        assert!(matches!(
+            RadixMap::<u32>::read_from(buf.as_slice(), &LeBytesCodec),
+            Err(SnapshotError::InvalidValue)
+        ));
+    }
+}
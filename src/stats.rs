@@ -0,0 +1,153 @@
+//! Memory usage reporting for maps and sets.
+//!
+//! Every node of a tree is a single heap allocation holding its header, label, children and
+//! value, so [`GenericRadixMap::memory_stats`] can total the exact number of bytes the tree asks
+//! the allocator for, and how many of them are lost to alignment padding. Heap memory owned by
+//! the values themselves (e.g. the buffer of a `String` value) is not included.
+//!
+//! # Examples
+//!
+//! ```
+//! use fast_radix_trie::StringRadixMap;
+//!
+//! let map: StringRadixMap<u32> = [("apple", 1), ("apply", 2), ("box", 3)].into_iter().collect();
+//! let stats = map.memory_stats();
+//!
+//! // "" -> "appl" -> {"e", "y"}, and "" -> "box"
+//! assert_eq!(stats.node_count, 5);
+//! assert_eq!(stats.valued_nodes, 3);
+//! assert_eq!(stats.label_bytes, 9);
+//! assert_eq!(stats.depth_histogram, [1, 2, 2]);
+//! assert_eq!(stats.fanout_histogram, [3, 0, 2]);
+//! assert!(stats.padding_bytes < stats.heap_bytes);
+//! ```
+use crate::{GenericRadixMap, GenericRadixSet, Node};
+use alloc::vec::Vec;
+
+/// Memory usage of a tree, as returned by [`GenericRadixMap::memory_stats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Total bytes allocated for the nodes, including padding.
+    pub heap_bytes: usize,
+    /// Number of nodes, including the root.
+    pub node_count: usize,
+    /// Number of nodes holding a value.
+    pub valued_nodes: usize,
+    /// Number of nodes without a value (branches, chained labels and an empty root).
+    pub valueless_nodes: usize,
+    /// Total length of all labels.
+    pub label_bytes: usize,
+    /// Bytes of the node allocations that hold neither header, label, children nor value.
+    pub padding_bytes: usize,
+    /// `depth_histogram[d]` is the number of nodes `d` edges below the root.
+    pub depth_histogram: Vec<usize>,
+    /// `fanout_histogram[n]` is the number of nodes with `n` children.
+    pub fanout_histogram: Vec<usize>,
+}
+
+impl MemoryStats {
+    fn collect<V>(root: &Node<V>) -> Self {
+        let mut stats = MemoryStats::default();
+        let mut stack = vec![(root, 0)];
+        while let Some((node, depth)) = stack.pop() {
+            let (size, padding) = node.allocation_size();
+            stats.heap_bytes += size;
+            stats.padding_bytes += padding;
+            stats.node_count += 1;
+            if node.value().is_some() {
+                stats.valued_nodes += 1;
+            } else {
+                stats.valueless_nodes += 1;
+            }
+            stats.label_bytes += node.label_len();
+            bump(&mut stats.depth_histogram, depth);
+            bump(&mut stats.fanout_histogram, node.children_len());
+            stack.extend(node.children().iter().map(|child| (child, depth + 1)));
+        }
+        stats
+    }
+}
+
+fn bump(histogram: &mut Vec<usize>, i: usize) {
+    if histogram.len() <= i {
+        histogram.resize(i + 1, 0);
+    }
+    histogram[i] += 1;
+}
+
+impl<K, V> GenericRadixMap<K, V> {
+    /// Reports how much heap memory the nodes of this map use and how they are shaped.
+    ///
+    /// This walks every node, so it's `O(n)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::RadixMap;
+    ///
+    /// let map: RadixMap<u8> = (0..200u8).map(|i| ([i], i)).collect();
+    /// let stats = map.memory_stats();
+    /// assert_eq!(stats.valued_nodes, 200);
+    /// assert_eq!(stats.fanout_histogram[0], 200);
+    /// ```
+    pub fn memory_stats(&self) -> MemoryStats {
+        MemoryStats::collect(self.as_node())
+    }
+}
+
+impl<T> GenericRadixSet<T> {
+    /// Reports how much heap memory the nodes of this set use and how they are shaped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fast_radix_trie::StringRadixSet;
+    ///
+    /// let set: StringRadixSet = ["foo", "foobar"].into_iter().collect();
+    /// assert_eq!(set.memory_stats().node_count, 3);
+    /// ```
+    pub fn memory_stats(&self) -> MemoryStats {
+        MemoryStats::collect(self.as_node())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::StringRadixMap;
+
+    #[test]
+    fn stats_add_up() {
+        let mut map = StringRadixMap::new();
+        for i in 0..if cfg!(miri) { 50 } else { 2000 } {
+            map.insert(format!("key/{}", i * 7), i);
+        }
+        map.insert("x".repeat(600), 0);
+
+        let stats = map.memory_stats();
+        assert_eq!(stats.node_count, stats.valued_nodes + stats.valueless_nodes);
+        assert_eq!(stats.valued_nodes, map.len());
+        assert_eq!(
+            stats.depth_histogram.iter().sum::<usize>(),
+            stats.node_count
+        );
+        assert_eq!(
+            stats.fanout_histogram.iter().sum::<usize>(),
+            stats.node_count
+        );
+        assert_eq!(
+            stats.label_bytes,
+            map.as_node()
+                .iter()
+                .map(|(_, n)| n.label_len())
+                .sum::<usize>()
+        );
+        // every node holds at least its header and label
+        assert!(stats.heap_bytes >= stats.padding_bytes + stats.node_count * 2 + stats.label_bytes);
+
+        let empty = StringRadixMap::<u32>::new().memory_stats();
+        assert_eq!(empty.node_count, 1);
+        assert_eq!(empty.valueless_nodes, 1);
+        assert_eq!(empty.depth_histogram, [1]);
+        assert_eq!(empty.fanout_histogram, [1]);
+    }
+}
@@ -1,11 +1,11 @@
-use core::fmt;
+use core::{cmp::Ordering, fmt, mem, ops::Bound};
 
 use alloc::vec::Vec;
 
 use crate::{
     BorrowedBytes, Bytes,
     node::Node,
-    node_common::{self, NodeMut},
+    node_common::{self, MAX_LABEL_LEN, NodeEntry, NodeMut, some},
 };
 
 #[derive(Clone)]
@@ -30,9 +30,18 @@ impl<V> RadixTrie<V> {
             len: 0,
         }
     }
+    /// `len` must be the number of values below `root`
+    #[cfg(any(feature = "std", feature = "serde"))]
+    pub(crate) fn from_root(root: Node<V>, len: usize) -> Self {
+        RadixTrie { root, len }
+    }
     pub(crate) fn root(&self) -> &Node<V> {
         &self.root
     }
+    #[cfg(feature = "rayon")]
+    pub(crate) fn root_mut(&mut self) -> &mut Node<V> {
+        &mut self.root
+    }
     pub(crate) fn into_root(self) -> Node<V> {
         self.root
     }
@@ -50,7 +59,16 @@ impl<V> RadixTrie<V> {
     pub fn get_mut<K: ?Sized + BorrowedBytes>(&mut self, key: &K) -> Option<&mut V> {
         self.root.get_mut(key)
     }
+    pub(crate) fn entry<K: ?Sized + BorrowedBytes>(
+        &mut self,
+        key: &K,
+    ) -> (NodeEntry<'_, V>, &mut usize) {
+        (self.root.entry_mut(key.as_bytes()), &mut self.len)
+    }
     pub fn split_by_prefix<K: ?Sized + BorrowedBytes>(&mut self, key: &K) -> Self {
+        if key.as_bytes().is_empty() {
+            return mem::take(self);
+        }
         match self.root.split_by_prefix(key) {
             Some(node) => {
                 let new_root = Node::new(b"", [node], None);
@@ -61,6 +79,74 @@ impl<V> RadixTrie<V> {
             None => Self::new(),
         }
     }
+    /// Removes every entry whose key starts with `prefix`, returning how many were removed.
+    pub(crate) fn remove_prefix(&mut self, prefix: &[u8]) -> usize {
+        if prefix.is_empty() {
+            let removed = self.len;
+            self.clear();
+            return removed;
+        }
+        match self.root.detach_prefix(prefix) {
+            Some((node, _)) => {
+                let removed = node.iter().filter(|(_, n)| n.value().is_some()).count();
+                self.len -= removed;
+                removed
+            }
+            None => 0,
+        }
+    }
+    /// Removes every entry for which `pred` returns `true`, passing it to `out` in key order.
+    pub(crate) fn extract_if<P, O>(&mut self, mut pred: P, mut out: O)
+    where
+        P: FnMut(&[u8], &mut V) -> bool,
+        O: FnMut(&[u8], V),
+    {
+        self.len -= self.root.extract_if(&mut Vec::new(), &mut pred, &mut out);
+    }
+    /// Moves every entry of `other` into `self`, combining values found in both with `f`.
+    pub(crate) fn merge<F>(&mut self, other: Self, mut f: F)
+    where
+        F: FnMut(&[u8], V, V) -> V,
+    {
+        let len = self.len + other.len;
+        let both = self.root.merge(other.root, &mut Vec::new(), &mut f);
+        self.len = len - both;
+    }
+    /// Merges `other` into `self` with `prefix` prepended to all of its keys. Values of `other`
+    /// replace existing ones.
+    pub(crate) fn insert_subtree(&mut self, prefix: &[u8], other: Self) {
+        if other.len == 0 {
+            return;
+        }
+        let len = other.len;
+        self.merge(
+            RadixTrie {
+                root: other.root.with_prefix(prefix),
+                len,
+            },
+            |_, _, b| b,
+        );
+    }
+    /// Moves every key starting with `from` to start with `to` instead, returning how many
+    /// entries were moved.
+    pub(crate) fn rename_prefix(&mut self, from: &[u8], to: &[u8]) -> usize {
+        let subtree = if from.is_empty() {
+            mem::take(self)
+        } else {
+            match self.root.detach_prefix(from) {
+                Some((node, consumed)) => {
+                    let node = node.strip_label(from.len() - consumed);
+                    let len = node.iter().filter(|(_, n)| n.value().is_some()).count();
+                    self.len -= len;
+                    RadixTrie { root: node, len }
+                }
+                None => return 0,
+            }
+        };
+        let moved = subtree.len;
+        self.insert_subtree(to, subtree);
+        moved
+    }
     pub fn longest_common_prefix_len<K: ?Sized + BorrowedBytes>(&self, key: &K) -> usize {
         self.root.longest_common_prefix_len(key)
     }
@@ -85,10 +171,7 @@ impl<V> RadixTrie<V> {
         prefix: &K,
     ) -> Option<(usize, Nodes<'_, V>)> {
         if let Some((common_len, node)) = self.root.get_prefix_node(prefix) {
-            let nodes = Nodes {
-                nodes: node.iter(),
-                label_lens: Vec::new(),
-            };
+            let nodes = Nodes { nodes: node.iter() };
             Some((prefix.as_bytes().len() - common_len, nodes))
         } else {
             None
@@ -101,13 +184,66 @@ impl<V> RadixTrie<V> {
         if let Some((common_len, node)) = self.root.get_prefix_node_mut(prefix) {
             let nodes = NodesMut {
                 nodes: node.iter_mut(),
-                label_lens: Vec::new(),
             };
             Some((prefix.as_bytes().len() - common_len, nodes))
         } else {
             None
         }
     }
+    pub(crate) fn first(&self) -> Option<(Vec<u8>, &V)> {
+        let mut key = Vec::new();
+        let value = self.root.first_value(&mut key)?;
+        Some((key, value))
+    }
+    pub(crate) fn last(&self) -> Option<(Vec<u8>, &V)> {
+        let mut key = Vec::new();
+        let value = self.root.last_value(&mut key)?;
+        Some((key, value))
+    }
+    pub(crate) fn floor<K: ?Sized + BorrowedBytes>(
+        &self,
+        key: &K,
+        inclusive: bool,
+    ) -> Option<(Vec<u8>, &V)> {
+        let mut found = Vec::new();
+        let value = self
+            .root
+            .floor_value(key.as_bytes(), inclusive, &mut found)?;
+        Some((found, value))
+    }
+    pub(crate) fn ceiling<K: ?Sized + BorrowedBytes>(
+        &self,
+        key: &K,
+        inclusive: bool,
+    ) -> Option<(Vec<u8>, &V)> {
+        let mut found = Vec::new();
+        let value = self
+            .root
+            .ceiling_value(key.as_bytes(), inclusive, &mut found)?;
+        Some((found, value))
+    }
+    /// Returns an iterator over the nodes whose keys may lie within `start` and `end`, along
+    /// with the initial front key buffer.
+    ///
+    /// Subtrees entirely outside of the bounds are never visited, the nodes on the path to each
+    /// bound still need to be checked by the caller.
+    pub(crate) fn range_nodes(
+        &self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> (Nodes<'_, V>, Vec<u8>) {
+        let (nodes, front_key) = node_common::Iter::range(&self.root, start, end);
+        (Nodes { nodes }, front_key)
+    }
+    /// Mutable version of [`range_nodes`](Self::range_nodes).
+    pub(crate) fn range_nodes_mut(
+        &mut self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> (NodesMut<'_, V>, Vec<u8>) {
+        let (nodes, front_key) = node_common::IterMut::range(&mut self.root, start, end);
+        (NodesMut { nodes }, front_key)
+    }
     pub(crate) fn common_prefixes<'a, 'b, K>(
         &'a self,
         key: &'b K,
@@ -144,13 +280,11 @@ impl<V> RadixTrie<V> {
     pub fn nodes(&self) -> Nodes<'_, V> {
         Nodes {
             nodes: self.root.iter(),
-            label_lens: Vec::new(),
         }
     }
     pub fn nodes_mut(&mut self) -> NodesMut<'_, V> {
         NodesMut {
             nodes: self.root.iter_mut(),
-            label_lens: Vec::new(),
         }
     }
     pub fn into_nodes(self) -> IntoNodes<V> {
@@ -165,6 +299,122 @@ impl<V> Default for RadixTrie<V> {
         Self::new()
     }
 }
+
+/// Why a key was rejected by [`SortedBuilder::push`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SortedPushError {
+    Unsorted,
+    Duplicate,
+}
+
+/// Builds a tree bottom up from keys in ascending order, allocating each node once with all of
+/// its children.
+///
+/// Keeps one frame per node on the path to the last key. A frame is only turned into a node once
+/// a key that doesn't start with its key arrives, at which point all of its children are known.
+pub(crate) struct SortedBuilder<V> {
+    frames: Vec<SortedFrame<V>>,
+    last: Vec<u8>,
+    len: usize,
+}
+
+struct SortedFrame<V> {
+    /// length of the key up to the end of this node
+    depth: usize,
+    value: Option<V>,
+    children: Vec<Node<V>>,
+}
+
+impl<V> SortedBuilder<V> {
+    pub(crate) fn new() -> Self {
+        SortedBuilder {
+            frames: vec![SortedFrame {
+                depth: 0,
+                value: None,
+                children: Vec::new(),
+            }],
+            last: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Adds the next key, which must be greater than the previous one. Equal keys replace the
+    /// previous value when `dedup` is set.
+    pub(crate) fn push(
+        &mut self,
+        key: &[u8],
+        value: V,
+        dedup: bool,
+    ) -> Result<(), SortedPushError> {
+        let common = if self.len == 0 {
+            0
+        } else {
+            match crate::longest_common_prefix(&self.last, key) {
+                (n, Some(Ordering::Less)) => n,
+                (_, Some(_)) => return Err(SortedPushError::Unsorted),
+                (n, None) if n < key.len() => n,
+                (n, None) if n < self.last.len() => return Err(SortedPushError::Unsorted),
+                (_, None) if !dedup => return Err(SortedPushError::Duplicate),
+                (_, None) => {
+                    some!(self.frames.last_mut()).value = Some(value);
+                    return Ok(());
+                }
+            }
+        };
+        self.close(common);
+        let top = some!(self.frames.last_mut());
+        if top.depth == key.len() {
+            // only the empty key lands on the root
+            top.value = Some(value);
+        } else {
+            self.frames.push(SortedFrame {
+                depth: key.len(),
+                value: Some(value),
+                children: Vec::new(),
+            });
+        }
+        self.last.clear();
+        self.last.extend_from_slice(key);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// turns every frame below `depth` into a node, adding a branch at `depth` if there's none
+    fn close(&mut self, depth: usize) {
+        while some!(self.frames.last()).depth > depth {
+            let frame = some!(self.frames.pop());
+            let parent = some!(self.frames.last()).depth;
+            if parent < depth {
+                self.frames.push(SortedFrame {
+                    depth,
+                    value: None,
+                    children: Vec::new(),
+                });
+            }
+            let label = &self.last[parent.max(depth)..frame.depth];
+            let node = frame.into_node(label);
+            some!(self.frames.last_mut()).children.push(node);
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> RadixTrie<V> {
+        self.close(0);
+        let root = some!(self.frames.pop());
+        RadixTrie {
+            root: Node::with_children(b"", root.children, root.value),
+            len: self.len,
+        }
+    }
+}
+
+impl<V> SortedFrame<V> {
+    fn into_node(self, label: &[u8]) -> Node<V> {
+        // labels over the max are chained through valueless nodes
+        let (front, tail) = label.split_at(label.len().saturating_sub(MAX_LABEL_LEN));
+        Node::with_children(tail, self.children, self.value).with_prefix(front)
+    }
+}
+
 impl<V> From<Node<V>> for RadixTrie<V> {
     fn from(f: Node<V>) -> Self {
         let mut this = RadixTrie { root: f, len: 0 };
@@ -174,42 +424,73 @@ impl<V> From<Node<V>> for RadixTrie<V> {
     }
 }
 
+/// Nodes of a tree in depth first order, along with the key length of each node's parent.
 #[derive(Debug)]
 pub struct Nodes<'a, V: 'a> {
     nodes: node_common::Iter<'a, V>,
-    label_lens: Vec<usize>,
+}
+impl<'a, V> Nodes<'a, V> {
+    /// nodes of the subtree rooted at `node`, starting with `node` itself
+    #[cfg(feature = "rayon")]
+    pub(crate) fn subtree(node: &'a Node<V>) -> Self {
+        Nodes { nodes: node.iter() }
+    }
+}
+impl<V> Nodes<'_, V> {
+    /// stop iterating on both ends
+    pub(crate) fn clear(&mut self) {
+        self.nodes.clear();
+    }
+    /// key of the node last yielded by `next_back`
+    pub(crate) fn back_key(&self) -> &[u8] {
+        self.nodes.back_key()
+    }
 }
 impl<'a, V: 'a> Iterator for Nodes<'a, V> {
     type Item = (usize, &'a Node<V>);
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some((level, node)) = self.nodes.next() {
-            self.label_lens.resize(level + 1, 0);
-            self.label_lens[level] = node.label().len();
-            let parent_key_len = self.label_lens.iter().take(level).sum();
-            Some((parent_key_len, node))
-        } else {
-            None
-        }
+        self.nodes.next_keyed()
+    }
+}
+impl<'a, V: 'a> DoubleEndedIterator for Nodes<'a, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.nodes.next_back_keyed()
     }
 }
 
+/// Mutable nodes of a tree in depth first order, along with the key length of each node's parent.
 #[derive(Debug)]
 pub struct NodesMut<'a, V: 'a> {
     nodes: node_common::IterMut<'a, V>,
-    label_lens: Vec<usize>,
+}
+impl<'a, V> NodesMut<'a, V> {
+    /// mutable nodes of the subtree rooted at `node`, starting with `node` itself
+    #[cfg(feature = "rayon")]
+    pub(crate) fn subtree(node: &'a mut Node<V>) -> Self {
+        NodesMut {
+            nodes: node.iter_mut(),
+        }
+    }
+}
+impl<V> NodesMut<'_, V> {
+    /// stop iterating on both ends
+    pub(crate) fn clear(&mut self) {
+        self.nodes.clear();
+    }
+    /// key of the node last yielded by `next_back`
+    pub(crate) fn back_key(&self) -> &[u8] {
+        self.nodes.back_key()
+    }
 }
 impl<'a, V: 'a> Iterator for NodesMut<'a, V> {
     type Item = (usize, NodeMut<'a, V>);
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some((level, node)) = self.nodes.next() {
-            self.label_lens.resize(level + 1, 0);
-            self.label_lens[level] = node.label().len();
-
-            let parent_key_len = self.label_lens.iter().take(level).sum();
-            Some((parent_key_len, node))
-        } else {
-            None
-        }
+        self.nodes.next_keyed()
+    }
+}
+impl<'a, V: 'a> DoubleEndedIterator for NodesMut<'a, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.nodes.next_back_keyed()
     }
 }
 
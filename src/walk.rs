@@ -0,0 +1,112 @@
+//! Depth first search of a tree for the keys an automaton accepts, feeding it the keys one
+//! symbol at a time.
+//!
+//! The automaton's state after each symbol of the current key is kept as a row, shared by all
+//! of the keys below it, so each label is only fed once, and a subtree is skipped as soon as a
+//! row rules out every key below.
+
+use alloc::vec::Vec;
+
+use crate::node::Node;
+
+/// An automaton whose state is a row of `width` cells, advanced one symbol at a time.
+pub(crate) trait RowAutomaton {
+    type Cell;
+    /// What an accepted key is reported with.
+    type Match;
+
+    /// Returns the number of cells in a row.
+    fn width(&self) -> usize;
+
+    /// Pushes the row for the empty key.
+    fn start(&self, rows: &mut Vec<Self::Cell>);
+
+    /// Pushes the row reached from the last one by `symbol`, `previous` being the symbol before
+    /// it, returning `false` if neither the key so far nor any longer one can be accepted.
+    fn step(&self, rows: &mut Vec<Self::Cell>, symbol: &[u8], previous: Option<&[u8]>) -> bool;
+
+    /// Returns the match for a key ending on `row`, if it's accepted.
+    fn accept(&self, row: &[Self::Cell]) -> Option<Self::Match>;
+}
+
+/// Depth first search for the entries an automaton accepts, in key order.
+#[derive(Debug)]
+pub(crate) struct Walk<'a, V, A: RowAutomaton> {
+    automaton: A,
+    symbol_len: fn(&[u8]) -> Option<usize>,
+    /// nodes still to visit, with the key length and symbol count of their parent
+    stack: Vec<(usize, usize, &'a Node<V>)>,
+    key: Vec<u8>,
+    /// end offset of each complete symbol of the key
+    key_ends: Vec<usize>,
+    /// one row per complete symbol of the key plus the first
+    rows: Vec<A::Cell>,
+}
+
+impl<'a, V, A: RowAutomaton> Walk<'a, V, A> {
+    pub(crate) fn new(
+        root: &'a Node<V>,
+        automaton: A,
+        symbol_len: fn(&[u8]) -> Option<usize>,
+    ) -> Self {
+        let mut rows = Vec::new();
+        automaton.start(&mut rows);
+        Walk {
+            automaton,
+            symbol_len,
+            stack: vec![(0, 0, root)],
+            key: Vec::new(),
+            key_ends: Vec::new(),
+            rows,
+        }
+    }
+
+    /// Adds a row for each complete symbol at the end of the key, returning `false` once a row
+    /// rules out every key below.
+    fn advance(&mut self) -> bool {
+        let mut start = self.key_ends.last().copied().unwrap_or(0);
+        while let Some(len) = (self.symbol_len)(&self.key[start..]) {
+            let previous = match self.key_ends[..] {
+                [.., before, last] => Some(&self.key[before..last]),
+                [last] => Some(&self.key[..last]),
+                [] => None,
+            };
+            let symbol = &self.key[start..start + len];
+            if !self.automaton.step(&mut self.rows, symbol, previous) {
+                return false;
+            }
+            start += len;
+            self.key_ends.push(start);
+        }
+        true
+    }
+
+    /// Returns the next accepted key, with its value and match.
+    pub(crate) fn next(&mut self) -> Option<(&[u8], &'a V, A::Match)> {
+        let width = self.automaton.width();
+        while let Some((key_len, symbols, node)) = self.stack.pop() {
+            self.key.truncate(key_len);
+            self.key_ends.truncate(symbols);
+            self.rows.truncate((symbols + 1) * width);
+            self.key.extend_from_slice(node.label());
+            if !self.advance() {
+                continue;
+            }
+            let (key_len, symbols) = (self.key.len(), self.key_ends.len());
+            self.stack.extend(
+                node.children()
+                    .iter()
+                    .rev()
+                    .map(|child| (key_len, symbols, child)),
+            );
+            // values only sit where a key ends, so every symbol of the key is complete
+            if let Some(value) = node.value() {
+                let row = &self.rows[self.rows.len() - width..];
+                if let Some(found) = self.automaton.accept(row) {
+                    return Some((&self.key, value, found));
+                }
+            }
+        }
+        None
+    }
+}